@@ -1,8 +1,126 @@
+use std::cell::Cell;
+use std::cmp::Ordering;
+#[cfg(test)]
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+#[cfg(test)]
+use rand::rngs::StdRng;
+#[cfg(test)]
+use rand::SeedableRng;
 use rand::Rng;
 
+#[cfg(test)]
+use crate::structures::types::Map;
+
 pub fn crate_random_vector() -> Vec<i32> {
     let mut rng = rand::thread_rng();
     (0..20)
         .map(|_| rng.gen_range(0..100))
         .collect()
-}
\ No newline at end of file
+}
+
+thread_local! {
+    static COMPARISONS: Cell<usize> = const { Cell::new(0) };
+}
+
+// Wraps a value and counts every comparison made on it in the current thread,
+// used to check the amortised bounds of the structures.
+#[derive(Debug, Copy, Clone)]
+pub struct Counted<T>(pub T);
+
+pub fn comparisons() -> usize {
+    COMPARISONS.with(|count| count.get())
+}
+
+pub fn reset_comparisons() {
+    COMPARISONS.with(|count| count.set(0));
+}
+
+impl<T> PartialEq for Counted<T> where T: Ord {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Counted<T> where T: Ord {}
+
+impl<T> PartialOrd for Counted<T> where T: Ord {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Counted<T> where T: Ord {
+    fn cmp(&self, other: &Self) -> Ordering {
+        COMPARISONS.with(|count| count.set(count.get() + 1));
+        self.0.cmp(&other.0)
+    }
+}
+
+static TEMP_PATHS: AtomicUsize = AtomicUsize::new(0);
+
+// A file path in the temporary directory that no other test uses, the file is removed
+// when the path is dropped.
+pub struct TempPath(PathBuf);
+
+impl TempPath {
+    pub fn new(name: &str) -> TempPath {
+        let id = TEMP_PATHS.fetch_add(1, AtomicOrdering::Relaxed);
+        let path = env::temp_dir().join(format!("computer-science-{}-{}-{}", process::id(), id, name));
+        let _ = fs::remove_file(&path);
+        TempPath(path)
+    }
+}
+
+impl Deref for TempPath {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+// Runs the same random puts, updates and removals on `map` and on a `BTreeMap`, comparing
+// the sizes and the changed entry after every step. `check` then gets the step and its key
+// for the invariants and queries of the map under test. Returns the reference map for a
+// final comparison of the entries.
+#[cfg(test)]
+pub fn compare_with_btree_map<M>(map: &mut M, seed: u64, mut check: impl FnMut(&mut M, &BTreeMap<i32, i32>, usize, i32)) -> BTreeMap<i32, i32> where M: Map<i32, i32> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut expected = BTreeMap::new();
+
+    for step in 0..5000 {
+        let key = rng.gen_range(0..500);
+        match rng.gen_range(0..10) {
+            0..=5 => {
+                map.put(key, key * 2);
+                expected.insert(key, key * 2);
+            }
+            6 => {
+                if let Some(value) = map.get_mut(&key) {
+                    *value += 1;
+                }
+                if let Some(value) = expected.get_mut(&key) {
+                    *value += 1;
+                }
+            }
+            _ => assert_eq!(expected.remove(&key), map.remove(&key)),
+        }
+        assert_eq!(expected.len(), map.size());
+        assert_eq!(expected.get(&key), map.get(&key));
+        check(map, &expected, step, key);
+    }
+    expected
+}
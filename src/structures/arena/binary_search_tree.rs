@@ -0,0 +1,269 @@
+use std::cmp::Ordering;
+use std::mem;
+
+use crate::structures::arena::generational::{Arena, Index};
+use crate::structures::types::*;
+
+#[derive(Debug, Clone)]
+struct Node<K, V> {
+    key: K,
+    value: V,
+    parent: Option<Index>,
+    left: Option<Index>,
+    right: Option<Index>,
+}
+
+// Unbalanced binary search tree whose nodes live in an arena. Parent links are plain
+// indices, so in-order iteration walks the tree without an explicit stack.
+#[derive(Debug, Clone)]
+pub struct ArenaBinarySearchTree<K, V> {
+    arena: Arena<Node<K, V>>,
+    root: Option<Index>,
+}
+
+pub struct Iter<'a, K, V> {
+    tree: &'a ArenaBinarySearchTree<K, V>,
+    next: Option<Index>,
+    size: usize,
+}
+
+impl<K, V> ArenaBinarySearchTree<K, V> where K: Ord {
+    pub fn new() -> ArenaBinarySearchTree<K, V> {
+        ArenaBinarySearchTree {
+            arena: Arena::new(),
+            root: None,
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            tree: self,
+            next: self.root.map(|root| self.leftmost(root)),
+            size: self.arena.size(),
+        }
+    }
+
+    fn node(&self, index: Index) -> &Node<K, V> {
+        self.arena.get(index).unwrap()
+    }
+
+    fn node_mut(&mut self, index: Index) -> &mut Node<K, V> {
+        self.arena.get_mut(index).unwrap()
+    }
+
+    fn find(&self, key: &K) -> Option<Index> {
+        let mut current = self.root;
+        while let Some(index) = current {
+            let node = self.node(index);
+            current = match key.cmp(&node.key) {
+                Ordering::Equal => return Some(index),
+                Ordering::Less => node.left,
+                Ordering::Greater => node.right,
+            };
+        }
+        None
+    }
+
+    fn leftmost(&self, mut index: Index) -> Index {
+        while let Some(left) = self.node(index).left {
+            index = left;
+        }
+        index
+    }
+
+    fn successor(&self, index: Index) -> Option<Index> {
+        if let Some(right) = self.node(index).right {
+            return Some(self.leftmost(right));
+        }
+
+        let mut child = index;
+        let mut parent = self.node(index).parent;
+        while let Some(index) = parent {
+            if self.node(index).left == Some(child) {
+                break;
+            }
+            child = index;
+            parent = self.node(index).parent;
+        }
+        parent
+    }
+
+    // Puts `child` in place of `index` under the parent of `index`.
+    fn replace_child(&mut self, index: Index, child: Option<Index>) {
+        let parent = self.node(index).parent;
+        match parent {
+            Some(parent) => {
+                let node = self.node_mut(parent);
+                if node.left == Some(index) {
+                    node.left = child;
+                } else {
+                    node.right = child;
+                }
+            }
+            None => self.root = child,
+        }
+        if let Some(child) = child {
+            self.node_mut(child).parent = parent;
+        }
+    }
+}
+
+impl<K, V> ReadonlyMap<K, V> for ArenaBinarySearchTree<K, V> where K: Ord {
+    fn get(&self, key: &K) -> Option<&V> {
+        self.find(key).map(|index| &self.node(index).value)
+    }
+
+    fn size(&self) -> usize {
+        self.arena.size()
+    }
+}
+
+impl<K, V> Map<K, V> for ArenaBinarySearchTree<K, V> where K: Ord {
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.find(key).map(move |index| &mut self.node_mut(index).value)
+    }
+
+    fn put(&mut self, key: K, value: V) {
+        let mut parent = None;
+        let mut current = self.root;
+        while let Some(index) = current {
+            let node = self.node_mut(index);
+            parent = Some(index);
+            current = match key.cmp(&node.key) {
+                Ordering::Equal => {
+                    node.value = value;
+                    return;
+                }
+                Ordering::Less => node.left,
+                Ordering::Greater => node.right,
+            };
+        }
+
+        let goes_left = parent.map(|parent| key < self.node(parent).key);
+        let index = self.arena.insert(Node {
+            key,
+            value,
+            parent,
+            left: None,
+            right: None,
+        });
+        match (parent, goes_left) {
+            (Some(parent), Some(true)) => self.node_mut(parent).left = Some(index),
+            (Some(parent), _) => self.node_mut(parent).right = Some(index),
+            (None, _) => self.root = Some(index),
+        }
+    }
+
+    // A node with two children trades its entry with the in-order successor,
+    // which has no left child and is unlinked instead.
+    fn remove(&mut self, key: &K) -> Option<V> {
+        let mut index = self.find(key)?;
+
+        let node = self.node(index);
+        if let (Some(_), Some(right)) = (node.left, node.right) {
+            let successor = self.leftmost(right);
+            if let (Some(node), Some(next)) = self.arena.get2_mut(index, successor) {
+                mem::swap(&mut node.key, &mut next.key);
+                mem::swap(&mut node.value, &mut next.value);
+            }
+            index = successor;
+        }
+
+        let node = self.node(index);
+        let child = node.left.or(node.right);
+        self.replace_child(index, child);
+        self.arena.remove(index).map(|node| node.value)
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> where K: Ord {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.next?;
+        self.next = self.tree.successor(index);
+        self.size -= 1;
+
+        let node = self.tree.node(index);
+        Some((&node.key, &node.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> where K: Ord {}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::compare_with_btree_map;
+
+    use super::*;
+
+    fn create_tree() -> ArenaBinarySearchTree<i32, String> {
+        let mut tree = ArenaBinarySearchTree::new();
+        for key in [44, 17, 88, 32, 65, 97, 28, 54, 82, 29, 76, 80, 78].iter() {
+            tree.put(*key, key.to_string());
+        }
+        tree
+    }
+
+    fn keys(tree: &ArenaBinarySearchTree<i32, String>) -> Vec<i32> {
+        tree.iter().map(|(key, _)| *key).collect()
+    }
+
+    #[test]
+    fn create() {
+        let tree = ArenaBinarySearchTree::<i32, String>::new();
+        assert_eq!(0, tree.size());
+        assert_eq!(None, tree.get(&1));
+    }
+
+    #[test]
+    fn put_get() {
+        let mut tree = create_tree();
+        assert_eq!(13, tree.size());
+        assert_eq!(Some(&"54".to_string()), tree.get(&54));
+
+        tree.put(54, "fifty four".to_string());
+        *tree.get_mut(&17).unwrap() = "seventeen".to_string();
+        assert_eq!(13, tree.size());
+        assert_eq!(Some(&"fifty four".to_string()), tree.get(&54));
+        assert_eq!(Some(&"seventeen".to_string()), tree.get(&17));
+    }
+
+    #[test]
+    fn remove() {
+        let mut tree = create_tree();
+        assert_eq!(Some("32".to_string()), tree.remove(&32));
+        assert_eq!(vec![17, 28, 29, 44, 54, 65, 76, 78, 80, 82, 88, 97], keys(&tree));
+
+        assert_eq!(Some("17".to_string()), tree.remove(&17));
+        assert_eq!(Some("65".to_string()), tree.remove(&65));
+        assert_eq!(Some("44".to_string()), tree.remove(&44));
+        assert_eq!(None, tree.remove(&44));
+        assert_eq!(vec![28, 29, 54, 76, 78, 80, 82, 88, 97], keys(&tree));
+        assert_eq!(9, tree.size());
+    }
+
+    #[test]
+    fn clone() {
+        let original = create_tree();
+        let mut copy = original.clone();
+        copy.remove(&44);
+        copy.put(1, "1".to_string());
+
+        assert_eq!(Some(&"44".to_string()), original.get(&44));
+        assert_eq!(None, original.get(&1));
+        assert_eq!(None, copy.get(&44));
+        assert_eq!(13, copy.size());
+    }
+
+    #[test]
+    fn against_btree_map() {
+        let mut tree = ArenaBinarySearchTree::new();
+        let expected = compare_with_btree_map(&mut tree, 7, |_, _, _, _| {});
+        assert!(tree.iter().eq(expected.iter()));
+    }
+}
@@ -0,0 +1,242 @@
+use std::mem;
+
+// An index stays valid only as long as its slot holds the value it was issued for.
+// Removing a value bumps the generation of the slot, so stale indices stop resolving
+// even after the slot is reused.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Index {
+    slot: usize,
+    generation: u64,
+}
+
+#[derive(Debug, Clone)]
+enum Entry<T> {
+    Occupied { generation: u64, value: T },
+    Free { generation: u64, next_free: Option<usize> },
+}
+
+#[derive(Debug, Clone)]
+pub struct Arena<T> {
+    entries: Vec<Entry<T>>,
+    free_head: Option<usize>,
+    size: usize,
+}
+
+pub struct Iter<'a, T> {
+    entries: std::iter::Enumerate<std::slice::Iter<'a, Entry<T>>>,
+}
+
+impl Index {
+    pub fn slot(&self) -> usize {
+        self.slot
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+impl<T> Arena<T> {
+    pub fn new() -> Arena<T> {
+        Arena::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Arena<T> {
+        Arena {
+            entries: Vec::with_capacity(capacity),
+            free_head: None,
+            size: 0,
+        }
+    }
+
+    pub fn insert(&mut self, value: T) -> Index {
+        self.size += 1;
+        match self.free_head {
+            Some(slot) => {
+                let generation = match self.entries[slot] {
+                    Entry::Free { generation, next_free } => {
+                        self.free_head = next_free;
+                        generation
+                    }
+                    Entry::Occupied { .. } => unreachable!(),
+                };
+                self.entries[slot] = Entry::Occupied { generation, value };
+                Index { slot, generation }
+            }
+            None => {
+                self.entries.push(Entry::Occupied { generation: 0, value });
+                Index { slot: self.entries.len() - 1, generation: 0 }
+            }
+        }
+    }
+
+    pub fn remove(&mut self, index: Index) -> Option<T> {
+        if !self.contains(index) {
+            return None;
+        }
+
+        let free = Entry::Free {
+            generation: index.generation + 1,
+            next_free: self.free_head,
+        };
+        self.free_head = Some(index.slot);
+        self.size -= 1;
+        match mem::replace(&mut self.entries[index.slot], free) {
+            Entry::Occupied { value, .. } => Some(value),
+            Entry::Free { .. } => unreachable!(),
+        }
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.get(index).is_some()
+    }
+
+    pub fn get(&self, index: Index) -> Option<&T> {
+        match self.entries.get(index.slot) {
+            Some(Entry::Occupied { generation, value }) if *generation == index.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, index: Index) -> Option<&mut T> {
+        self.entries.get_mut(index.slot).and_then(|entry| occupied(entry, index))
+    }
+
+    // Mutable access to two distinct values at once, e.g. to link two nodes.
+    pub fn get2_mut(&mut self, first: Index, second: Index) -> (Option<&mut T>, Option<&mut T>) {
+        assert_ne!(first.slot, second.slot, "indices must refer to distinct slots");
+
+        let (low, high, swapped) = if first.slot < second.slot {
+            (first, second, false)
+        } else {
+            (second, first, true)
+        };
+        if high.slot >= self.entries.len() {
+            let low = self.get_mut(low);
+            return if swapped { (None, low) } else { (low, None) };
+        }
+
+        let (head, tail) = self.entries.split_at_mut(high.slot);
+        let low = occupied(&mut head[low.slot], low);
+        let high = occupied(&mut tail[0], high);
+
+        if swapped { (high, low) } else { (low, high) }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.entries.capacity()
+    }
+
+    // Drops every value, indices issued before stop resolving.
+    pub fn clear(&mut self) {
+        self.free_head = None;
+        self.size = 0;
+        for (slot, entry) in self.entries.iter_mut().enumerate().rev() {
+            let generation = match entry {
+                Entry::Occupied { generation, .. } => *generation + 1,
+                Entry::Free { generation, .. } => *generation,
+            };
+            *entry = Entry::Free { generation, next_free: self.free_head };
+            self.free_head = Some(slot);
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            entries: self.entries.iter().enumerate(),
+        }
+    }
+}
+
+fn occupied<T>(entry: &mut Entry<T>, index: Index) -> Option<&mut T> {
+    match entry {
+        Entry::Occupied { generation, value } if *generation == index.generation => Some(value),
+        _ => None,
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (Index, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (slot, entry) in &mut self.entries {
+            if let Entry::Occupied { generation, value } = entry {
+                return Some((Index { slot, generation: *generation }, value));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_get_remove() {
+        let mut arena = Arena::new();
+        let first = arena.insert("first");
+        let second = arena.insert("second");
+        assert_eq!(2, arena.size());
+        assert_eq!(Some(&"first"), arena.get(first));
+
+        *arena.get_mut(second).unwrap() = "changed";
+        assert_eq!(Some("changed"), arena.remove(second));
+        assert_eq!(None, arena.remove(second));
+        assert_eq!(None, arena.get(second));
+        assert_eq!(1, arena.size());
+    }
+
+    #[test]
+    fn stale_indices() {
+        let mut arena = Arena::new();
+        let old = arena.insert(1);
+        arena.remove(old);
+        let new = arena.insert(2);
+
+        assert_eq!(old.slot(), new.slot());
+        assert_eq!(old.generation() + 1, new.generation());
+        assert!(!arena.contains(old));
+        assert_eq!(None, arena.get_mut(old));
+        assert_eq!(Some(&2), arena.get(new));
+    }
+
+    #[test]
+    fn get2_mut() {
+        let mut arena = Arena::new();
+        let first = arena.insert(1);
+        let second = arena.insert(2);
+        let removed = arena.insert(3);
+        arena.remove(removed);
+
+        if let (Some(first), Some(second)) = arena.get2_mut(second, first) {
+            std::mem::swap(first, second);
+        }
+        assert_eq!(Some(&2), arena.get(first));
+        assert_eq!((Some(&mut 1), None), arena.get2_mut(second, removed));
+    }
+
+    #[test]
+    fn clear_and_iter() {
+        let mut arena = Arena::new();
+        let indices = (0..5).map(|i| arena.insert(i)).collect::<Vec<_>>();
+        arena.remove(indices[1]);
+        assert_eq!(vec![0, 2, 3, 4], arena.iter().map(|(_, value)| *value).collect::<Vec<_>>());
+
+        arena.clear();
+        assert!(arena.is_empty());
+        assert!(indices.iter().all(|index| !arena.contains(*index)));
+
+        let reused = arena.insert(10);
+        assert_eq!(0, reused.slot());
+        assert_eq!(Some(&10), arena.get(reused));
+    }
+}
@@ -0,0 +1,302 @@
+use std::iter::FromIterator;
+
+use crate::structures::arena::generational::{Arena, Index};
+use crate::structures::types::*;
+
+#[derive(Debug, Clone)]
+struct Node<T> {
+    value: T,
+    next: Option<Index>,
+}
+
+// Singly linked list whose nodes live in an arena and link to each other by index.
+// Keeping the tail index makes `enqueue` O(1).
+#[derive(Debug, Clone)]
+pub struct ArenaLinkedList<T> {
+    arena: Arena<Node<T>>,
+    head: Option<Index>,
+    tail: Option<Index>,
+}
+
+pub struct Iter<'a, T> {
+    list: &'a ArenaLinkedList<T>,
+    next: Option<Index>,
+    size: usize,
+}
+
+pub struct IntoIter<T> {
+    list: ArenaLinkedList<T>,
+}
+
+impl<T> ArenaLinkedList<T> {
+    pub fn new() -> ArenaLinkedList<T> {
+        ArenaLinkedList {
+            arena: Arena::new(),
+            head: None,
+            tail: None,
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            list: self,
+            next: self.head,
+            size: self.arena.size(),
+        }
+    }
+
+    fn node(&self, index: Index) -> &Node<T> {
+        self.arena.get(index).unwrap()
+    }
+
+    fn node_mut(&mut self, index: Index) -> &mut Node<T> {
+        self.arena.get_mut(index).unwrap()
+    }
+
+    fn index_at(&self, position: usize) -> Option<Index> {
+        let mut current = self.head;
+        for _ in 0..position {
+            current = self.node(current?).next;
+        }
+        current
+    }
+
+    fn link_after(&mut self, previous: Option<Index>, value: T) {
+        let next = match previous {
+            Some(previous) => self.node(previous).next,
+            None => self.head,
+        };
+
+        let index = self.arena.insert(Node { value, next });
+        match previous {
+            Some(previous) => self.node_mut(previous).next = Some(index),
+            None => self.head = Some(index),
+        }
+        if next.is_none() {
+            self.tail = Some(index);
+        }
+    }
+
+    fn unlink_after(&mut self, previous: Option<Index>) -> Option<T> {
+        let index = match previous {
+            Some(previous) => self.node(previous).next?,
+            None => self.head?,
+        };
+
+        let node = self.arena.remove(index).unwrap();
+        match previous {
+            Some(previous) => self.node_mut(previous).next = node.next,
+            None => self.head = node.next,
+        }
+        if node.next.is_none() {
+            self.tail = previous;
+        }
+        Some(node.value)
+    }
+}
+
+impl<T> Queue<T> for ArenaLinkedList<T> {
+    fn peek(&self) -> Option<&T> {
+        self.head.map(|head| &self.node(head).value)
+    }
+
+    fn enqueue(&mut self, value: T) {
+        self.link_after(self.tail, value);
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.unlink_after(None)
+    }
+}
+
+impl<T> ReadonlyStack<T> for ArenaLinkedList<T> {
+    fn peek(&self) -> Option<&T> {
+        Queue::peek(self)
+    }
+}
+
+impl<T> Stack<T> for ArenaLinkedList<T> {
+    fn push(&mut self, value: T) {
+        self.link_after(None, value);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.unlink_after(None)
+    }
+}
+
+impl<T> ReadonlySeq<T> for ArenaLinkedList<T> {
+    fn get(&self, index: usize) -> Option<&T> {
+        self.index_at(index).map(|index| &self.node(index).value)
+    }
+
+    fn index_of(&self, value: &T) -> Option<usize> where T: Eq {
+        self.iter().position(|current| current.eq(value))
+    }
+
+    fn size(&self) -> usize {
+        self.arena.size()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+}
+
+impl<T> Seq<T> for ArenaLinkedList<T> {
+    fn insert(&mut self, index: usize, value: T) -> Result<(), SeqError> {
+        let size = self.size();
+        if index > size {
+            return Err(SeqError::OutOfBound { index, size });
+        }
+
+        let previous = if index == 0 { None } else { self.index_at(index - 1) };
+        self.link_after(previous, value);
+        Ok(())
+    }
+
+    fn remove_at(&mut self, index: usize) -> Option<T> {
+        if index >= self.size() {
+            return None;
+        }
+
+        let previous = if index == 0 { None } else { self.index_at(index - 1) };
+        self.unlink_after(previous)
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.index_at(index).map(move |index| &mut self.node_mut(index).value)
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.list.node(self.next?);
+        self.next = node.next;
+        self.size -= 1;
+        Some(&node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.dequeue()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let size = self.list.size();
+        (size, Some(size))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for ArenaLinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ArenaLinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> Extend<T> for ArenaLinkedList<T> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        for value in iter {
+            self.enqueue(value);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for ArenaLinkedList<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut list = ArenaLinkedList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn create_new() {
+        let list = ArenaLinkedList::<i32>::new();
+        assert_eq!(0, list.size());
+        assert!(list.is_empty());
+        assert_eq!(None, list.get(0));
+    }
+
+    #[test]
+    fn queue_and_stack() {
+        let mut list = ArenaLinkedList::new();
+        list.enqueue(2);
+        list.enqueue(3);
+        list.push(1);
+        assert_eq!(Some(&1), ReadonlyStack::peek(&list));
+        assert_eq!(Some(1), list.dequeue());
+        assert_eq!(Some(2), list.pop());
+        list.enqueue(4);
+        assert_eq!(vec![3, 4], list.into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn seq() {
+        let mut list: ArenaLinkedList<i32> = (0..5).collect();
+        list.insert(5, 5).unwrap();
+        list.insert(0, -1).unwrap();
+        assert_eq!(Err(SeqError::OutOfBound { index: 8, size: 7 }), list.insert(8, 0));
+        assert_eq!(Some(3), list.index_of(&2));
+
+        *list.get_mut(1).unwrap() = 10;
+        assert_eq!(Some(5), list.remove_at(6));
+        assert_eq!(Some(-1), list.remove_at(0));
+        assert_eq!(None, list.remove_at(5));
+
+        list.enqueue(6);
+        assert_eq!(vec![&10, &1, &2, &3, &4, &6], list.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn reuses_slots() {
+        let mut list: ArenaLinkedList<i32> = (0..100).collect();
+        let capacity = list.arena.capacity();
+        for i in 0..1000 {
+            list.dequeue();
+            list.enqueue(i);
+        }
+        assert_eq!(capacity, list.arena.capacity());
+        assert_eq!(100, list.size());
+    }
+
+    #[test]
+    fn clone() {
+        let original: ArenaLinkedList<i32> = (0..3).collect();
+        let mut copy = original.clone();
+        copy.enqueue(3);
+        *copy.get_mut(0).unwrap() = 10;
+
+        assert_eq!(vec![&0, &1, &2], original.iter().collect::<Vec<_>>());
+        assert_eq!(vec![&10, &1, &2, &3], copy.iter().collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,3 @@
+pub mod generational;
+pub mod linked_list;
+pub mod binary_search_tree;
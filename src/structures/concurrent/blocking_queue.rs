@@ -0,0 +1,322 @@
+use std::marker::PhantomData;
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::structures::lists::array_deque::ArrayDeque;
+use crate::structures::types::*;
+
+struct State<Q> {
+    queue: Q,
+    size: usize,
+    closed: bool,
+}
+
+// Bounded multi-producer multi-consumer channel over any `Queue`. Senders wait on `not_full`,
+// receivers on `not_empty`; closing wakes everybody, receivers still drain what is left.
+pub struct BlockingQueue<T, Q = ArrayDeque<T>> {
+    state: Mutex<State<Q>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    capacity: usize,
+    marker: PhantomData<fn(T) -> T>,
+}
+
+// Blocks on every step and ends once the queue is closed and drained.
+pub struct Iter<'a, T, Q> {
+    queue: &'a BlockingQueue<T, Q>,
+}
+
+// Takes only the values queued at the moment of each call, never blocks.
+pub struct Drain<'a, T, Q> {
+    queue: &'a BlockingQueue<T, Q>,
+}
+
+impl<T> BlockingQueue<T> {
+    pub fn with_capacity(capacity: usize) -> BlockingQueue<T> {
+        BlockingQueue::new(ArrayDeque::new(), capacity)
+    }
+}
+
+impl<T, Q> BlockingQueue<T, Q> where Q: Queue<T> {
+    pub fn new(queue: Q, capacity: usize) -> BlockingQueue<T, Q> {
+        assert!(capacity > 0, "capacity must be positive");
+        assert!(queue.peek().is_none(), "underlying queue must be empty");
+
+        BlockingQueue {
+            state: Mutex::new(State {
+                queue,
+                size: 0,
+                closed: false,
+            }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            capacity,
+            marker: PhantomData,
+        }
+    }
+
+    // A panicking thread cannot leave the state inconsistent, every update is a single step.
+    fn lock(&self) -> MutexGuard<'_, State<Q>> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn push(&self, mut state: MutexGuard<'_, State<Q>>, value: T) {
+        state.queue.enqueue(value);
+        state.size += 1;
+        drop(state);
+        self.not_empty.notify_one();
+    }
+
+    fn pop(&self, mut state: MutexGuard<'_, State<Q>>) -> T {
+        let value = state.queue.dequeue().unwrap();
+        state.size -= 1;
+        drop(state);
+        self.not_full.notify_one();
+        value
+    }
+
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let mut state = self.lock();
+        while !state.closed && state.size == self.capacity {
+            state = self.not_full.wait(state).unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+
+        if state.closed {
+            return Err(SendError(value));
+        }
+        self.push(state, value);
+        Ok(())
+    }
+
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        let state = self.lock();
+        if state.closed {
+            Err(TrySendError::Closed(value))
+        } else if state.size == self.capacity {
+            Err(TrySendError::Full(value))
+        } else {
+            self.push(state, value);
+            Ok(())
+        }
+    }
+
+    // Returns `None` once the queue is closed and every value was received.
+    pub fn recv(&self) -> Option<T> {
+        let mut state = self.lock();
+        while !state.closed && state.size == 0 {
+            state = self.not_empty.wait(state).unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+
+        if state.size == 0 {
+            None
+        } else {
+            Some(self.pop(state))
+        }
+    }
+
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let state = self.lock();
+        if state.size > 0 {
+            Ok(self.pop(state))
+        } else if state.closed {
+            Err(TryRecvError::Closed)
+        } else {
+            Err(TryRecvError::Empty)
+        }
+    }
+
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.lock();
+        while !state.closed && state.size == 0 {
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(RecvTimeoutError::Timeout);
+            }
+            state = self.not_empty.wait_timeout(state, deadline - now)
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .0;
+        }
+
+        if state.size == 0 {
+            Err(RecvTimeoutError::Closed)
+        } else {
+            Ok(self.pop(state))
+        }
+    }
+
+    // Rejects further sends and wakes every waiting thread. Values already queued stay receivable.
+    pub fn close(&self) {
+        self.lock().closed = true;
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+
+    pub fn size(&self) -> usize {
+        self.lock().size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn iter(&self) -> Iter<'_, T, Q> {
+        Iter { queue: self }
+    }
+
+    pub fn drain(&self) -> Drain<'_, T, Q> {
+        Drain { queue: self }
+    }
+}
+
+impl<'a, T, Q> Iterator for Iter<'a, T, Q> where Q: Queue<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.queue.recv()
+    }
+}
+
+impl<'a, T, Q> Iterator for Drain<'a, T, Q> where Q: Queue<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.queue.try_recv().ok()
+    }
+}
+
+impl<'a, T, Q> IntoIterator for &'a BlockingQueue<T, Q> where Q: Queue<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T, Q>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+
+    use crate::structures::lists::linked_list::LinkedList;
+
+    use super::*;
+
+    #[test]
+    fn create_new() {
+        let queue = BlockingQueue::<i32>::with_capacity(2);
+        assert_eq!(2, queue.capacity());
+        assert!(queue.is_empty());
+        assert!(!queue.is_closed());
+        assert_eq!(Err(TryRecvError::Empty), queue.try_recv());
+    }
+
+    #[test]
+    fn try_send_recv() {
+        let queue = BlockingQueue::new(LinkedList::new(), 2);
+        assert_eq!(Ok(()), queue.try_send(1));
+        assert_eq!(Ok(()), queue.try_send(2));
+        assert_eq!(Err(TrySendError::Full(3)), queue.try_send(3));
+        assert_eq!(2, queue.size());
+
+        assert_eq!(Ok(1), queue.try_recv());
+        assert_eq!(Ok(()), queue.try_send(3));
+        assert_eq!(vec![2, 3], queue.drain().collect::<Vec<_>>());
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn close() {
+        let queue = BlockingQueue::with_capacity(4);
+        queue.send(1).unwrap();
+        queue.send(2).unwrap();
+        queue.close();
+
+        assert_eq!(Err(SendError(3)), queue.send(3));
+        assert_eq!(Err(TrySendError::Closed(4)), queue.try_send(4));
+        assert_eq!(Some(1), queue.recv());
+        assert_eq!(Ok(2), queue.recv_timeout(Duration::from_millis(10)));
+        assert_eq!(None, queue.recv());
+        assert_eq!(Err(TryRecvError::Closed), queue.try_recv());
+        assert_eq!(Err(RecvTimeoutError::Closed), queue.recv_timeout(Duration::from_millis(10)));
+    }
+
+    #[test]
+    fn recv_timeout() {
+        let queue = Arc::new(BlockingQueue::with_capacity(1));
+        let start = Instant::now();
+        assert_eq!(Err(RecvTimeoutError::Timeout), queue.recv_timeout(Duration::from_millis(50)));
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        let sender = Arc::clone(&queue);
+        let worker = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            sender.send(42).unwrap();
+        });
+        assert_eq!(Ok(42), queue.recv_timeout(Duration::from_secs(10)));
+        worker.join().unwrap();
+    }
+
+    #[test]
+    fn close_wakes_waiters() {
+        let queue = Arc::new(BlockingQueue::with_capacity(1));
+        queue.send(0).unwrap();
+
+        let sender = Arc::clone(&queue);
+        let blocked = thread::spawn(move || sender.send(1));
+        thread::sleep(Duration::from_millis(20));
+        queue.close();
+
+        assert_eq!(Err(SendError(1)), blocked.join().unwrap());
+        assert_eq!(vec![0], queue.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn worker_pool() {
+        const PRODUCERS: usize = 4;
+        const CONSUMERS: usize = 4;
+        const PER_PRODUCER: usize = 10_000;
+
+        let queue = Arc::new(BlockingQueue::with_capacity(16));
+        let producers = (0..PRODUCERS)
+            .map(|producer| {
+                let queue = Arc::clone(&queue);
+                thread::spawn(move || {
+                    for i in 0..PER_PRODUCER {
+                        queue.send(producer * PER_PRODUCER + i).unwrap();
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let consumers = (0..CONSUMERS)
+            .map(|_| {
+                let queue = Arc::clone(&queue);
+                thread::spawn(move || {
+                    assert!(queue.size() <= queue.capacity());
+                    queue.iter().collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        queue.close();
+
+        let mut values = consumers.into_iter()
+            .flat_map(|consumer| consumer.join().unwrap())
+            .collect::<Vec<_>>();
+        values.sort_unstable();
+        assert_eq!((0..PRODUCERS * PER_PRODUCER).collect::<Vec<_>>(), values);
+    }
+}
@@ -0,0 +1,255 @@
+use std::ptr;
+use std::sync::atomic::{fence, AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+
+const SCAN_THRESHOLD: usize = 64;
+
+// Hazard records are only appended and recycled, never freed while the domain is alive,
+// so a thread may walk the list without protection.
+struct Record {
+    hazard: AtomicPtr<u8>,
+    active: AtomicBool,
+    next: *mut Record,
+}
+
+struct Retired {
+    pointer: *mut u8,
+    reclaim: unsafe fn(*mut u8),
+    next: *mut Retired,
+}
+
+// Hazard pointer domain owned by a single data structure. A thread publishes the node it
+// is about to dereference and retired nodes are freed only once no record publishes them.
+pub struct Domain {
+    records: AtomicPtr<Record>,
+    record_count: AtomicUsize,
+    retired: AtomicPtr<Retired>,
+    retired_count: AtomicUsize,
+}
+
+pub struct HazardPointer<'a> {
+    record: &'a Record,
+}
+
+unsafe impl Send for Domain {}
+unsafe impl Sync for Domain {}
+
+unsafe fn reclaim_box<T>(pointer: *mut u8) {
+    drop(Box::from_raw(pointer as *mut T));
+}
+
+impl Domain {
+    pub fn new() -> Domain {
+        Domain {
+            records: AtomicPtr::new(ptr::null_mut()),
+            record_count: AtomicUsize::new(0),
+            retired: AtomicPtr::new(ptr::null_mut()),
+            retired_count: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn hazard_pointer(&self) -> HazardPointer<'_> {
+        let mut current = self.records.load(Ordering::Acquire);
+        while !current.is_null() {
+            let record = unsafe { &*current };
+            if !record.active.load(Ordering::Relaxed)
+                && record.active.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed).is_ok() {
+                return HazardPointer { record };
+            }
+            current = record.next;
+        }
+
+        let record = Box::into_raw(Box::new(Record {
+            hazard: AtomicPtr::new(ptr::null_mut()),
+            active: AtomicBool::new(true),
+            next: ptr::null_mut(),
+        }));
+        let mut head = self.records.load(Ordering::Acquire);
+        loop {
+            unsafe { (*record).next = head };
+            match self.records.compare_exchange_weak(head, record, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => break,
+                Err(actual) => head = actual,
+            }
+        }
+        self.record_count.fetch_add(1, Ordering::Relaxed);
+        HazardPointer { record: unsafe { &*record } }
+    }
+
+    // Safety: the pointer must come from `Box::into_raw`, must be unreachable for threads
+    // that did not protect it yet and must not be retired twice.
+    pub unsafe fn retire<T>(&self, pointer: *mut T) {
+        let retired = Box::into_raw(Box::new(Retired {
+            pointer: pointer as *mut u8,
+            reclaim: reclaim_box::<T>,
+            next: ptr::null_mut(),
+        }));
+        // Counted before it is published, otherwise a concurrent scan may reclaim it first.
+        let count = self.retired_count.fetch_add(1, Ordering::Relaxed) + 1;
+        self.push_retired(retired, retired);
+
+        if count >= SCAN_THRESHOLD.max(2 * self.record_count.load(Ordering::Relaxed)) {
+            self.scan();
+        }
+    }
+
+    // Frees every retired node that is not published by a hazard pointer.
+    pub fn scan(&self) {
+        // Pairs with the SeqCst store and reload in `protect`. The unlinking CAS of a retired
+        // node comes before this fence, so either the reader's reload sees the node unlinked
+        // and retries, or the hazard slots read below see the reader's hazard.
+        fence(Ordering::SeqCst);
+        let mut current = self.retired.swap(ptr::null_mut(), Ordering::Acquire);
+        if current.is_null() {
+            return;
+        }
+
+        let mut hazards = Vec::new();
+        let mut record = self.records.load(Ordering::Acquire);
+        while !record.is_null() {
+            let hazard = unsafe { (*record).hazard.load(Ordering::SeqCst) };
+            if !hazard.is_null() {
+                hazards.push(hazard);
+            }
+            record = unsafe { (*record).next };
+        }
+        hazards.sort_unstable();
+
+        let mut kept_first: *mut Retired = ptr::null_mut();
+        let mut kept_last: *mut Retired = ptr::null_mut();
+        let mut reclaimed = 0;
+        while !current.is_null() {
+            let retired = unsafe { &mut *current };
+            let next = retired.next;
+            if hazards.binary_search(&retired.pointer).is_ok() {
+                retired.next = kept_first;
+                if kept_first.is_null() {
+                    kept_last = current;
+                }
+                kept_first = current;
+            } else {
+                unsafe {
+                    (retired.reclaim)(retired.pointer);
+                    drop(Box::from_raw(current));
+                }
+                reclaimed += 1;
+            }
+            current = next;
+        }
+
+        self.retired_count.fetch_sub(reclaimed, Ordering::Relaxed);
+        if !kept_first.is_null() {
+            self.push_retired(kept_first, kept_last);
+        }
+    }
+
+    fn push_retired(&self, first: *mut Retired, last: *mut Retired) {
+        let mut head = self.retired.load(Ordering::Relaxed);
+        loop {
+            unsafe { (*last).next = head };
+            match self.retired.compare_exchange_weak(head, first, Ordering::Release, Ordering::Relaxed) {
+                Ok(_) => return,
+                Err(actual) => head = actual,
+            }
+        }
+    }
+
+    #[cfg(test)]
+    pub fn retired_count(&self) -> usize {
+        self.retired_count.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for Domain {
+    fn drop(&mut self) {
+        let mut retired = *self.retired.get_mut();
+        while !retired.is_null() {
+            let node = unsafe { Box::from_raw(retired) };
+            unsafe { (node.reclaim)(node.pointer) };
+            retired = node.next;
+        }
+
+        let mut record = *self.records.get_mut();
+        while !record.is_null() {
+            let node = unsafe { Box::from_raw(record) };
+            record = node.next;
+        }
+    }
+}
+
+impl<'a> HazardPointer<'a> {
+    // Loads the pointer and publishes it, retrying until the source still holds it after the
+    // publication. From then on the node stays allocated until `clear` or drop.
+    pub fn protect<T>(&self, source: &AtomicPtr<T>) -> *mut T {
+        let mut pointer = source.load(Ordering::Relaxed);
+        loop {
+            self.record.hazard.store(pointer as *mut u8, Ordering::SeqCst);
+            let current = source.load(Ordering::SeqCst);
+            if current == pointer {
+                return pointer;
+            }
+            pointer = current;
+        }
+    }
+
+    pub fn clear(&self) {
+        self.record.hazard.store(ptr::null_mut(), Ordering::Release);
+    }
+}
+
+impl<'a> Drop for HazardPointer<'a> {
+    fn drop(&mut self) {
+        self.clear();
+        self.record.active.store(false, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    struct Tracked;
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn protected_nodes_survive_scan() {
+        let domain = Domain::new();
+        let source = AtomicPtr::new(Box::into_raw(Box::new(Tracked)));
+
+        let hazard = domain.hazard_pointer();
+        let protected = hazard.protect(&source);
+        source.store(ptr::null_mut(), Ordering::SeqCst);
+        unsafe { domain.retire(protected) };
+
+        domain.scan();
+        assert_eq!(1, domain.retired_count());
+        assert_eq!(0, DROPS.load(Ordering::SeqCst));
+
+        hazard.clear();
+        domain.scan();
+        assert_eq!(0, domain.retired_count());
+        assert_eq!(1, DROPS.load(Ordering::SeqCst));
+
+        unsafe { domain.retire(Box::into_raw(Box::new(Tracked))) };
+        drop(hazard);
+        drop(domain);
+        assert_eq!(2, DROPS.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn records_are_recycled() {
+        let domain = Domain::new();
+        for _ in 0..10 {
+            let _hazard = domain.hazard_pointer();
+        }
+        let _first = domain.hazard_pointer();
+        let _second = domain.hazard_pointer();
+        assert_eq!(2, domain.record_count.load(Ordering::Relaxed));
+    }
+}
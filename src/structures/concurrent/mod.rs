@@ -0,0 +1,4 @@
+pub mod hazard;
+pub mod treiber_stack;
+pub mod ms_queue;
+pub mod blocking_queue;
@@ -0,0 +1,206 @@
+use std::mem::MaybeUninit;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::structures::concurrent::hazard::Domain;
+use crate::structures::types::*;
+
+// The head always points to a sentinel whose value was already taken or never existed.
+struct Node<T> {
+    value: MaybeUninit<T>,
+    next: AtomicPtr<Node<T>>,
+}
+
+pub struct MichaelScottQueue<T> {
+    head: AtomicPtr<Node<T>>,
+    tail: AtomicPtr<Node<T>>,
+    domain: Domain,
+}
+
+unsafe impl<T> Send for MichaelScottQueue<T> where T: Send {}
+unsafe impl<T> Sync for MichaelScottQueue<T> where T: Send {}
+
+impl<T> Node<T> {
+    fn allocate(value: MaybeUninit<T>) -> *mut Node<T> {
+        Box::into_raw(Box::new(Node {
+            value,
+            next: AtomicPtr::new(ptr::null_mut()),
+        }))
+    }
+}
+
+impl<T> MichaelScottQueue<T> {
+    pub fn new() -> MichaelScottQueue<T> {
+        let sentinel = Node::allocate(MaybeUninit::uninit());
+        MichaelScottQueue {
+            head: AtomicPtr::new(sentinel),
+            tail: AtomicPtr::new(sentinel),
+            domain: Domain::new(),
+        }
+    }
+}
+
+impl<T> ConcurrentQueue<T> for MichaelScottQueue<T> where T: Send {
+    fn enqueue(&self, value: T) {
+        let node = Node::allocate(MaybeUninit::new(value));
+        let hazard = self.domain.hazard_pointer();
+        loop {
+            let tail = hazard.protect(&self.tail);
+            let next = unsafe { (*tail).next.load(Ordering::Acquire) };
+            if !next.is_null() {
+                // Another thread linked a node but has not swung the tail yet, help it.
+                let _ = self.tail.compare_exchange(tail, next, Ordering::Release, Ordering::Relaxed);
+                continue;
+            }
+
+            let linked = unsafe {
+                (*tail).next.compare_exchange(ptr::null_mut(), node, Ordering::Release, Ordering::Relaxed)
+            };
+            if linked.is_ok() {
+                let _ = self.tail.compare_exchange(tail, node, Ordering::Release, Ordering::Relaxed);
+                return;
+            }
+        }
+    }
+
+    // Both the sentinel and its successor are protected: the successor becomes the new
+    // sentinel and its value is read after the head moved past the old one.
+    fn dequeue(&self) -> Option<T> {
+        let head_hazard = self.domain.hazard_pointer();
+        let next_hazard = self.domain.hazard_pointer();
+        loop {
+            let head = head_hazard.protect(&self.head);
+            let next = next_hazard.protect(unsafe { &(*head).next });
+            if self.head.load(Ordering::Acquire) != head {
+                continue;
+            }
+            if next.is_null() {
+                return None;
+            }
+
+            let tail = self.tail.load(Ordering::Acquire);
+            if tail == head {
+                let _ = self.tail.compare_exchange(tail, next, Ordering::Release, Ordering::Relaxed);
+                continue;
+            }
+
+            if self.head.compare_exchange(head, next, Ordering::AcqRel, Ordering::Relaxed).is_ok() {
+                let value = unsafe { ptr::read((*next).value.as_ptr()) };
+                head_hazard.clear();
+                next_hazard.clear();
+                unsafe { self.domain.retire(head) };
+                return Some(value);
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        let hazard = self.domain.hazard_pointer();
+        let head = hazard.protect(&self.head);
+        unsafe { (*head).next.load(Ordering::Acquire).is_null() }
+    }
+}
+
+impl<T> Drop for MichaelScottQueue<T> {
+    fn drop(&mut self) {
+        let sentinel = unsafe { Box::from_raw(*self.head.get_mut()) };
+        let mut current = sentinel.next.load(Ordering::Relaxed);
+        while !current.is_null() {
+            let mut node = unsafe { Box::from_raw(current) };
+            unsafe { ptr::drop_in_place(node.value.as_mut_ptr()) };
+            current = node.next.load(Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn create_new() {
+        let queue = MichaelScottQueue::<i32>::new();
+        assert!(queue.is_empty());
+        assert_eq!(None, queue.dequeue());
+    }
+
+    #[test]
+    fn enqueue_dequeue() {
+        let queue = MichaelScottQueue::new();
+        for i in 0..10 {
+            queue.enqueue(i);
+        }
+        assert!(!queue.is_empty());
+        for i in 0..10 {
+            assert_eq!(Some(i), queue.dequeue());
+        }
+        assert_eq!(None, queue.dequeue());
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn drops_remaining_values() {
+        let value = Arc::new(());
+        let queue = MichaelScottQueue::new();
+        for _ in 0..100 {
+            queue.enqueue(Arc::clone(&value));
+        }
+        for _ in 0..40 {
+            queue.dequeue();
+        }
+        assert_eq!(61, Arc::strong_count(&value));
+        drop(queue);
+        assert_eq!(1, Arc::strong_count(&value));
+    }
+
+    // Every consumer must see the values of a single producer in the order they were sent.
+    #[test]
+    fn stress() {
+        const PRODUCERS: usize = 4;
+        const CONSUMERS: usize = 4;
+        const PER_PRODUCER: usize = 50_000;
+
+        let queue = Arc::new(MichaelScottQueue::new());
+        let producers = (0..PRODUCERS)
+            .map(|producer| {
+                let queue = Arc::clone(&queue);
+                thread::spawn(move || {
+                    for i in 0..PER_PRODUCER {
+                        queue.enqueue((producer, i));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let consumers = (0..CONSUMERS)
+            .map(|_| {
+                let queue = Arc::clone(&queue);
+                thread::spawn(move || {
+                    let mut last = [None; PRODUCERS];
+                    let mut received = 0;
+                    while received < PRODUCERS * PER_PRODUCER / CONSUMERS {
+                        if let Some((producer, i)) = queue.dequeue() {
+                            assert!(last[producer] < Some(i));
+                            last[producer] = Some(i);
+                            received += 1;
+                        }
+                    }
+                    received
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        let received = consumers.into_iter()
+            .map(|consumer| consumer.join().unwrap())
+            .sum::<usize>();
+
+        assert_eq!(PRODUCERS * PER_PRODUCER, received);
+        assert!(queue.is_empty());
+    }
+}
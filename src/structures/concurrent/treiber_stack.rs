@@ -0,0 +1,159 @@
+use std::mem::ManuallyDrop;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::structures::concurrent::hazard::Domain;
+use crate::structures::types::*;
+
+// The value is moved out by the thread that unlinks the node, the node itself is freed
+// later by the hazard pointer domain, so it must not drop the value again.
+struct Node<T> {
+    value: ManuallyDrop<T>,
+    next: *mut Node<T>,
+}
+
+pub struct TreiberStack<T> {
+    head: AtomicPtr<Node<T>>,
+    domain: Domain,
+}
+
+unsafe impl<T> Send for TreiberStack<T> where T: Send {}
+unsafe impl<T> Sync for TreiberStack<T> where T: Send {}
+
+impl<T> TreiberStack<T> {
+    pub fn new() -> TreiberStack<T> {
+        TreiberStack {
+            head: AtomicPtr::new(ptr::null_mut()),
+            domain: Domain::new(),
+        }
+    }
+}
+
+impl<T> ConcurrentStack<T> for TreiberStack<T> where T: Send {
+    fn push(&self, value: T) {
+        let node = Box::into_raw(Box::new(Node {
+            value: ManuallyDrop::new(value),
+            next: ptr::null_mut(),
+        }));
+
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            unsafe { (*node).next = head };
+            match self.head.compare_exchange_weak(head, node, Ordering::Release, Ordering::Relaxed) {
+                Ok(_) => return,
+                Err(actual) => head = actual,
+            }
+        }
+    }
+
+    // The hazard pointer keeps the head allocated while `next` is read, which also rules
+    // out the ABA problem: an address cannot be reused while a thread still compares against it.
+    fn pop(&self) -> Option<T> {
+        let hazard = self.domain.hazard_pointer();
+        loop {
+            let head = hazard.protect(&self.head);
+            if head.is_null() {
+                return None;
+            }
+
+            let next = unsafe { (*head).next };
+            if self.head.compare_exchange(head, next, Ordering::AcqRel, Ordering::Relaxed).is_ok() {
+                let value = unsafe { ManuallyDrop::take(&mut (*head).value) };
+                hazard.clear();
+                unsafe { self.domain.retire(head) };
+                return Some(value);
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire).is_null()
+    }
+}
+
+impl<T> Drop for TreiberStack<T> {
+    fn drop(&mut self) {
+        let mut current = *self.head.get_mut();
+        while !current.is_null() {
+            let mut node = unsafe { Box::from_raw(current) };
+            unsafe { ManuallyDrop::drop(&mut node.value) };
+            current = node.next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn create_new() {
+        let stack = TreiberStack::<i32>::new();
+        assert!(stack.is_empty());
+        assert_eq!(None, stack.pop());
+    }
+
+    #[test]
+    fn push_pop() {
+        let stack = TreiberStack::new();
+        for i in 0..10 {
+            stack.push(i);
+        }
+        assert!(!stack.is_empty());
+        for i in (0..10).rev() {
+            assert_eq!(Some(i), stack.pop());
+        }
+        assert_eq!(None, stack.pop());
+    }
+
+    #[test]
+    fn drops_remaining_values() {
+        let value = Arc::new(());
+        let stack = TreiberStack::new();
+        for _ in 0..100 {
+            stack.push(Arc::clone(&value));
+        }
+        for _ in 0..40 {
+            stack.pop();
+        }
+        assert_eq!(61, Arc::strong_count(&value));
+        drop(stack);
+        assert_eq!(1, Arc::strong_count(&value));
+    }
+
+    #[test]
+    fn stress() {
+        const THREADS: usize = 8;
+        const PER_THREAD: usize = 50_000;
+
+        let stack = Arc::new(TreiberStack::new());
+        let workers = (0..THREADS)
+            .map(|thread| {
+                let stack = Arc::clone(&stack);
+                thread::spawn(move || {
+                    let mut popped = Vec::new();
+                    for i in 0..PER_THREAD {
+                        stack.push(thread * PER_THREAD + i);
+                        if i % 2 == 1 {
+                            popped.extend(stack.pop());
+                        }
+                    }
+                    popped
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut values = workers.into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect::<Vec<_>>();
+        while let Some(value) = stack.pop() {
+            values.push(value);
+        }
+
+        values.sort_unstable();
+        assert_eq!((0..THREADS * PER_THREAD).collect::<Vec<_>>(), values);
+    }
+}
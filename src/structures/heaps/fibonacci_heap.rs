@@ -0,0 +1,360 @@
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr::NonNull;
+use std::rc::Rc;
+
+use crate::structures::heaps::handle::{NodeHandle, NodeTracker, Owner};
+use crate::structures::types::*;
+
+type Link<T> = NonNull<Node<T>>;
+
+// Every node is its own allocation and nodes point to each other, so melding only splices
+// the root lists. Siblings, including the roots, form circular doubly linked lists through
+// `left` and `right`.
+struct Node<T> {
+    value: T,
+    tracker: NodeTracker,
+    parent: Option<Link<T>>,
+    child: Option<Link<T>>,
+    left: Link<T>,
+    right: Link<T>,
+    degree: usize,
+    marked: bool,
+}
+
+pub struct FibonacciHeap<T> {
+    owner: Rc<Owner>,
+    min: Option<Link<T>>,
+    size: usize,
+    marker: PhantomData<Box<Node<T>>>,
+}
+
+impl<T> FibonacciHeap<T> where T: Ord {
+    pub fn new() -> FibonacciHeap<T> {
+        FibonacciHeap {
+            owner: Owner::new(),
+            min: None,
+            size: 0,
+            marker: PhantomData,
+        }
+    }
+
+    fn node(&self, link: Link<T>) -> &Node<T> {
+        // Links only point to nodes of this heap, which are freed through `&mut self`.
+        unsafe { &*link.as_ptr() }
+    }
+
+    fn node_mut(&mut self, link: Link<T>) -> &mut Node<T> {
+        unsafe { &mut *link.as_ptr() }
+    }
+
+    fn resolve(&self, handle: &NodeHandle) -> Result<Link<T>, HeapError> {
+        handle.resolve(&self.owner).map(NonNull::cast).ok_or(HeapError::InvalidHandle)
+    }
+
+    fn less(&self, left: Link<T>, right: Link<T>) -> bool {
+        self.node(left).value < self.node(right).value
+    }
+
+    fn allocate(&mut self, value: T) -> (Link<T>, NodeHandle) {
+        let (tracker, handle) = NodeTracker::new(&self.owner);
+        let node = Box::new(Node {
+            value,
+            tracker,
+            parent: None,
+            child: None,
+            left: NonNull::dangling(),
+            right: NonNull::dangling(),
+            degree: 0,
+            marked: false,
+        });
+
+        let link = NonNull::from(Box::leak(node));
+        let node = self.node_mut(link);
+        node.left = link;
+        node.right = link;
+        node.tracker.place(link.cast());
+        (link, handle)
+    }
+
+    fn release(&mut self, link: Link<T>) -> Node<T> {
+        unsafe { *Box::from_raw(link.as_ptr()) }
+    }
+
+    // Joins the circular lists containing `left` and `right`.
+    fn concat(&mut self, left: Link<T>, right: Link<T>) {
+        let left_next = self.node(left).right;
+        let right_prev = self.node(right).left;
+
+        self.node_mut(left).right = right;
+        self.node_mut(right).left = left;
+        self.node_mut(left_next).left = right_prev;
+        self.node_mut(right_prev).right = left_next;
+    }
+
+    fn unlink(&mut self, link: Link<T>) {
+        let (left, right) = (self.node(link).left, self.node(link).right);
+        self.node_mut(left).right = right;
+        self.node_mut(right).left = left;
+
+        let node = self.node_mut(link);
+        node.left = link;
+        node.right = link;
+    }
+
+    fn add_root(&mut self, link: Link<T>) {
+        let node = self.node_mut(link);
+        node.parent = None;
+        node.marked = false;
+
+        match self.min {
+            Some(min) => {
+                self.concat(min, link);
+                if self.less(link, min) {
+                    self.min = Some(link);
+                }
+            }
+            None => self.min = Some(link),
+        }
+    }
+
+    fn link(&mut self, child: Link<T>, parent: Link<T>) {
+        self.unlink(child);
+        match self.node(parent).child {
+            Some(first) => self.concat(first, child),
+            None => self.node_mut(parent).child = Some(child),
+        }
+
+        let node = self.node_mut(child);
+        node.parent = Some(parent);
+        node.marked = false;
+        self.node_mut(parent).degree += 1;
+    }
+
+    fn consolidate(&mut self) {
+        let start = match self.min {
+            Some(start) => start,
+            None => return,
+        };
+
+        let mut roots = vec![start];
+        let mut current = self.node(start).right;
+        while current != start {
+            roots.push(current);
+            current = self.node(current).right;
+        }
+
+        let mut by_degree: Vec<Option<Link<T>>> = Vec::new();
+        for root in roots {
+            let mut tree = root;
+            loop {
+                let degree = self.node(tree).degree;
+                if by_degree.len() <= degree {
+                    by_degree.resize(degree + 1, None);
+                }
+
+                match by_degree[degree].take() {
+                    Some(mut other) => {
+                        if self.less(other, tree) {
+                            mem::swap(&mut tree, &mut other);
+                        }
+                        self.link(other, tree);
+                    }
+                    None => {
+                        by_degree[degree] = Some(tree);
+                        break;
+                    }
+                }
+            }
+        }
+
+        self.min = None;
+        for root in by_degree.into_iter().flatten() {
+            match self.min {
+                Some(min) if !self.less(root, min) => {}
+                _ => self.min = Some(root),
+            }
+        }
+    }
+
+    // Detaches the minimum from the heap, the node stays allocated.
+    fn extract_min(&mut self) -> Option<Link<T>> {
+        let min = self.min?;
+
+        if let Some(child) = self.node_mut(min).child.take() {
+            let mut current = child;
+            loop {
+                let node = self.node_mut(current);
+                node.parent = None;
+                node.marked = false;
+                current = node.right;
+                if current == child {
+                    break;
+                }
+            }
+            self.concat(min, child);
+            self.node_mut(min).degree = 0;
+        }
+
+        let next = self.node(min).right;
+        self.unlink(min);
+        if next == min {
+            self.min = None;
+        } else {
+            self.min = Some(next);
+            self.consolidate();
+        }
+
+        self.size -= 1;
+        Some(min)
+    }
+
+    fn cut(&mut self, link: Link<T>, parent: Link<T>) {
+        if self.node(parent).child == Some(link) {
+            let next = self.node(link).right;
+            self.node_mut(parent).child = if next == link { None } else { Some(next) };
+        }
+
+        self.unlink(link);
+        self.node_mut(parent).degree -= 1;
+        self.add_root(link);
+    }
+
+    fn cascading_cut(&mut self, mut link: Link<T>) {
+        while let Some(parent) = self.node(link).parent {
+            if !self.node(link).marked {
+                self.node_mut(link).marked = true;
+                return;
+            }
+
+            self.cut(link, parent);
+            link = parent;
+        }
+    }
+
+    // Moves the node to the root list and makes it the minimum, so `extract_min` takes it out.
+    fn extract(&mut self, link: Link<T>) {
+        if let Some(parent) = self.node(link).parent {
+            self.cut(link, parent);
+            self.cascading_cut(parent);
+        }
+
+        self.min = Some(link);
+        self.extract_min();
+    }
+}
+
+impl<T> Queue<T> for FibonacciHeap<T> where T: Ord {
+    fn peek(&self) -> Option<&T> {
+        self.min.map(|min| &self.node(min).value)
+    }
+
+    fn enqueue(&mut self, value: T) {
+        self.insert(value);
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.extract_min().map(|min| self.release(min).value)
+    }
+}
+
+impl<T> AddressableHeap<T> for FibonacciHeap<T> where T: Ord {
+    type Handle = NodeHandle;
+
+    fn insert(&mut self, value: T) -> NodeHandle {
+        let (link, handle) = self.allocate(value);
+        self.add_root(link);
+        self.size += 1;
+        handle
+    }
+
+    fn get(&self, handle: &NodeHandle) -> Option<&T> {
+        self.resolve(handle).ok().map(|link| &self.node(link).value)
+    }
+
+    fn decrease_key(&mut self, handle: &NodeHandle, value: T) -> Result<(), HeapError> {
+        let link = self.resolve(handle)?;
+        if value > self.node(link).value {
+            return Err(HeapError::InvalidKey);
+        }
+
+        self.node_mut(link).value = value;
+        if let Some(parent) = self.node(link).parent {
+            if self.less(link, parent) {
+                self.cut(link, parent);
+                self.cascading_cut(parent);
+            }
+        }
+
+        if let Some(min) = self.min {
+            if self.less(link, min) {
+                self.min = Some(link);
+            }
+        }
+        Ok(())
+    }
+
+    // Implemented as an extraction followed by a reinsertion of the same node,
+    // so the handle stays valid. Amortised O(log n).
+    fn increase_key(&mut self, handle: &NodeHandle, value: T) -> Result<(), HeapError> {
+        let link = self.resolve(handle)?;
+        if value < self.node(link).value {
+            return Err(HeapError::InvalidKey);
+        }
+
+        self.extract(link);
+        self.node_mut(link).value = value;
+        self.add_root(link);
+        self.size += 1;
+        Ok(())
+    }
+
+    fn remove(&mut self, handle: &NodeHandle) -> Result<T, HeapError> {
+        let link = self.resolve(handle)?;
+        self.extract(link);
+        Ok(self.release(link).value)
+    }
+
+    // The nodes of the other heap become part of this one by forwarding its identity, so
+    // only the two root lists are touched.
+    fn meld(&mut self, mut other: FibonacciHeap<T>) {
+        other.owner.forward_to(&self.owner);
+        if let Some(other_min) = other.min.take() {
+            match self.min {
+                Some(min) => {
+                    self.concat(min, other_min);
+                    if self.less(other_min, min) {
+                        self.min = Some(other_min);
+                    }
+                }
+                None => self.min = Some(other_min),
+            }
+        }
+        self.size += mem::take(&mut other.size);
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+}
+
+impl<T> Drop for FibonacciHeap<T> {
+    fn drop(&mut self) {
+        let mut pending = self.min.take().into_iter().collect::<Vec<_>>();
+        while let Some(first) = pending.pop() {
+            let mut current = first;
+            loop {
+                let node = unsafe { Box::from_raw(current.as_ptr()) };
+                pending.extend(node.child);
+                current = node.right;
+                if current == first {
+                    break;
+                }
+            }
+        }
+    }
+}
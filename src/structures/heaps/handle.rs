@@ -0,0 +1,139 @@
+use std::cell::{Cell, RefCell};
+use std::ptr::NonNull;
+use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_HEAP_ID: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct Location {
+    heap: usize,
+    index: usize,
+}
+
+// A handle only observes the location of its element. The element owns the `Tracker`,
+// so once it leaves the heap every handle pointing to it stops resolving.
+#[derive(Debug, Clone)]
+pub struct Handle {
+    location: Weak<Cell<Location>>,
+}
+
+#[derive(Debug)]
+pub(super) struct Tracker {
+    location: Rc<Cell<Location>>,
+}
+
+pub(super) fn next_heap_id() -> usize {
+    NEXT_HEAP_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+impl Handle {
+    pub(super) fn resolve(&self, heap: usize) -> Option<usize> {
+        let location = self.location.upgrade()?.get();
+        if location.heap == heap {
+            Some(location.index)
+        } else {
+            None
+        }
+    }
+}
+
+impl Tracker {
+    pub(super) fn new(heap: usize, index: usize) -> (Tracker, Handle) {
+        let location = Rc::new(Cell::new(Location { heap, index }));
+        let handle = Handle {
+            location: Rc::downgrade(&location),
+        };
+        (Tracker { location }, handle)
+    }
+
+    pub(super) fn move_to(&self, heap: usize, index: usize) {
+        self.location.set(Location { heap, index });
+    }
+
+    pub(super) fn set_index(&self, index: usize) {
+        let heap = self.location.get().heap;
+        self.move_to(heap, index);
+    }
+}
+
+// Identity of a node based heap. Melding forwards the absorbed heap to the one it was melded
+// into, so its nodes change heap without being visited.
+#[derive(Debug, Default)]
+pub(super) struct Owner {
+    forward: RefCell<Option<Rc<Owner>>>,
+}
+
+// Handle to an element of a node based heap. The node lives in its own allocation, so its
+// address is fixed for as long as it is in a heap.
+#[derive(Debug, Clone)]
+pub struct NodeHandle {
+    location: Weak<NodeLocation>,
+}
+
+#[derive(Debug)]
+struct NodeLocation {
+    owner: RefCell<Rc<Owner>>,
+    node: Cell<NonNull<()>>,
+}
+
+#[derive(Debug)]
+pub(super) struct NodeTracker {
+    location: Rc<NodeLocation>,
+}
+
+impl Owner {
+    pub(super) fn new() -> Rc<Owner> {
+        Rc::new(Owner::default())
+    }
+
+    // The heap that absorbed this one, directly or through later melds. Every heap on the way
+    // is pointed at it, so the next lookup is direct.
+    fn resolve(owner: &Rc<Owner>) -> Rc<Owner> {
+        let mut root = Rc::clone(owner);
+        loop {
+            let forward = root.forward.borrow().clone();
+            match forward {
+                Some(forward) => root = forward,
+                None => break,
+            }
+        }
+
+        let mut current = Rc::clone(owner);
+        while !Rc::ptr_eq(&current, &root) {
+            current = current.forward.replace(Some(Rc::clone(&root))).unwrap();
+        }
+        root
+    }
+
+    pub(super) fn forward_to(&self, owner: &Rc<Owner>) {
+        *self.forward.borrow_mut() = Some(Rc::clone(owner));
+    }
+}
+
+impl NodeHandle {
+    pub(super) fn resolve(&self, owner: &Rc<Owner>) -> Option<NonNull<()>> {
+        let location = self.location.upgrade()?;
+        let current = Owner::resolve(&location.owner.borrow());
+        let found = Rc::ptr_eq(&current, owner);
+        *location.owner.borrow_mut() = current;
+        found.then(|| location.node.get())
+    }
+}
+
+impl NodeTracker {
+    pub(super) fn new(owner: &Rc<Owner>) -> (NodeTracker, NodeHandle) {
+        let location = Rc::new(NodeLocation {
+            owner: RefCell::new(Rc::clone(owner)),
+            node: Cell::new(NonNull::dangling()),
+        });
+        let handle = NodeHandle {
+            location: Rc::downgrade(&location),
+        };
+        (NodeTracker { location }, handle)
+    }
+
+    pub(super) fn place(&self, node: NonNull<()>) {
+        self.location.node.set(node);
+    }
+}
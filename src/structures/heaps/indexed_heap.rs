@@ -0,0 +1,174 @@
+use std::mem;
+
+use crate::structures::heaps::handle::{self, Handle, Tracker};
+use crate::structures::types::*;
+
+struct Entry<T> {
+    value: T,
+    tracker: Tracker,
+}
+
+// Implicit d-ary min heap: children of `i` live at `D * i + 1 ..= D * i + D`.
+// Every entry tracks its own position so a handle finds it in O(1).
+pub struct IndexedHeap<T, const D: usize = 2> {
+    id: usize,
+    data: Vec<Entry<T>>,
+}
+
+impl<T, const D: usize> IndexedHeap<T, D> where T: Ord {
+    pub fn new() -> IndexedHeap<T, D> {
+        assert!(D >= 2, "heap arity must be at least 2");
+
+        IndexedHeap {
+            id: handle::next_heap_id(),
+            data: Vec::new(),
+        }
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.data.swap(i, j);
+        self.data[i].tracker.set_index(i);
+        self.data[j].tracker.set_index(j);
+    }
+
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent = (index - 1) / D;
+            if self.data[index].value >= self.data[parent].value {
+                break;
+            }
+            self.swap(index, parent);
+            index = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut index: usize) {
+        loop {
+            let first = D * index + 1;
+            let last = (first + D).min(self.data.len());
+
+            let mut smallest = index;
+            for child in first..last {
+                if self.data[child].value < self.data[smallest].value {
+                    smallest = child;
+                }
+            }
+
+            if smallest == index {
+                break;
+            }
+            self.swap(index, smallest);
+            index = smallest;
+        }
+    }
+
+    fn remove_at(&mut self, index: usize) -> T {
+        let last = self.data.len() - 1;
+        self.swap(index, last);
+        let entry = self.data.pop().unwrap();
+
+        if index < self.data.len() {
+            self.sift_up(index);
+            self.sift_down(index);
+        }
+        entry.value
+    }
+}
+
+impl<T, const D: usize> Queue<T> for IndexedHeap<T, D> where T: Ord {
+    fn peek(&self) -> Option<&T> {
+        self.data.first().map(|entry| &entry.value)
+    }
+
+    fn enqueue(&mut self, value: T) {
+        self.insert(value);
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        if self.data.is_empty() {
+            None
+        } else {
+            Some(self.remove_at(0))
+        }
+    }
+}
+
+impl<T, const D: usize> AddressableHeap<T> for IndexedHeap<T, D> where T: Ord {
+    type Handle = Handle;
+
+    fn insert(&mut self, value: T) -> Handle {
+        let index = self.data.len();
+        let (tracker, handle) = Tracker::new(self.id, index);
+        self.data.push(Entry { value, tracker });
+        self.sift_up(index);
+        handle
+    }
+
+    fn get(&self, handle: &Handle) -> Option<&T> {
+        handle.resolve(self.id).map(|index| &self.data[index].value)
+    }
+
+    fn decrease_key(&mut self, handle: &Handle, value: T) -> Result<(), HeapError> {
+        let index = handle.resolve(self.id).ok_or(HeapError::InvalidHandle)?;
+        if value > self.data[index].value {
+            return Err(HeapError::InvalidKey);
+        }
+
+        self.data[index].value = value;
+        self.sift_up(index);
+        Ok(())
+    }
+
+    fn increase_key(&mut self, handle: &Handle, value: T) -> Result<(), HeapError> {
+        let index = handle.resolve(self.id).ok_or(HeapError::InvalidHandle)?;
+        if value < self.data[index].value {
+            return Err(HeapError::InvalidKey);
+        }
+
+        self.data[index].value = value;
+        self.sift_down(index);
+        Ok(())
+    }
+
+    fn remove(&mut self, handle: &Handle) -> Result<T, HeapError> {
+        let index = handle.resolve(self.id).ok_or(HeapError::InvalidHandle)?;
+        Ok(self.remove_at(index))
+    }
+
+    fn meld(&mut self, mut other: IndexedHeap<T, D>) {
+        let data = mem::take(&mut other.data);
+        for entry in data {
+            entry.tracker.move_to(self.id, self.data.len());
+            self.data.push(entry);
+        }
+
+        for index in (0..self.data.len() / D + 1).rev() {
+            self.sift_down(index);
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.data.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arity() {
+        let mut heap = IndexedHeap::<i32, 4>::new();
+        for value in (0..50).rev() {
+            heap.insert(value);
+        }
+        for expected in 0..50 {
+            assert_eq!(Some(expected), heap.dequeue());
+        }
+        assert!(heap.is_empty());
+    }
+}
@@ -0,0 +1,8 @@
+#[cfg(test)]
+mod tests;
+
+pub mod handle;
+pub mod priority_queue;
+pub mod indexed_heap;
+pub mod pairing_heap;
+pub mod fibonacci_heap;
@@ -0,0 +1,239 @@
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr::NonNull;
+use std::rc::Rc;
+
+use crate::structures::heaps::handle::{NodeHandle, NodeTracker, Owner};
+use crate::structures::types::*;
+
+type Link<T> = NonNull<Node<T>>;
+
+// Every node is its own allocation and nodes point to each other, so melding only links the
+// two roots. `prev` points to the parent for the leftmost child and to the previous sibling
+// otherwise.
+struct Node<T> {
+    value: T,
+    tracker: NodeTracker,
+    child: Option<Link<T>>,
+    sibling: Option<Link<T>>,
+    prev: Option<Link<T>>,
+}
+
+pub struct PairingHeap<T> {
+    owner: Rc<Owner>,
+    root: Option<Link<T>>,
+    size: usize,
+    marker: PhantomData<Box<Node<T>>>,
+}
+
+impl<T> PairingHeap<T> where T: Ord {
+    pub fn new() -> PairingHeap<T> {
+        PairingHeap {
+            owner: Owner::new(),
+            root: None,
+            size: 0,
+            marker: PhantomData,
+        }
+    }
+
+    fn node(&self, link: Link<T>) -> &Node<T> {
+        // Links only point to nodes of this heap, which are freed through `&mut self`.
+        unsafe { &*link.as_ptr() }
+    }
+
+    fn node_mut(&mut self, link: Link<T>) -> &mut Node<T> {
+        unsafe { &mut *link.as_ptr() }
+    }
+
+    fn resolve(&self, handle: &NodeHandle) -> Result<Link<T>, HeapError> {
+        handle.resolve(&self.owner).map(NonNull::cast).ok_or(HeapError::InvalidHandle)
+    }
+
+    fn allocate(&mut self, value: T) -> (Link<T>, NodeHandle) {
+        let (tracker, handle) = NodeTracker::new(&self.owner);
+        let node = Box::new(Node {
+            value,
+            tracker,
+            child: None,
+            sibling: None,
+            prev: None,
+        });
+
+        let link = NonNull::from(Box::leak(node));
+        self.node(link).tracker.place(link.cast());
+        (link, handle)
+    }
+
+    fn release(&mut self, link: Link<T>) -> Node<T> {
+        unsafe { *Box::from_raw(link.as_ptr()) }
+    }
+
+    // Links two detached trees, the root with the greater value becomes the leftmost child.
+    fn link(&mut self, left: Option<Link<T>>, right: Option<Link<T>>) -> Option<Link<T>> {
+        let (left, right) = match (left, right) {
+            (Some(left), Some(right)) => (left, right),
+            (left, None) => return left,
+            (None, right) => return right,
+        };
+
+        let (parent, child) = if self.node(right).value < self.node(left).value {
+            (right, left)
+        } else {
+            (left, right)
+        };
+
+        let first = self.node(parent).child;
+        if let Some(first) = first {
+            self.node_mut(first).prev = Some(child);
+        }
+        let node = self.node_mut(child);
+        node.sibling = first;
+        node.prev = Some(parent);
+        self.node_mut(parent).child = Some(child);
+        Some(parent)
+    }
+
+    fn detach(&mut self, link: Link<T>) {
+        let (prev, sibling) = {
+            let node = self.node_mut(link);
+            (node.prev.take(), node.sibling.take())
+        };
+
+        if let Some(prev) = prev {
+            let prev = self.node_mut(prev);
+            if prev.child == Some(link) {
+                prev.child = sibling;
+            } else {
+                prev.sibling = sibling;
+            }
+        }
+        if let Some(sibling) = sibling {
+            self.node_mut(sibling).prev = prev;
+        }
+    }
+
+    // Two pass pairing: link children pairwise left to right, then fold the pairs right to left.
+    fn merge_children(&mut self, link: Link<T>) -> Option<Link<T>> {
+        let mut pairs = Vec::new();
+        let mut current = self.node_mut(link).child.take();
+
+        while let Some(first) = current {
+            let second = self.node(first).sibling;
+            current = second.and_then(|second| self.node(second).sibling);
+
+            for child in [Some(first), second].iter().flatten() {
+                let node = self.node_mut(*child);
+                node.prev = None;
+                node.sibling = None;
+            }
+            pairs.push(self.link(Some(first), second));
+        }
+
+        pairs.into_iter().rev().fold(None, |merged, pair| self.link(pair, merged))
+    }
+
+    fn remove_node(&mut self, link: Link<T>) -> T {
+        if self.root == Some(link) {
+            self.root = self.merge_children(link);
+        } else {
+            self.detach(link);
+            let children = self.merge_children(link);
+            self.root = self.link(self.root, children);
+        }
+
+        self.size -= 1;
+        self.release(link).value
+    }
+}
+
+impl<T> Queue<T> for PairingHeap<T> where T: Ord {
+    fn peek(&self) -> Option<&T> {
+        self.root.map(|root| &self.node(root).value)
+    }
+
+    fn enqueue(&mut self, value: T) {
+        self.insert(value);
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.root.map(|root| self.remove_node(root))
+    }
+}
+
+impl<T> AddressableHeap<T> for PairingHeap<T> where T: Ord {
+    type Handle = NodeHandle;
+
+    fn insert(&mut self, value: T) -> NodeHandle {
+        let (link, handle) = self.allocate(value);
+        self.root = self.link(self.root, Some(link));
+        self.size += 1;
+        handle
+    }
+
+    fn get(&self, handle: &NodeHandle) -> Option<&T> {
+        self.resolve(handle).ok().map(|link| &self.node(link).value)
+    }
+
+    fn decrease_key(&mut self, handle: &NodeHandle, value: T) -> Result<(), HeapError> {
+        let link = self.resolve(handle)?;
+        if value > self.node(link).value {
+            return Err(HeapError::InvalidKey);
+        }
+
+        self.node_mut(link).value = value;
+        if self.root != Some(link) {
+            self.detach(link);
+            self.root = self.link(self.root, Some(link));
+        }
+        Ok(())
+    }
+
+    fn increase_key(&mut self, handle: &NodeHandle, value: T) -> Result<(), HeapError> {
+        let link = self.resolve(handle)?;
+        if value < self.node(link).value {
+            return Err(HeapError::InvalidKey);
+        }
+
+        self.node_mut(link).value = value;
+        let children = self.merge_children(link);
+        if self.root == Some(link) {
+            self.root = self.link(Some(link), children);
+        } else {
+            self.detach(link);
+            let root = self.link(self.root, Some(link));
+            self.root = self.link(root, children);
+        }
+        Ok(())
+    }
+
+    fn remove(&mut self, handle: &NodeHandle) -> Result<T, HeapError> {
+        let link = self.resolve(handle)?;
+        Ok(self.remove_node(link))
+    }
+
+    // The nodes of the other heap become part of this one by forwarding its identity, so
+    // only the two roots are touched.
+    fn meld(&mut self, mut other: PairingHeap<T>) {
+        other.owner.forward_to(&self.owner);
+        self.root = self.link(self.root, other.root.take());
+        self.size += mem::take(&mut other.size);
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+}
+
+impl<T> Drop for PairingHeap<T> {
+    fn drop(&mut self) {
+        let mut pending = self.root.take().into_iter().collect::<Vec<_>>();
+        while let Some(link) = pending.pop() {
+            let node = unsafe { Box::from_raw(link.as_ptr()) };
+            pending.extend(node.child.into_iter().chain(node.sibling));
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Formatter};
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::slice;
+
+use crate::structures::types::*;
+
+// Implicit binary heap: children of `i` live at `2i + 1` and `2i + 2`.
+// The element the comparator considers the greatest is kept at the root.
+pub struct PriorityQueue<T, C = MaxOrder> {
+    data: Vec<T>,
+    comparator: C,
+}
+
+pub struct PeekMut<'a, T, C> where C: Compare<T> {
+    queue: &'a mut PriorityQueue<T, C>,
+}
+
+impl<T> PriorityQueue<T, MaxOrder> where T: Ord {
+    pub fn new() -> PriorityQueue<T, MaxOrder> {
+        PriorityQueue::with_comparator(MaxOrder)
+    }
+
+    pub fn from_vec(data: Vec<T>) -> PriorityQueue<T, MaxOrder> {
+        PriorityQueue::from_vec_with(data, MaxOrder)
+    }
+}
+
+impl<T> PriorityQueue<T, MinOrder> where T: Ord {
+    pub fn new_min() -> PriorityQueue<T, MinOrder> {
+        PriorityQueue::with_comparator(MinOrder)
+    }
+
+    pub fn from_vec_min(data: Vec<T>) -> PriorityQueue<T, MinOrder> {
+        PriorityQueue::from_vec_with(data, MinOrder)
+    }
+}
+
+impl<T, C> PriorityQueue<T, C> where C: Compare<T> {
+    pub fn with_comparator(comparator: C) -> PriorityQueue<T, C> {
+        PriorityQueue {
+            data: Vec::new(),
+            comparator,
+        }
+    }
+
+    pub fn from_vec_with(data: Vec<T>, comparator: C) -> PriorityQueue<T, C> {
+        let mut queue = PriorityQueue { data, comparator };
+        queue.heapify();
+        queue
+    }
+
+    pub fn size(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.data.iter()
+    }
+
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, C>> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(PeekMut { queue: self })
+        }
+    }
+
+    pub fn merge(&mut self, other: &mut PriorityQueue<T, C>) {
+        if other.size() > self.size() {
+            mem::swap(&mut self.data, &mut other.data);
+        }
+
+        let appended = other.data.len();
+        self.data.append(&mut other.data);
+
+        // Sifting up every appended element costs O(m log(n + m)),
+        // rebuilding the whole heap costs O(n + m), pick whichever is cheaper.
+        let size = self.data.len();
+        if appended * log2(size) > size {
+            self.heapify();
+        } else {
+            for index in size - appended..size {
+                self.sift_up(index);
+            }
+        }
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut end = self.data.len();
+        while end > 1 {
+            end -= 1;
+            self.data.swap(0, end);
+            self.sift_down(0, end);
+        }
+        self.data
+    }
+
+    fn greater(&self, left: usize, right: usize) -> bool {
+        self.comparator.compare(&self.data[left], &self.data[right]) == Ordering::Greater
+    }
+
+    fn heapify(&mut self) {
+        let size = self.data.len();
+        for index in (0..size / 2).rev() {
+            self.sift_down(index, size);
+        }
+    }
+
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent = (index - 1) / 2;
+            if !self.greater(index, parent) {
+                break;
+            }
+            self.data.swap(index, parent);
+            index = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut index: usize, end: usize) {
+        loop {
+            let left = 2 * index + 1;
+            let right = left + 1;
+
+            let mut largest = index;
+            if left < end && self.greater(left, largest) {
+                largest = left;
+            }
+            if right < end && self.greater(right, largest) {
+                largest = right;
+            }
+
+            if largest == index {
+                break;
+            }
+            self.data.swap(index, largest);
+            index = largest;
+        }
+    }
+}
+
+fn log2(value: usize) -> usize {
+    (mem::size_of::<usize>() * 8) - value.leading_zeros() as usize
+}
+
+impl<T, C> Queue<T> for PriorityQueue<T, C> where C: Compare<T> {
+    fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    fn enqueue(&mut self, value: T) {
+        self.data.push(value);
+        self.sift_up(self.data.len() - 1);
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        let last = self.data.pop()?;
+        if self.data.is_empty() {
+            return Some(last);
+        }
+
+        let top = mem::replace(&mut self.data[0], last);
+        let size = self.data.len();
+        self.sift_down(0, size);
+        Some(top)
+    }
+}
+
+impl<'a, T, C> PeekMut<'a, T, C> where C: Compare<T> {
+    pub fn pop(this: PeekMut<'a, T, C>) -> T {
+        this.queue.dequeue().unwrap()
+    }
+}
+
+impl<'a, T, C> Deref for PeekMut<'a, T, C> where C: Compare<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.queue.data[0]
+    }
+}
+
+impl<'a, T, C> DerefMut for PeekMut<'a, T, C> where C: Compare<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.queue.data[0]
+    }
+}
+
+impl<'a, T, C> Drop for PeekMut<'a, T, C> where C: Compare<T> {
+    fn drop(&mut self) {
+        let size = self.queue.data.len();
+        self.queue.sift_down(0, size);
+    }
+}
+
+impl<T, C> Debug for PriorityQueue<T, C> where T: Debug {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.data.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain<T, C>(queue: &mut PriorityQueue<T, C>) -> Vec<T> where C: Compare<T> {
+        let mut result = Vec::new();
+        while let Some(value) = queue.dequeue() {
+            result.push(value);
+        }
+        result
+    }
+
+    #[test]
+    fn create_new() {
+        let queue = PriorityQueue::<i32>::new();
+        assert_eq!(0, queue.size());
+        assert!(queue.is_empty());
+        assert_eq!(None, queue.peek());
+    }
+
+    #[test]
+    fn max_order() {
+        let mut queue = PriorityQueue::new();
+        for value in crate::helpers::crate_random_vector() {
+            queue.enqueue(value);
+        }
+        let mut expected = queue.iter().copied().collect::<Vec<_>>();
+        expected.sort_by(|a, b| b.cmp(a));
+        assert_eq!(Some(&expected[0]), queue.peek());
+        assert_eq!(expected, drain(&mut queue));
+    }
+
+    #[test]
+    fn min_order() {
+        let mut queue = PriorityQueue::from_vec_min(vec![5, 1, 8, 3, 9, 2]);
+        assert_eq!(Some(&1), queue.peek());
+        assert_eq!(vec![1, 2, 3, 5, 8, 9], drain(&mut queue));
+    }
+
+    #[test]
+    fn custom_comparator() {
+        let by_length = |left: &&str, right: &&str| left.len().cmp(&right.len());
+        let mut queue = PriorityQueue::from_vec_with(vec!["ccc", "a", "dddd", "bb"], by_length);
+        assert_eq!(Some("dddd"), queue.dequeue());
+        assert_eq!(Some("ccc"), queue.dequeue());
+    }
+
+    #[test]
+    fn into_sorted_vec() {
+        let queue = PriorityQueue::from_vec(vec![4, 7, 1, 9, 3, 3, 0]);
+        assert_eq!(vec![0, 1, 3, 3, 4, 7, 9], queue.into_sorted_vec());
+
+        let queue = PriorityQueue::from_vec_min(vec![4, 7, 1, 9]);
+        assert_eq!(vec![9, 7, 4, 1], queue.into_sorted_vec());
+    }
+
+    #[test]
+    fn peek_mut() {
+        let mut queue = PriorityQueue::from_vec(vec![10, 5, 7]);
+        if let Some(mut top) = queue.peek_mut() {
+            *top = 1;
+        }
+        assert_eq!(Some(&7), queue.peek());
+
+        let top = queue.peek_mut().unwrap();
+        assert_eq!(7, PeekMut::pop(top));
+        assert_eq!(vec![5, 1], drain(&mut queue));
+    }
+
+    #[test]
+    fn merge() {
+        let mut left = PriorityQueue::from_vec(vec![1, 8, 4]);
+        let mut right = PriorityQueue::from_vec((0..20).collect());
+        left.merge(&mut right);
+        assert!(right.is_empty());
+        assert_eq!(23, left.size());
+
+        let mut small = PriorityQueue::from_vec(vec![100]);
+        left.merge(&mut small);
+        assert_eq!(Some(&100), left.peek());
+
+        let mut expected = vec![1, 8, 4, 100];
+        expected.extend(0..20);
+        expected.sort_by(|a, b| b.cmp(a));
+        assert_eq!(expected, drain(&mut left));
+    }
+}
@@ -0,0 +1,189 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::helpers::{comparisons, crate_random_vector, reset_comparisons, Counted};
+use crate::structures::types::*;
+
+use super::fibonacci_heap::FibonacciHeap;
+use super::indexed_heap::IndexedHeap;
+use super::pairing_heap::PairingHeap;
+
+const SIZE: usize = 1 << 12;
+
+fn drain<H>(heap: &mut H) -> Vec<i32> where H: AddressableHeap<i32> {
+    let mut result = Vec::new();
+    while let Some(value) = heap.dequeue() {
+        result.push(value);
+    }
+    result
+}
+
+fn log2(value: usize) -> usize {
+    (usize::BITS - value.leading_zeros()) as usize
+}
+
+fn sorts<H>(mut heap: H) where H: AddressableHeap<i32> {
+    let mut expected = crate_random_vector();
+    for value in expected.iter() {
+        heap.insert(*value);
+    }
+    expected.sort();
+
+    assert_eq!(expected.len(), heap.size());
+    assert_eq!(expected.first(), heap.peek());
+    assert_eq!(expected, drain(&mut heap));
+    assert!(heap.is_empty());
+}
+
+fn changes_keys<H>(mut heap: H) where H: AddressableHeap<i32> {
+    let handles = (0..10).map(|value| heap.insert(value * 10)).collect::<Vec<_>>();
+
+    assert_eq!(Ok(()), heap.decrease_key(&handles[7], -5));
+    assert_eq!(Some(&-5), heap.peek());
+    assert_eq!(Ok(()), heap.increase_key(&handles[7], 75));
+    assert_eq!(Ok(()), heap.increase_key(&handles[0], 1000));
+    assert_eq!(Some(&10), heap.peek());
+    assert_eq!(Some(&75), heap.get(&handles[7]));
+
+    assert_eq!(Err(HeapError::InvalidKey), heap.decrease_key(&handles[3], 31));
+    assert_eq!(Err(HeapError::InvalidKey), heap.increase_key(&handles[3], 29));
+
+    assert_eq!(Ok(40), heap.remove(&handles[4]));
+    assert_eq!(Err(HeapError::InvalidHandle), heap.remove(&handles[4]));
+    assert_eq!(None, heap.get(&handles[4]));
+
+    assert_eq!(Some(10), heap.dequeue());
+    assert_eq!(Err(HeapError::InvalidHandle), heap.decrease_key(&handles[1], 0));
+    assert_eq!(vec![20, 30, 50, 60, 75, 80, 90, 1000], drain(&mut heap));
+}
+
+fn melds<H>(mut left: H, mut right: H) where H: AddressableHeap<i32> {
+    let left_handles = (0..5).map(|value| left.insert(value * 2)).collect::<Vec<_>>();
+    let right_handles = (0..5).map(|value| right.insert(value * 2 + 1)).collect::<Vec<_>>();
+    right.dequeue();
+
+    left.meld(right);
+    assert_eq!(9, left.size());
+    assert_eq!(Some(&0), left.peek());
+
+    assert_eq!(Ok(()), left.decrease_key(&right_handles[4], -1));
+    assert_eq!(Ok(4), left.remove(&left_handles[2]));
+    assert_eq!(Err(HeapError::InvalidHandle), left.remove(&right_handles[0]));
+    assert_eq!(vec![-1, 0, 2, 3, 5, 6, 7, 8], drain(&mut left));
+}
+
+fn rejects_foreign_handles<H>(mut heap: H, mut other: H) where H: AddressableHeap<i32> {
+    heap.insert(1);
+    let foreign = other.insert(2);
+    assert_eq!(Err(HeapError::InvalidHandle), heap.decrease_key(&foreign, 0));
+    assert_eq!(None, heap.get(&foreign));
+}
+
+// Every element gets decreased once in a shuffled order, which exercises the cuts
+// of the tree based heaps. Returns the comparisons spent per phase.
+fn count_operations<H>(mut heap: H) -> (usize, usize, usize) where H: AddressableHeap<Counted<usize>> {
+    reset_comparisons();
+    let handles = (0..SIZE).map(|value| heap.insert(Counted(SIZE + value))).collect::<Vec<_>>();
+    let inserts = comparisons();
+
+    heap.dequeue();
+    let mut order = (1..SIZE).collect::<Vec<_>>();
+    order.shuffle(&mut StdRng::seed_from_u64(SIZE as u64));
+
+    reset_comparisons();
+    for value in order {
+        heap.decrease_key(&handles[value], Counted(value)).unwrap();
+    }
+    let decreases = comparisons();
+
+    reset_comparisons();
+    let mut previous = 0;
+    while let Some(Counted(value)) = heap.dequeue() {
+        assert!(previous <= value);
+        previous = value;
+    }
+    let removals = comparisons();
+
+    (inserts, decreases, removals)
+}
+
+// Melds a heap of `size` elements into another one and returns the comparisons it took.
+// The elements of the absorbed heap have to stay where they are, copying them would be O(m).
+fn meld_operations<H>(mut heap: H, mut other: H, size: usize) -> usize where H: AddressableHeap<Counted<usize>> {
+    for value in 0..size {
+        heap.insert(Counted(value));
+    }
+    let handles = (0..size).map(|value| other.insert(Counted(size + value))).collect::<Vec<_>>();
+    let before = handles.iter().map(|handle| other.get(handle).unwrap() as *const _).collect::<Vec<_>>();
+
+    reset_comparisons();
+    heap.meld(other);
+    let melds = comparisons();
+
+    let after = handles.iter().map(|handle| heap.get(handle).unwrap() as *const _).collect::<Vec<_>>();
+    assert_eq!(before, after);
+    assert_eq!(2 * size, heap.size());
+    melds
+}
+
+#[test]
+fn indexed_heap() {
+    sorts(IndexedHeap::<i32>::new());
+    sorts(IndexedHeap::<i32, 4>::new());
+    changes_keys(IndexedHeap::<i32, 3>::new());
+    melds(IndexedHeap::<i32>::new(), IndexedHeap::new());
+    rejects_foreign_handles(IndexedHeap::<i32>::new(), IndexedHeap::new());
+}
+
+#[test]
+fn pairing_heap() {
+    sorts(PairingHeap::new());
+    changes_keys(PairingHeap::new());
+    melds(PairingHeap::new(), PairingHeap::new());
+    rejects_foreign_handles(PairingHeap::new(), PairingHeap::new());
+}
+
+#[test]
+fn fibonacci_heap() {
+    sorts(FibonacciHeap::new());
+    changes_keys(FibonacciHeap::new());
+    melds(FibonacciHeap::new(), FibonacciHeap::new());
+    rejects_foreign_handles(FibonacciHeap::new(), FibonacciHeap::new());
+}
+
+#[test]
+fn indexed_heap_bounds() {
+    let (inserts, decreases, removals) = count_operations(IndexedHeap::<_, 4>::new());
+    println!("\nIndexed   : insert {}, decrease_key {}, dequeue {}", inserts, decreases, removals);
+
+    assert!(inserts <= SIZE * log2(SIZE));
+    assert!(decreases <= SIZE * log2(SIZE));
+    assert!(removals <= 4 * SIZE * log2(SIZE));
+}
+
+#[test]
+fn pairing_heap_bounds() {
+    let (inserts, decreases, removals) = count_operations(PairingHeap::new());
+    println!("\nPairing   : insert {}, decrease_key {}, dequeue {}", inserts, decreases, removals);
+
+    assert!(inserts <= SIZE);
+    assert!(decreases <= 2 * SIZE);
+    assert!(removals <= 3 * SIZE * log2(SIZE));
+
+    assert!(meld_operations(PairingHeap::new(), PairingHeap::new(), 1) <= 1);
+    assert!(meld_operations(PairingHeap::new(), PairingHeap::new(), SIZE) <= 1);
+}
+
+#[test]
+fn fibonacci_heap_bounds() {
+    let (inserts, decreases, removals) = count_operations(FibonacciHeap::new());
+    println!("\nFibonacci : insert {}, decrease_key {}, dequeue {}", inserts, decreases, removals);
+
+    assert!(inserts <= SIZE);
+    assert!(decreases <= 5 * SIZE);
+    assert!(removals <= 3 * SIZE * log2(SIZE));
+
+    assert!(meld_operations(FibonacciHeap::new(), FibonacciHeap::new(), 1) <= 1);
+    assert!(meld_operations(FibonacciHeap::new(), FibonacciHeap::new(), SIZE) <= 1);
+}
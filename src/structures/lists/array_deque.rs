@@ -0,0 +1,494 @@
+use std::iter::{Chain, FromIterator};
+use std::slice;
+
+use crate::structures::types::*;
+
+const MIN_CAPACITY: usize = 4;
+
+// Ring buffer whose capacity is always a power of two, so wrapping an index is a single mask.
+// When `limit` is set the deque never grows past it and pushing into a full deque
+// overwrites the element at the opposite end.
+#[derive(Debug)]
+pub struct ArrayDeque<T> {
+    buffer: Vec<Option<T>>,
+    head: usize,
+    size: usize,
+    limit: Option<usize>,
+}
+
+pub struct Iter<'a, T> {
+    inner: Chain<slice::Iter<'a, Option<T>>, slice::Iter<'a, Option<T>>>,
+}
+
+pub struct IterMut<'a, T> {
+    inner: Chain<slice::IterMut<'a, Option<T>>, slice::IterMut<'a, Option<T>>>,
+}
+
+pub struct IntoIter<T> {
+    deque: ArrayDeque<T>,
+}
+
+fn allocate<T>(capacity: usize) -> Vec<Option<T>> {
+    (0..capacity).map(|_| None).collect()
+}
+
+impl<T> ArrayDeque<T> {
+    pub fn new() -> ArrayDeque<T> {
+        ArrayDeque::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> ArrayDeque<T> {
+        ArrayDeque {
+            buffer: allocate(capacity.max(MIN_CAPACITY).next_power_of_two()),
+            head: 0,
+            size: 0,
+            limit: None,
+        }
+    }
+
+    pub fn overwriting(capacity: usize) -> ArrayDeque<T> {
+        assert!(capacity > 0, "overwriting deque requires a non zero capacity");
+
+        let mut deque = ArrayDeque::with_capacity(capacity);
+        deque.limit = Some(capacity);
+        deque
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.limit.unwrap_or(self.buffer.len())
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.size == self.capacity()
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (front, back) = self.as_slices();
+        Iter {
+            inner: front.iter().chain(back.iter()),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let (front, back) = self.as_mut_slices();
+        IterMut {
+            inner: front.iter_mut().chain(back.iter_mut()),
+        }
+    }
+
+    fn mask(&self) -> usize {
+        self.buffer.len() - 1
+    }
+
+    fn physical(&self, index: usize) -> usize {
+        (self.head + index) & self.mask()
+    }
+
+    fn as_slices(&self) -> (&[Option<T>], &[Option<T>]) {
+        let end = self.head + self.size;
+        if end <= self.buffer.len() {
+            (&self.buffer[self.head..end], &[])
+        } else {
+            let (wrapped, front) = self.buffer.split_at(self.head);
+            (front, &wrapped[..end - self.buffer.len()])
+        }
+    }
+
+    fn as_mut_slices(&mut self) -> (&mut [Option<T>], &mut [Option<T>]) {
+        let end = self.head + self.size;
+        let capacity = self.buffer.len();
+        if end <= capacity {
+            (&mut self.buffer[self.head..end], &mut [])
+        } else {
+            let (wrapped, front) = self.buffer.split_at_mut(self.head);
+            (front, &mut wrapped[..end - capacity])
+        }
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        let (i, j) = (self.physical(i), self.physical(j));
+        self.buffer.swap(i, j);
+    }
+
+    // Makes room for one more element, returns the element evicted in overwrite mode.
+    fn reserve_one(&mut self, evict_front: bool) -> Option<T> {
+        if self.limit.is_some() && self.is_full() {
+            return if evict_front { self.pop_front() } else { self.pop_back() };
+        }
+
+        if self.size == self.buffer.len() {
+            self.grow();
+        }
+        None
+    }
+
+    fn grow(&mut self) {
+        let mut buffer = allocate(self.buffer.len() * 2);
+        for (i, slot) in buffer.iter_mut().enumerate().take(self.size) {
+            let index = self.physical(i);
+            *slot = self.buffer[index].take();
+        }
+        self.buffer = buffer;
+        self.head = 0;
+    }
+}
+
+impl<T> Deque<T> for ArrayDeque<T> {
+    fn peek_front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    fn peek_back(&self) -> Option<&T> {
+        self.get(self.size.checked_sub(1)?)
+    }
+
+    fn push_front(&mut self, value: T) {
+        self.reserve_one(false);
+        self.head = self.head.wrapping_sub(1) & self.mask();
+        self.buffer[self.head] = Some(value);
+        self.size += 1;
+    }
+
+    fn push_back(&mut self, value: T) {
+        self.reserve_one(true);
+        let index = self.physical(self.size);
+        self.buffer[index] = Some(value);
+        self.size += 1;
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        if self.size == 0 {
+            return None;
+        }
+
+        let value = self.buffer[self.head].take();
+        self.head = self.physical(1);
+        self.size -= 1;
+        value
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        if self.size == 0 {
+            return None;
+        }
+
+        self.size -= 1;
+        let index = self.physical(self.size);
+        self.buffer[index].take()
+    }
+}
+
+impl<T> Queue<T> for ArrayDeque<T> {
+    fn peek(&self) -> Option<&T> {
+        self.peek_front()
+    }
+
+    fn enqueue(&mut self, value: T) {
+        self.push_back(value);
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T> ReadonlyStack<T> for ArrayDeque<T> {
+    fn peek(&self) -> Option<&T> {
+        self.peek_back()
+    }
+}
+
+impl<T> Stack<T> for ArrayDeque<T> {
+    fn push(&mut self, value: T) {
+        self.push_back(value);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop_back()
+    }
+}
+
+impl<T> ReadonlySeq<T> for ArrayDeque<T> {
+    fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.size {
+            return None;
+        }
+
+        self.buffer[self.physical(index)].as_ref()
+    }
+
+    fn index_of(&self, value: &T) -> Option<usize> where T: Eq {
+        self.iter().position(|current| current.eq(value))
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+}
+
+impl<T> Seq<T> for ArrayDeque<T> {
+    fn insert(&mut self, index: usize, value: T) -> Result<(), SeqError> {
+        if index > self.size {
+            return Err(SeqError::OutOfBound { index, size: self.size });
+        }
+
+        let mut index = index;
+        // A full deque in overwrite mode drops the element furthest from the new one, the back
+        // when inserting at the front as `push_front` does and the front otherwise.
+        if self.limit.is_some() && self.is_full() {
+            if index == 0 {
+                self.pop_back();
+            } else {
+                self.pop_front();
+                index -= 1;
+            }
+        }
+
+        self.push_back(value);
+        for i in (index..self.size - 1).rev() {
+            self.swap(i, i + 1);
+        }
+        Ok(())
+    }
+
+    fn remove_at(&mut self, index: usize) -> Option<T> {
+        if index >= self.size {
+            return None;
+        }
+
+        for i in index..self.size - 1 {
+            self.swap(i, i + 1);
+        }
+        self.pop_back()
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.size {
+            return None;
+        }
+
+        let index = self.physical(index);
+        self.buffer[index].as_mut()
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().and_then(Option::as_ref)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().and_then(Option::as_ref)
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().and_then(Option::as_mut)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().and_then(Option::as_mut)
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.deque.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.deque.size, Some(self.deque.size))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.deque.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for ArrayDeque<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { deque: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ArrayDeque<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut ArrayDeque<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> Extend<T> for ArrayDeque<T> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push_back(value);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for ArrayDeque<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut deque = ArrayDeque::new();
+        deque.extend(iter);
+        deque
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(deque: &ArrayDeque<i32>) -> Vec<i32> {
+        deque.iter().copied().collect()
+    }
+
+    #[test]
+    fn create_new() {
+        let deque = ArrayDeque::<i32>::new();
+        assert_eq!(0, deque.size());
+        assert_eq!(MIN_CAPACITY, deque.capacity());
+        assert_eq!(None, deque.peek_back());
+        assert_eq!(8, ArrayDeque::<i32>::with_capacity(5).capacity());
+    }
+
+    #[test]
+    fn deque() {
+        let mut deque = ArrayDeque::new();
+        for i in 0..10 {
+            deque.push_back(i);
+            deque.push_front(-i);
+        }
+        assert_eq!(20, deque.size());
+        assert_eq!(32, deque.capacity());
+        assert_eq!(Some(&-9), deque.peek_front());
+        assert_eq!(Some(&9), deque.peek_back());
+        assert_eq!(Some(&0), deque.get(9));
+        assert_eq!(Some(&1), deque.get(11));
+        assert_eq!(Some(9), deque.pop_back());
+        assert_eq!(Some(-9), deque.pop_front());
+        assert_eq!(18, deque.size());
+
+        let mut deque: ArrayDeque<i32> = (1..=2).collect();
+        assert_eq!(Ok(2), deque.try_pop_back());
+        assert_eq!(Ok(1), deque.try_pop_front());
+        assert_eq!(Err(SeqError::Empty), deque.try_pop_front());
+        assert_eq!(Err(SeqError::Empty), deque.try_pop_back());
+    }
+
+    #[test]
+    fn queue_and_stack() {
+        let mut deque = ArrayDeque::new();
+        deque.enqueue(1);
+        deque.enqueue(2);
+        deque.push(3);
+        assert_eq!(Some(&1), Queue::peek(&deque));
+        assert_eq!(Some(&3), ReadonlyStack::peek(&deque));
+        assert_eq!(Some(3), deque.pop());
+        assert_eq!(Some(1), deque.dequeue());
+        assert_eq!(Some(2), deque.dequeue());
+        assert_eq!(None, deque.pop());
+    }
+
+    #[test]
+    fn seq() {
+        let mut deque = ArrayDeque::new();
+        deque.extend(vec![3, 4]);
+        deque.push_front(1);
+        assert_eq!(Ok(()), deque.insert(1, 2));
+        assert_eq!(Ok(()), deque.insert(4, 5));
+        assert_eq!(Err(SeqError::OutOfBound { index: 6, size: 5 }), deque.insert(6, 6));
+        assert_eq!(vec![1, 2, 3, 4, 5], collect(&deque));
+        assert_eq!(Some(2usize), deque.index_of(&3));
+        assert_eq!(Some(3), deque.remove_at(2));
+        *deque.get_mut(0).unwrap() = 0;
+        assert_eq!(vec![0, 2, 4, 5], collect(&deque));
+        assert_eq!(None, deque.remove_at(4));
+    }
+
+    #[test]
+    fn overwriting() {
+        let mut deque = ArrayDeque::overwriting(3);
+        deque.extend(1..=5);
+        assert!(deque.is_full());
+        assert_eq!(vec![3, 4, 5], collect(&deque));
+        deque.push_front(2);
+        assert_eq!(vec![2, 3, 4], collect(&deque));
+        assert_eq!(Ok(()), deque.insert(2, 10));
+        assert_eq!(vec![3, 10, 4], collect(&deque));
+        assert_eq!(3, deque.capacity());
+    }
+
+    #[test]
+    fn overwriting_insert() {
+        let mut deque: ArrayDeque<i32> = ArrayDeque::overwriting(3);
+        deque.extend(1..=3);
+        assert_eq!(Ok(()), deque.insert(0, 10));
+        assert_eq!(vec![10, 1, 2], collect(&deque));
+        assert_eq!(Ok(()), deque.insert(1, 20));
+        assert_eq!(vec![20, 1, 2], collect(&deque));
+        assert_eq!(Ok(()), deque.insert(3, 30));
+        assert_eq!(vec![1, 2, 30], collect(&deque));
+        assert_eq!(Err(SeqError::OutOfBound { index: 4, size: 3 }), deque.insert(4, 40));
+        assert_eq!(3, deque.size());
+    }
+
+    #[test]
+    fn iter() {
+        let mut deque: ArrayDeque<i32> = (1..=3).collect();
+        deque.push_front(0);
+        deque.push_front(-1);
+        for value in &mut deque {
+            *value *= 2;
+        }
+        let mut iter = deque.iter();
+        assert_eq!(5, iter.len());
+        assert_eq!(Some(&-2), iter.next());
+        assert_eq!(Some(&6), iter.next_back());
+        assert_eq!(vec![-2, 0, 2, 4, 6], deque.into_iter().collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,870 @@
+use std::fmt::{self, Debug, Formatter};
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr::NonNull;
+
+use crate::structures::types::*;
+
+type Link<T> = Option<NonNull<Node<T>>>;
+
+struct Node<T> {
+    value: T,
+    prev: Link<T>,
+    next: Link<T>,
+}
+
+pub struct DoublyLinkedList<T> {
+    head: Link<T>,
+    tail: Link<T>,
+    size: usize,
+    marker: PhantomData<Box<Node<T>>>,
+}
+
+pub struct Iter<'a, T> {
+    head: Link<T>,
+    tail: Link<T>,
+    size: usize,
+    marker: PhantomData<&'a Node<T>>,
+}
+
+pub struct IterMut<'a, T> {
+    head: Link<T>,
+    tail: Link<T>,
+    size: usize,
+    marker: PhantomData<&'a mut Node<T>>,
+}
+
+pub struct IntoIter<T> {
+    list: DoublyLinkedList<T>,
+}
+
+// The cursor sits either on an element or on the "ghost" position between the tail and the head,
+// in which case `current` is `None` and `index` equals the size of the list.
+pub struct Cursor<'a, T> {
+    index: usize,
+    current: Link<T>,
+    list: &'a DoublyLinkedList<T>,
+}
+
+pub struct CursorMut<'a, T> {
+    index: usize,
+    current: Link<T>,
+    list: &'a mut DoublyLinkedList<T>,
+}
+
+unsafe impl<T: Send> Send for DoublyLinkedList<T> {}
+
+unsafe impl<T: Sync> Sync for DoublyLinkedList<T> {}
+
+impl<T> Node<T> {
+    fn new_link(value: T) -> NonNull<Node<T>> {
+        NonNull::from(Box::leak(Box::new(Node {
+            value,
+            prev: None,
+            next: None,
+        })))
+    }
+}
+
+impl<T> DoublyLinkedList<T> {
+    pub fn new() -> DoublyLinkedList<T> {
+        DoublyLinkedList {
+            head: None,
+            tail: None,
+            size: 0,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn from_value(value: T) -> DoublyLinkedList<T> {
+        let mut list = DoublyLinkedList::new();
+        list.push_back(value);
+        list
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            size: self.size,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            head: self.head,
+            tail: self.tail,
+            size: self.size,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn cursor_front(&self) -> Cursor<'_, T> {
+        Cursor {
+            index: 0,
+            current: self.head,
+            list: self,
+        }
+    }
+
+    pub fn cursor_back(&self) -> Cursor<'_, T> {
+        Cursor {
+            index: self.size.saturating_sub(1),
+            current: self.tail,
+            list: self,
+        }
+    }
+
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            index: 0,
+            current: self.head,
+            list: self,
+        }
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            index: self.size.saturating_sub(1),
+            current: self.tail,
+            list: self,
+        }
+    }
+
+    pub fn append(&mut self, other: &mut DoublyLinkedList<T>) {
+        let other = mem::replace(other, DoublyLinkedList::new());
+        unsafe { self.splice_between(self.tail, None, other) }
+    }
+
+    fn node_at(&self, index: usize) -> Link<T> {
+        if index >= self.size {
+            return None;
+        }
+
+        unsafe {
+            if index < self.size / 2 {
+                let mut current = self.head;
+                for _ in 0..index {
+                    current = (*current?.as_ptr()).next;
+                }
+                current
+            } else {
+                let mut current = self.tail;
+                for _ in index + 1..self.size {
+                    current = (*current?.as_ptr()).prev;
+                }
+                current
+            }
+        }
+    }
+
+    unsafe fn link_between(&mut self, node: NonNull<Node<T>>, prev: Link<T>, next: Link<T>) {
+        (*node.as_ptr()).prev = prev;
+        (*node.as_ptr()).next = next;
+
+        match prev {
+            Some(prev) => (*prev.as_ptr()).next = Some(node),
+            None => self.head = Some(node),
+        }
+        match next {
+            Some(next) => (*next.as_ptr()).prev = Some(node),
+            None => self.tail = Some(node),
+        }
+
+        self.size += 1;
+    }
+
+    unsafe fn unlink(&mut self, node: NonNull<Node<T>>) -> Box<Node<T>> {
+        let node = Box::from_raw(node.as_ptr());
+
+        match node.prev {
+            Some(prev) => (*prev.as_ptr()).next = node.next,
+            None => self.head = node.next,
+        }
+        match node.next {
+            Some(next) => (*next.as_ptr()).prev = node.prev,
+            None => self.tail = node.prev,
+        }
+
+        self.size -= 1;
+        node
+    }
+
+    unsafe fn splice_between(&mut self, prev: Link<T>, next: Link<T>, mut other: DoublyLinkedList<T>) {
+        let (first, last) = match (other.head.take(), other.tail.take()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return,
+        };
+
+        (*first.as_ptr()).prev = prev;
+        (*last.as_ptr()).next = next;
+
+        match prev {
+            Some(prev) => (*prev.as_ptr()).next = Some(first),
+            None => self.head = Some(first),
+        }
+        match next {
+            Some(next) => (*next.as_ptr()).prev = Some(last),
+            None => self.tail = Some(last),
+        }
+
+        self.size += mem::replace(&mut other.size, 0);
+    }
+
+    // Detaches every node after `node` (the whole list when `node` is `None`),
+    // `count` is the number of detached nodes.
+    unsafe fn split_after_node(&mut self, node: Link<T>, count: usize) -> DoublyLinkedList<T> {
+        let node = match node {
+            Some(node) => node,
+            None => return mem::replace(self, DoublyLinkedList::new()),
+        };
+
+        let first = match (*node.as_ptr()).next.take() {
+            Some(first) => first,
+            None => return DoublyLinkedList::new(),
+        };
+        (*first.as_ptr()).prev = None;
+
+        let last = self.tail.replace(node);
+        self.size -= count;
+
+        DoublyLinkedList {
+            head: Some(first),
+            tail: last,
+            size: count,
+            marker: PhantomData,
+        }
+    }
+
+    // Detaches every node before `node` (the whole list when `node` is `None`),
+    // `count` is the number of detached nodes.
+    unsafe fn split_before_node(&mut self, node: Link<T>, count: usize) -> DoublyLinkedList<T> {
+        let node = match node {
+            Some(node) => node,
+            None => return mem::replace(self, DoublyLinkedList::new()),
+        };
+
+        let last = match (*node.as_ptr()).prev.take() {
+            Some(last) => last,
+            None => return DoublyLinkedList::new(),
+        };
+        (*last.as_ptr()).next = None;
+
+        let first = self.head.replace(node);
+        self.size -= count;
+
+        DoublyLinkedList {
+            head: first,
+            tail: Some(last),
+            size: count,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Deque<T> for DoublyLinkedList<T> {
+    fn peek_front(&self) -> Option<&T> {
+        self.head.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    fn peek_back(&self) -> Option<&T> {
+        self.tail.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    fn push_front(&mut self, value: T) {
+        unsafe { self.link_between(Node::new_link(value), None, self.head) }
+    }
+
+    fn push_back(&mut self, value: T) {
+        unsafe { self.link_between(Node::new_link(value), self.tail, None) }
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.head.map(|node| unsafe { self.unlink(node).value })
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        self.tail.map(|node| unsafe { self.unlink(node).value })
+    }
+}
+
+impl<T> Queue<T> for DoublyLinkedList<T> {
+    fn peek(&self) -> Option<&T> {
+        self.peek_front()
+    }
+
+    fn enqueue(&mut self, value: T) {
+        self.push_back(value);
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T> ReadonlyStack<T> for DoublyLinkedList<T> {
+    fn peek(&self) -> Option<&T> {
+        self.peek_front()
+    }
+}
+
+impl<T> Stack<T> for DoublyLinkedList<T> {
+    fn push(&mut self, value: T) {
+        self.push_front(value);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T> ReadonlySeq<T> for DoublyLinkedList<T> {
+    fn get(&self, index: usize) -> Option<&T> {
+        self.node_at(index).map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    fn index_of(&self, value: &T) -> Option<usize> where T: Eq {
+        self.iter().position(|current| current.eq(value))
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+}
+
+impl<T> Seq<T> for DoublyLinkedList<T> {
+    fn insert(&mut self, index: usize, value: T) -> Result<(), SeqError> {
+        if index > self.size {
+            return Err(SeqError::OutOfBound { index, size: self.size });
+        }
+
+        let next = self.node_at(index);
+        let prev = match next {
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+            None => self.tail,
+        };
+        unsafe { self.link_between(Node::new_link(value), prev, next) }
+        Ok(())
+    }
+
+    fn remove_at(&mut self, index: usize) -> Option<T> {
+        self.node_at(index).map(|node| unsafe { self.unlink(node).value })
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.node_at(index).map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+}
+
+impl<'a, T> Cursor<'a, T> {
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    pub fn move_next(&mut self) {
+        match self.current {
+            Some(node) => {
+                self.current = unsafe { (*node.as_ptr()).next };
+                self.index += 1;
+            }
+            None => {
+                self.current = self.list.head;
+                self.index = 0;
+            }
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        match self.current {
+            Some(node) => {
+                self.current = unsafe { (*node.as_ptr()).prev };
+                self.index = self.index.checked_sub(1).unwrap_or(self.list.size);
+            }
+            None => {
+                self.current = self.list.tail;
+                self.index = self.list.size.saturating_sub(1);
+            }
+        }
+    }
+
+    pub fn current(&self) -> Option<&'a T> {
+        self.current.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    pub fn peek_next(&self) -> Option<&'a T> {
+        let next = match self.current {
+            Some(node) => unsafe { (*node.as_ptr()).next },
+            None => self.list.head,
+        };
+        next.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        let prev = match self.current {
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+            None => self.list.tail,
+        };
+        prev.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    pub fn move_next(&mut self) {
+        match self.current {
+            Some(node) => {
+                self.current = unsafe { (*node.as_ptr()).next };
+                self.index += 1;
+            }
+            None => {
+                self.current = self.list.head;
+                self.index = 0;
+            }
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        match self.current {
+            Some(node) => {
+                self.current = unsafe { (*node.as_ptr()).prev };
+                self.index = self.index.checked_sub(1).unwrap_or(self.list.size);
+            }
+            None => {
+                self.current = self.list.tail;
+                self.index = self.list.size.saturating_sub(1);
+            }
+        }
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.current.map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = match self.current {
+            Some(node) => unsafe { (*node.as_ptr()).next },
+            None => self.list.head,
+        };
+        next.map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let prev = match self.current {
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+            None => self.list.tail,
+        };
+        prev.map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    pub fn as_cursor(&self) -> Cursor<'_, T> {
+        Cursor {
+            index: self.index,
+            current: self.current,
+            list: self.list,
+        }
+    }
+
+    pub fn insert_after(&mut self, value: T) {
+        let node = Node::new_link(value);
+        match self.current {
+            Some(current) => unsafe {
+                let next = (*current.as_ptr()).next;
+                self.list.link_between(node, Some(current), next);
+            },
+            None => unsafe {
+                let head = self.list.head;
+                self.list.link_between(node, None, head);
+                self.index = self.list.size;
+            },
+        }
+    }
+
+    pub fn insert_before(&mut self, value: T) {
+        let node = Node::new_link(value);
+        match self.current {
+            Some(current) => unsafe {
+                let prev = (*current.as_ptr()).prev;
+                self.list.link_between(node, prev, Some(current));
+            },
+            None => unsafe {
+                let tail = self.list.tail;
+                self.list.link_between(node, tail, None);
+            },
+        }
+        self.index += 1;
+    }
+
+    pub fn remove_current(&mut self) -> Option<T> {
+        let current = self.current?;
+        unsafe {
+            self.current = (*current.as_ptr()).next;
+            Some(self.list.unlink(current).value)
+        }
+    }
+
+    pub fn split_after(&mut self) -> DoublyLinkedList<T> {
+        let count = match self.current {
+            Some(_) => self.list.size - self.index - 1,
+            None => {
+                self.index = 0;
+                self.list.size
+            }
+        };
+        unsafe { self.list.split_after_node(self.current, count) }
+    }
+
+    pub fn split_before(&mut self) -> DoublyLinkedList<T> {
+        let count = match self.current {
+            Some(_) => self.index,
+            None => self.list.size,
+        };
+        self.index = 0;
+        unsafe { self.list.split_before_node(self.current, count) }
+    }
+
+    pub fn splice_after(&mut self, list: DoublyLinkedList<T>) {
+        match self.current {
+            Some(current) => unsafe {
+                let next = (*current.as_ptr()).next;
+                self.list.splice_between(Some(current), next, list);
+            },
+            None => unsafe {
+                let head = self.list.head;
+                self.list.splice_between(None, head, list);
+                self.index = self.list.size;
+            },
+        }
+    }
+
+    pub fn splice_before(&mut self, list: DoublyLinkedList<T>) {
+        self.index += list.size;
+        match self.current {
+            Some(current) => unsafe {
+                let prev = (*current.as_ptr()).prev;
+                self.list.splice_between(prev, Some(current), list);
+            },
+            None => unsafe {
+                let tail = self.list.tail;
+                self.list.splice_between(tail, None, list);
+            },
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.size == 0 {
+            return None;
+        }
+
+        self.head.map(|node| unsafe {
+            let node = &*node.as_ptr();
+            self.size -= 1;
+            self.head = node.next;
+            &node.value
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.size == 0 {
+            return None;
+        }
+
+        self.tail.map(|node| unsafe {
+            let node = &*node.as_ptr();
+            self.size -= 1;
+            self.tail = node.prev;
+            &node.value
+        })
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.size == 0 {
+            return None;
+        }
+
+        self.head.map(|node| unsafe {
+            let node = &mut *node.as_ptr();
+            self.size -= 1;
+            self.head = node.next;
+            &mut node.value
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.size == 0 {
+            return None;
+        }
+
+        self.tail.map(|node| unsafe {
+            let node = &mut *node.as_ptr();
+            self.size -= 1;
+            self.tail = node.prev;
+            &mut node.value
+        })
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.size, Some(self.list.size))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for DoublyLinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a DoublyLinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut DoublyLinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> Extend<T> for DoublyLinkedList<T> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push_back(value);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for DoublyLinkedList<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut list = DoublyLinkedList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Debug for DoublyLinkedList<T> where T: Debug {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Drop for DoublyLinkedList<T> {
+    fn drop(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(list: &DoublyLinkedList<i32>) -> Vec<i32> {
+        list.iter().copied().collect()
+    }
+
+    #[test]
+    fn create_new() {
+        let list = DoublyLinkedList::<i32>::new();
+        assert_eq!(0, list.size());
+        assert!(list.is_empty());
+        assert_eq!(None, list.get(0));
+    }
+
+    #[test]
+    fn deque() {
+        let mut list = DoublyLinkedList::new();
+        list.push_back(2);
+        list.push_front(1);
+        list.push_back(3);
+        assert_eq!(Some(&1), list.peek_front());
+        assert_eq!(Some(&3), list.peek_back());
+        assert_eq!(Some(3), list.pop_back());
+        assert_eq!(Some(1), list.pop_front());
+        assert_eq!(Some(2), list.pop_back());
+        assert_eq!(None, list.pop_front());
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn queue_and_stack() {
+        let mut list = DoublyLinkedList::new();
+        list.enqueue(1);
+        list.enqueue(2);
+        list.push(0);
+        assert_eq!(Some(&0), ReadonlyStack::peek(&list));
+        assert_eq!(Some(0), list.pop());
+        assert_eq!(Some(1), list.dequeue());
+        assert_eq!(Some(2), list.dequeue());
+        assert_eq!(None, list.dequeue());
+    }
+
+    #[test]
+    fn seq() {
+        let mut list: DoublyLinkedList<i32> = (0..6).collect();
+        assert_eq!(Ok(()), list.insert(6, 6));
+        assert_eq!(Ok(()), list.insert(0, -1));
+        assert_eq!(Err(SeqError::OutOfBound { index: 9, size: 8 }), list.insert(9, 9));
+        assert_eq!(Some(&5), list.get(6));
+        assert_eq!(Some(&1), list.get(2));
+        assert_eq!(Some(4usize), list.index_of(&3));
+        assert_eq!(Some(3), list.remove_at(4));
+        *list.get_mut(0).unwrap() = 10;
+        assert_eq!(vec![10, 0, 1, 2, 4, 5, 6], collect(&list));
+        assert_eq!(None, list.remove_at(7));
+    }
+
+    #[test]
+    fn iter() {
+        let mut list: DoublyLinkedList<i32> = (1..=5).collect();
+        for value in &mut list {
+            *value *= 2;
+        }
+        let mut iter = list.iter();
+        assert_eq!(Some(&2), iter.next());
+        assert_eq!(Some(&10), iter.next_back());
+        assert_eq!(3, iter.len());
+        assert_eq!(vec![&4, &6, &8], iter.collect::<Vec<_>>());
+        assert_eq!(vec![10, 8, 6, 4, 2], list.into_iter().rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn cursor() {
+        let list: DoublyLinkedList<i32> = (1..=3).collect();
+        let mut cursor = list.cursor_front();
+        assert_eq!(Some(&1), cursor.current());
+        assert_eq!(None, cursor.peek_prev());
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(Some(2), cursor.index());
+        assert_eq!(Some(&3), cursor.current());
+        cursor.move_next();
+        assert_eq!(None, cursor.index());
+        assert_eq!(None, cursor.current());
+        assert_eq!(Some(&1), cursor.peek_next());
+        cursor.move_next();
+        assert_eq!(Some(&1), cursor.current());
+        cursor.move_prev();
+        assert_eq!(None, cursor.current());
+        cursor.move_prev();
+        assert_eq!(Some(&3), cursor.current());
+    }
+
+    #[test]
+    fn cursor_insert_remove() {
+        let mut list: DoublyLinkedList<i32> = (1..=3).collect();
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        cursor.insert_before(10);
+        cursor.insert_after(20);
+        assert_eq!(Some(2), cursor.index());
+        assert_eq!(Some(&mut 2), cursor.current());
+        assert_eq!(Some(2), cursor.remove_current());
+        assert_eq!(Some(&mut 20), cursor.current());
+        cursor.move_prev();
+        cursor.move_prev();
+        cursor.move_prev();
+        cursor.insert_after(0);
+        cursor.insert_before(4);
+        assert_eq!(None, cursor.index());
+        assert_eq!(vec![0, 1, 10, 20, 3, 4], collect(&list));
+        assert_eq!(6, list.size());
+    }
+
+    #[test]
+    fn cursor_split_splice() {
+        let mut list: DoublyLinkedList<i32> = (1..=6).collect();
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        cursor.move_next();
+
+        let tail = cursor.split_after();
+        assert_eq!(vec![4, 5, 6], collect(&tail));
+        let head = cursor.split_before();
+        assert_eq!(vec![1, 2], collect(&head));
+        assert_eq!(Some(0), cursor.index());
+
+        cursor.splice_before(tail);
+        cursor.splice_after(head);
+        assert_eq!(Some(3), cursor.index());
+        assert_eq!(Some(&mut 3), cursor.current());
+        cursor.move_next();
+        assert_eq!(Some(&mut 1), cursor.current());
+
+        assert_eq!(vec![4, 5, 6, 3, 1, 2], collect(&list));
+        assert_eq!(6, list.size());
+        assert_eq!(Some(&1), list.get(4));
+    }
+
+    #[test]
+    fn append() {
+        let mut list: DoublyLinkedList<i32> = (1..=2).collect();
+        let mut other: DoublyLinkedList<i32> = (3..=4).collect();
+        list.append(&mut other);
+        assert!(other.is_empty());
+        assert_eq!(vec![1, 2, 3, 4], collect(&list));
+        assert_eq!(Some(&4), list.peek_back());
+    }
+
+    #[test]
+    fn drop_long() {
+        let list: DoublyLinkedList<i32> = (0..100_000).collect();
+        assert_eq!(100_000, list.size());
+    }
+}
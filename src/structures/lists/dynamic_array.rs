@@ -0,0 +1,452 @@
+use std::alloc::{self, Layout};
+use std::fmt::{self, Debug, Formatter};
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr::{self, NonNull};
+use std::slice;
+
+use crate::structures::types::*;
+
+const MIN_CAPACITY: usize = 4;
+
+pub trait GrowthPolicy {
+    fn grow(&self, capacity: usize) -> Option<usize>;
+
+    fn shrink(&self, _capacity: usize, _size: usize) -> Option<usize> {
+        None
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Doubling;
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct OneAndHalf;
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct GoldenRatio;
+
+// Shrinks the buffer by half once it is only a quarter full, the gap between the two
+// thresholds keeps alternating push/pop from reallocating on every call.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Hysteresis<P> {
+    growth: P,
+}
+
+impl GrowthPolicy for Doubling {
+    fn grow(&self, capacity: usize) -> Option<usize> {
+        capacity.checked_mul(2)
+    }
+}
+
+impl GrowthPolicy for OneAndHalf {
+    fn grow(&self, capacity: usize) -> Option<usize> {
+        capacity.checked_add(capacity / 2)
+    }
+}
+
+impl GrowthPolicy for GoldenRatio {
+    fn grow(&self, capacity: usize) -> Option<usize> {
+        let phi = (1.0 + 5f64.sqrt()) / 2.0;
+        capacity.checked_add((capacity as f64 * (phi - 1.0)) as usize)
+    }
+}
+
+impl<P> Hysteresis<P> {
+    pub fn decorate(growth: P) -> Hysteresis<P> {
+        Hysteresis { growth }
+    }
+}
+
+impl<P> GrowthPolicy for Hysteresis<P> where P: GrowthPolicy {
+    fn grow(&self, capacity: usize) -> Option<usize> {
+        self.growth.grow(capacity)
+    }
+
+    fn shrink(&self, capacity: usize, size: usize) -> Option<usize> {
+        if capacity > MIN_CAPACITY && size <= capacity / 4 {
+            Some((capacity / 2).max(MIN_CAPACITY))
+        } else {
+            None
+        }
+    }
+}
+
+pub struct DynamicArray<T, P = Doubling> {
+    ptr: NonNull<T>,
+    capacity: usize,
+    size: usize,
+    policy: P,
+    marker: PhantomData<T>,
+}
+
+unsafe impl<T: Send, P: Send> Send for DynamicArray<T, P> {}
+
+unsafe impl<T: Sync, P: Sync> Sync for DynamicArray<T, P> {}
+
+impl<T> DynamicArray<T> {
+    pub fn new() -> DynamicArray<T> {
+        DynamicArray::with_policy(Doubling)
+    }
+}
+
+impl<T, P> DynamicArray<T, P> where P: GrowthPolicy {
+    pub fn with_policy(policy: P) -> DynamicArray<T, P> {
+        DynamicArray {
+            ptr: NonNull::dangling(),
+            capacity: if mem::size_of::<T>() == 0 { usize::MAX } else { 0 },
+            size: 0,
+            policy,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.size) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.size) }
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.as_slice().iter()
+    }
+
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, T> {
+        self.as_mut_slice().iter_mut()
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.reserve(1);
+        unsafe { ptr::write(self.ptr.as_ptr().add(self.size), value) }
+        self.size += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.size == 0 {
+            return None;
+        }
+
+        self.size -= 1;
+        let value = unsafe { ptr::read(self.ptr.as_ptr().add(self.size)) };
+        self.shrink_by_policy();
+        Some(value)
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        match self.try_reserve(additional) {
+            Ok(()) => {}
+            Err(SeqError::AllocError { bytes }) =>
+                alloc::handle_alloc_error(Layout::from_size_align(bytes, mem::align_of::<T>()).unwrap()),
+            Err(_) => panic!("capacity overflow"),
+        }
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), SeqError> {
+        let required = self.size.checked_add(additional).ok_or(SeqError::CapacityOverflow)?;
+        if required <= self.capacity {
+            return Ok(());
+        }
+
+        let mut capacity = self.capacity.max(MIN_CAPACITY);
+        while capacity < required {
+            capacity = self.policy.grow(capacity)
+                .filter(|grown| *grown > capacity)
+                .ok_or(SeqError::CapacityOverflow)?;
+        }
+
+        self.reallocate(capacity)
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        if mem::size_of::<T>() != 0 && self.capacity > self.size {
+            // Shrinking never needs more memory than we already own, so a failure is not expected.
+            let _ = self.reallocate(self.size);
+        }
+    }
+
+    fn shrink_by_policy(&mut self) {
+        if mem::size_of::<T>() == 0 {
+            return;
+        }
+
+        if let Some(capacity) = self.policy.shrink(self.capacity, self.size) {
+            let _ = self.reallocate(capacity.max(self.size));
+        }
+    }
+
+    fn reallocate(&mut self, capacity: usize) -> Result<(), SeqError> {
+        let layout = Layout::array::<T>(capacity).map_err(|_| SeqError::CapacityOverflow)?;
+        if layout.size() > isize::MAX as usize {
+            return Err(SeqError::CapacityOverflow);
+        }
+
+        if capacity == 0 {
+            self.deallocate();
+            self.ptr = NonNull::dangling();
+            self.capacity = 0;
+            return Ok(());
+        }
+
+        let ptr = unsafe {
+            if self.capacity == 0 {
+                alloc::alloc(layout)
+            } else {
+                alloc::realloc(self.ptr.as_ptr() as *mut u8, current_layout::<T>(self.capacity), layout.size())
+            }
+        };
+
+        self.ptr = NonNull::new(ptr as *mut T).ok_or(SeqError::AllocError { bytes: layout.size() })?;
+        self.capacity = capacity;
+        Ok(())
+    }
+}
+
+impl<T, P> DynamicArray<T, P> {
+    fn deallocate(&mut self) {
+        if mem::size_of::<T>() != 0 && self.capacity != 0 {
+            unsafe { alloc::dealloc(self.ptr.as_ptr() as *mut u8, current_layout::<T>(self.capacity)) }
+        }
+    }
+}
+
+// The layout of an already allocated buffer was validated when it was allocated.
+fn current_layout<T>(capacity: usize) -> Layout {
+    Layout::array::<T>(capacity).unwrap()
+}
+
+impl<T, P> ReadonlySeq<T> for DynamicArray<T, P> where P: GrowthPolicy {
+    fn get(&self, index: usize) -> Option<&T> {
+        self.as_slice().get(index)
+    }
+
+    fn index_of(&self, value: &T) -> Option<usize> where T: Eq {
+        self.iter().position(|current| current.eq(value))
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+}
+
+impl<T, P> Seq<T> for DynamicArray<T, P> where P: GrowthPolicy {
+    fn insert(&mut self, index: usize, value: T) -> Result<(), SeqError> {
+        if index > self.size {
+            return Err(SeqError::OutOfBound { index, size: self.size });
+        }
+
+        self.try_reserve(1)?;
+        unsafe {
+            let slot = self.ptr.as_ptr().add(index);
+            ptr::copy(slot, slot.add(1), self.size - index);
+            ptr::write(slot, value);
+        }
+        self.size += 1;
+        Ok(())
+    }
+
+    fn remove_at(&mut self, index: usize) -> Option<T> {
+        if index >= self.size {
+            return None;
+        }
+
+        let value = unsafe {
+            let slot = self.ptr.as_ptr().add(index);
+            let value = ptr::read(slot);
+            ptr::copy(slot.add(1), slot, self.size - index - 1);
+            value
+        };
+        self.size -= 1;
+        self.shrink_by_policy();
+        Some(value)
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.as_mut_slice().get_mut(index)
+    }
+}
+
+impl<'a, T, P> IntoIterator for &'a DynamicArray<T, P> where P: GrowthPolicy {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, P> IntoIterator for &'a mut DynamicArray<T, P> where P: GrowthPolicy {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T, P> Extend<T> for DynamicArray<T, P> where P: GrowthPolicy {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for DynamicArray<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut array = DynamicArray::new();
+        array.extend(iter);
+        array
+    }
+}
+
+impl<T, P> Debug for DynamicArray<T, P> where T: Debug, P: GrowthPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, P> Drop for DynamicArray<T, P> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.ptr.as_ptr(), self.size)) }
+        self.deallocate();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    fn capacities<P>(policy: P) -> Vec<usize> where P: GrowthPolicy {
+        let mut array = DynamicArray::with_policy(policy);
+        let mut capacities = Vec::new();
+        for i in 0..1000 {
+            array.push(i);
+            if capacities.last() != Some(&array.capacity()) {
+                capacities.push(array.capacity());
+            }
+        }
+        capacities
+    }
+
+    #[test]
+    fn create_new() {
+        let array = DynamicArray::<i32>::new();
+        assert_eq!(0, array.size());
+        assert_eq!(0, array.capacity());
+        assert_eq!(None, array.get(0));
+    }
+
+    #[test]
+    fn push_pop() {
+        let mut array = DynamicArray::new();
+        for i in 0..100 {
+            array.push(i);
+        }
+        assert_eq!(100, array.size());
+        assert_eq!(128, array.capacity());
+        assert_eq!(Some(&42), array.get(42));
+        assert_eq!(Some(99), array.pop());
+        assert_eq!(99, array.size());
+    }
+
+    #[test]
+    fn seq() {
+        let mut array: DynamicArray<i32> = vec![1, 3, 4].into_iter().collect();
+        assert_eq!(Ok(()), array.insert(1, 2));
+        assert_eq!(Ok(()), array.insert(4, 5));
+        assert_eq!(Err(SeqError::OutOfBound { index: 6, size: 5 }), array.insert(6, 6));
+        assert_eq!(&[1, 2, 3, 4, 5], array.as_slice());
+        assert_eq!(Some(2usize), array.index_of(&3));
+        assert_eq!(Some(1), array.remove_at(0));
+        *array.get_mut(0).unwrap() = 0;
+        assert_eq!(&[0, 3, 4, 5], array.as_slice());
+        assert_eq!(None, array.remove_at(4));
+    }
+
+    #[test]
+    fn growth_policies() {
+        println!("\nDoubling    : {:?}", capacities(Doubling));
+        println!("OneAndHalf  : {:?}", capacities(OneAndHalf));
+        println!("GoldenRatio : {:?}", capacities(GoldenRatio));
+
+        assert_eq!(vec![4, 8, 16, 32, 64, 128, 256, 512, 1024], capacities(Doubling));
+        assert_eq!(vec![4, 6, 9, 13, 19, 28], capacities(OneAndHalf)[..6].to_vec());
+        assert_eq!(vec![4, 6, 9, 14, 22, 35], capacities(GoldenRatio)[..6].to_vec());
+    }
+
+    #[test]
+    fn hysteresis() {
+        let mut array = DynamicArray::with_policy(Hysteresis::decorate(Doubling));
+        array.extend(0..64);
+        assert_eq!(64, array.capacity());
+
+        while array.size() > 17 {
+            array.pop();
+        }
+        assert_eq!(64, array.capacity());
+        array.pop();
+        assert_eq!(32, array.capacity());
+        array.push(16);
+        array.pop();
+        assert_eq!(32, array.capacity());
+
+        while array.pop().is_some() {}
+        assert_eq!(MIN_CAPACITY, array.capacity());
+    }
+
+    #[test]
+    fn try_reserve() {
+        let mut array = DynamicArray::<u64>::new();
+        assert_eq!(Ok(()), array.try_reserve(10));
+        assert_eq!(16, array.capacity());
+        assert_eq!(Err(SeqError::CapacityOverflow), array.try_reserve(usize::MAX));
+        assert_eq!(Err(SeqError::CapacityOverflow), array.try_reserve(usize::MAX / 4));
+        assert_eq!(16, array.capacity());
+    }
+
+    #[test]
+    fn shrink_to_fit() {
+        let mut array: DynamicArray<i32> = (0..10).collect();
+        array.shrink_to_fit();
+        assert_eq!(10, array.capacity());
+        assert_eq!(Some(&9), array.get(9));
+    }
+
+    #[test]
+    fn zero_sized() {
+        let mut array = DynamicArray::new();
+        for _ in 0..10 {
+            array.push(());
+        }
+        assert_eq!(10, array.size());
+        assert_eq!(Some(()), array.pop());
+    }
+
+    #[test]
+    fn drops_elements() {
+        let counter = Rc::new(());
+        let mut array = DynamicArray::new();
+        for _ in 0..10 {
+            array.push(counter.clone());
+        }
+        array.remove_at(3);
+        assert_eq!(10, Rc::strong_count(&counter));
+        drop(array);
+        assert_eq!(1, Rc::strong_count(&counter));
+    }
+}
@@ -1,3 +1,5 @@
+use std::iter::FromIterator;
+
 use crate::structures::types::*;
 
 #[derive(Debug)]
@@ -9,6 +11,21 @@ struct Node<T> {
 #[derive(Debug)]
 pub struct LinkedList<T> {
     head: Option<Box<Node<T>>>,
+    size: usize,
+}
+
+pub struct Iter<'a, T> {
+    next: Option<&'a Node<T>>,
+    size: usize,
+}
+
+pub struct IterMut<'a, T> {
+    next: Option<&'a mut Node<T>>,
+    size: usize,
+}
+
+pub struct IntoIter<T> {
+    list: LinkedList<T>,
 }
 
 impl<T> Node<T> {
@@ -28,12 +45,28 @@ impl<T> LinkedList<T> {
     pub fn new() -> LinkedList<T> {
         LinkedList {
             head: None,
+            size: 0,
         }
     }
 
     pub fn from_value(value: T) -> LinkedList<T> {
         LinkedList {
-            head: Node::new_link(value, None)
+            head: Node::new_link(value, None),
+            size: 1,
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head.as_deref(),
+            size: self.size,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            next: self.head.as_deref_mut(),
+            size: self.size,
         }
     }
 }
@@ -53,6 +86,7 @@ impl<T> Queue<T> for LinkedList<T> {
         }
 
         *current = Node::new_link(value, None);
+        self.size += 1;
     }
 
     fn dequeue(&mut self) -> Option<T> {
@@ -61,6 +95,7 @@ impl<T> Queue<T> for LinkedList<T> {
             Some(node_box) => {
                 let mut node = *node_box;
                 self.head = node.next.take();
+                self.size -= 1;
                 Some(node.value)
             }
             None => None,
@@ -68,13 +103,16 @@ impl<T> Queue<T> for LinkedList<T> {
     }
 }
 
-impl<T> Stack<T> for LinkedList<T> {
+impl<T> ReadonlyStack<T> for LinkedList<T> {
     fn peek(&self) -> Option<&T> {
         Queue::peek(self)
     }
+}
 
+impl<T> Stack<T> for LinkedList<T> {
     fn push(&mut self, value: T) {
         self.head = Node::new_link(value, self.head.take());
+        self.size += 1;
     }
 
     fn pop(&mut self) -> Option<T> {
@@ -113,13 +151,7 @@ impl<T> ReadonlySeq<T>for LinkedList<T> {
     }
 
     fn size(&self) -> usize {
-        let mut size: usize = 0;
-        let mut current = &self.head;
-        while let Some(node_ref) = current {
-            size += 1;
-            current = &(**node_ref).next;
-        }
-        size
+        self.size
     }
 
     fn is_empty(&self) -> bool {
@@ -138,6 +170,7 @@ impl<T> Seq<T> for LinkedList<T> {
                 Some(node_ref) => {
                     if current_index == index {
                         *current = Node::new_link(value, Some(node_ref));
+                        self.size += 1;
                         return Ok(());
                     }
 
@@ -151,7 +184,7 @@ impl<T> Seq<T> for LinkedList<T> {
                 None => break
             }
         }
-        return Err(SeqError::OutOfBound);
+        return Err(SeqError::OutOfBound { index, size: self.size });
     }
 
     fn remove_at(&mut self, index: usize) -> Option<T> {
@@ -165,6 +198,7 @@ impl<T> Seq<T> for LinkedList<T> {
 
                     if current_index == index {
                         *current = node.next.take();
+                        self.size -= 1;
                         return Some(node_ref.value);
                     }
 
@@ -199,6 +233,110 @@ impl<T> Seq<T> for LinkedList<T> {
 }
 
 
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|node| {
+            self.next = node.next.as_deref();
+            self.size -= 1;
+            &node.value
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.take().map(|node| {
+            self.next = node.next.as_deref_mut();
+            self.size -= 1;
+            &mut node.value
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.dequeue()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.size, Some(self.list.size))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for LinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut LinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> Extend<T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        let mut current = &mut self.head;
+        while let Some(node_ref) = current {
+            current = &mut node_ref.next;
+        }
+
+        let mut added: usize = 0;
+        for value in iter {
+            *current = Node::new_link(value, None);
+            added += 1;
+            if let Some(node_ref) = current {
+                current = &mut node_ref.next;
+            }
+        }
+        self.size += added;
+    }
+}
+
+impl<T> FromIterator<T> for LinkedList<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut list = LinkedList::new();
+        list.extend(iter);
+        list
+    }
+}
+
 impl<T> Drop for LinkedList<T> {
     fn drop(&mut self) {
         let mut cur_link = self.head.take();
@@ -246,4 +384,63 @@ mod tests {
         assert_eq!(Ok(()), list.insert(0, 10));
         println!("{:?}", list);
     }
+
+    #[test]
+    fn try_methods() {
+        let mut list: LinkedList<i32> = (1..=3).collect();
+        assert_eq!(Ok(&mut 2), list.try_get_mut(1));
+        assert_eq!(Ok(1), list.try_remove_at(0));
+
+        let error = list.try_remove_at(5).unwrap_err();
+        assert_eq!(SeqError::OutOfBound { index: 5, size: 2 }, error);
+        assert_eq!("index 5 is out of bounds for a sequence of size 2", error.to_string());
+        assert_eq!(Err(SeqError::OutOfBound { index: 3, size: 2 }), list.insert(3, 4));
+
+        assert_eq!(Ok(2), list.try_dequeue());
+        assert_eq!(Ok(3), list.try_pop());
+        assert_eq!(Err(SeqError::Empty), list.try_pop());
+        assert_eq!(Err(SeqError::Empty), list.try_dequeue());
+    }
+
+    #[test]
+    fn iter() {
+        let list: LinkedList<i32> = (1..=4).collect();
+        let mut iter = list.iter();
+        assert_eq!(4, iter.len());
+        assert_eq!(Some(&1), iter.next());
+        assert_eq!(3, iter.len());
+        assert_eq!(vec![2, 3, 4], iter.copied().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn iter_mut() {
+        let mut list: LinkedList<i32> = (1..=3).collect();
+        for value in &mut list {
+            *value *= 10;
+        }
+        assert_eq!(vec![10, 20, 30], list.iter().copied().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut list = LinkedList::new();
+        list.push("b".to_string());
+        list.push("a".to_string());
+        let mut iter = list.into_iter();
+        assert_eq!(2, iter.len());
+        assert_eq!(Some("a".to_string()), iter.next());
+        assert_eq!(Some("b".to_string()), iter.next());
+        assert_eq!(None, iter.next());
+    }
+
+    #[test]
+    fn extend() {
+        let mut list = LinkedList::from_value(1);
+        list.extend(vec![2, 3]);
+        list.extend(Vec::new());
+        list.enqueue(4);
+        assert_eq!(4, list.size());
+        assert_eq!(Some(&3), list.get(2));
+        assert_eq!(vec![1, 2, 3, 4], list.into_iter().collect::<Vec<_>>());
+    }
 }
@@ -1 +1,7 @@
 pub mod linked_list;
+pub mod doubly_linked_list;
+pub mod array_deque;
+pub mod dynamic_array;
+pub mod persistent_list;
+pub mod unrolled_linked_list;
+pub mod self_organizing_list;
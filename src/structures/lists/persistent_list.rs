@@ -0,0 +1,272 @@
+// Both flavours share one implementation and differ only in the reference counted pointer,
+// `rc` for single threaded code and `sync` for versions shared between threads.
+macro_rules! persistent_list {
+    ($pointer:ident) => {
+        use std::fmt::{self, Debug, Formatter};
+        use std::iter::FromIterator;
+
+        use crate::structures::types::*;
+
+        struct Node<T> {
+            value: T,
+            next: Option<$pointer<Node<T>>>,
+        }
+
+        pub struct PersistentList<T> {
+            head: Option<$pointer<Node<T>>>,
+            size: usize,
+        }
+
+        pub struct Iter<'a, T> {
+            next: Option<&'a Node<T>>,
+            size: usize,
+        }
+
+        impl<T> PersistentList<T> {
+            pub fn new() -> PersistentList<T> {
+                PersistentList {
+                    head: None,
+                    size: 0,
+                }
+            }
+
+            pub fn push(&self, value: T) -> PersistentList<T> {
+                PersistentList {
+                    head: Some($pointer::new(Node {
+                        value,
+                        next: self.head.clone(),
+                    })),
+                    size: self.size + 1,
+                }
+            }
+
+            pub fn pop(&self) -> Option<(&T, PersistentList<T>)> {
+                self.head.as_ref().map(|node| {
+                    let tail = PersistentList {
+                        head: node.next.clone(),
+                        size: self.size - 1,
+                    };
+                    (&node.value, tail)
+                })
+            }
+
+            pub fn iter(&self) -> Iter<'_, T> {
+                Iter {
+                    next: self.head.as_deref(),
+                    size: self.size,
+                }
+            }
+
+            pub fn ptr_eq(&self, other: &PersistentList<T>) -> bool {
+                match (&self.head, &other.head) {
+                    (Some(left), Some(right)) => $pointer::ptr_eq(left, right),
+                    (None, None) => true,
+                    _ => false,
+                }
+            }
+        }
+
+        impl<T> ReadonlyStack<T> for PersistentList<T> {
+            fn peek(&self) -> Option<&T> {
+                self.head.as_ref().map(|node| &node.value)
+            }
+        }
+
+        impl<T> ReadonlySeq<T> for PersistentList<T> {
+            fn get(&self, index: usize) -> Option<&T> {
+                self.iter().nth(index)
+            }
+
+            fn index_of(&self, value: &T) -> Option<usize> where T: Eq {
+                self.iter().position(|current| current.eq(value))
+            }
+
+            fn size(&self) -> usize {
+                self.size
+            }
+
+            fn is_empty(&self) -> bool {
+                self.head.is_none()
+            }
+        }
+
+        impl<T> Clone for PersistentList<T> {
+            fn clone(&self) -> Self {
+                PersistentList {
+                    head: self.head.clone(),
+                    size: self.size,
+                }
+            }
+        }
+
+        impl<'a, T> Iterator for Iter<'a, T> {
+            type Item = &'a T;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.next.map(|node| {
+                    self.next = node.next.as_deref();
+                    self.size -= 1;
+                    &node.value
+                })
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.size, Some(self.size))
+            }
+        }
+
+        impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+        impl<'a, T> IntoIterator for &'a PersistentList<T> {
+            type Item = &'a T;
+            type IntoIter = Iter<'a, T>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter()
+            }
+        }
+
+        // Collects in iteration order, so the first element ends up on top.
+        impl<T> FromIterator<T> for PersistentList<T> {
+            fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+                let values = iter.into_iter().collect::<Vec<_>>();
+                values.into_iter()
+                    .rev()
+                    .fold(PersistentList::new(), |list, value| list.push(value))
+            }
+        }
+
+        impl<T> Debug for PersistentList<T> where T: Debug {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.debug_list().entries(self.iter()).finish()
+            }
+        }
+
+        // Unlinks the nodes owned only by this version one by one, the recursive drop
+        // generated for the nodes would overflow the stack on long lists. `into_inner` hands
+        // the node to exactly one of the versions released at the same time, where two
+        // failing `try_unwrap` calls could leave the node to the recursive drop.
+        impl<T> Drop for PersistentList<T> {
+            fn drop(&mut self) {
+                let mut cur_link = self.head.take();
+                while let Some(node) = cur_link {
+                    match $pointer::into_inner(node) {
+                        Some(mut node) => cur_link = node.next.take(),
+                        None => break,
+                    }
+                }
+            }
+        }
+    };
+}
+
+pub mod rc {
+    use std::rc::Rc;
+
+    persistent_list!(Rc);
+}
+
+pub mod sync {
+    use std::sync::Arc;
+
+    persistent_list!(Arc);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    use crate::structures::types::*;
+
+    use super::rc::PersistentList;
+    use super::sync::PersistentList as SyncPersistentList;
+
+    #[test]
+    fn create_new() {
+        let list = PersistentList::<i32>::new();
+        assert_eq!(0, list.size());
+        assert!(list.is_empty());
+        assert_eq!(None, list.peek());
+        assert!(list.pop().is_none());
+    }
+
+    #[test]
+    fn push_pop() {
+        let empty = PersistentList::new();
+        let one = empty.push(1);
+        let two = one.push(2);
+
+        assert_eq!(Some(&2), two.peek());
+        assert_eq!(2, two.size());
+        assert_eq!(0, empty.size());
+
+        let (top, tail) = two.pop().unwrap();
+        assert_eq!(&2, top);
+        assert!(tail.ptr_eq(&one));
+        assert_eq!(Some(&1), one.peek());
+    }
+
+    #[test]
+    fn structural_sharing() {
+        let base: PersistentList<i32> = (1..=3).collect();
+        let left = base.push(10);
+        let right = base.push(20);
+
+        assert_eq!(vec![&10, &1, &2, &3], left.iter().collect::<Vec<_>>());
+        assert_eq!(vec![&20, &1, &2, &3], right.iter().collect::<Vec<_>>());
+        assert!(left.pop().unwrap().1.ptr_eq(&right.pop().unwrap().1));
+
+        drop(base);
+        assert_eq!(Some(2usize), left.index_of(&2));
+        assert_eq!(Some(&3), right.get(3));
+    }
+
+    #[test]
+    fn drop_long() {
+        let mut list = PersistentList::new();
+        for i in 0..200_000 {
+            list = list.push(i);
+        }
+        let shared = list.pop().unwrap().1;
+        drop(list);
+        assert_eq!(199_999, shared.size());
+    }
+
+    #[test]
+    fn sync_flavour() {
+        let base: SyncPersistentList<i32> = (0..100).collect();
+        let workers = (0..4)
+            .map(|i| {
+                let version = base.push(i);
+                thread::spawn(move || version.iter().sum::<i32>())
+            })
+            .collect::<Vec<_>>();
+
+        let sums = workers.into_iter().map(|worker| worker.join().unwrap()).collect::<Vec<_>>();
+        assert_eq!(vec![4950, 4951, 4952, 4953], sums);
+        assert_eq!(100, base.size());
+    }
+
+    #[test]
+    fn sync_drop_long() {
+        for _ in 0..4 {
+            let base: SyncPersistentList<i32> = (0..200_000).collect();
+            let barrier = Arc::new(Barrier::new(2));
+            let workers = (0..2)
+                .map(|i| {
+                    let (version, barrier) = (base.push(i), Arc::clone(&barrier));
+                    thread::spawn(move || {
+                        barrier.wait();
+                        drop(version);
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            drop(base);
+            for worker in workers {
+                worker.join().unwrap();
+            }
+        }
+    }
+}
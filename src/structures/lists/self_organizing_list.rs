@@ -0,0 +1,268 @@
+use std::iter::FromIterator;
+
+use crate::structures::lists::linked_list::LinkedList;
+use crate::structures::types::*;
+
+// Chooses where an accessed element moves to. `counts` holds the access count of every
+// position, already including the current access.
+pub trait Policy {
+    fn target(&self, index: usize, counts: &[usize]) -> usize;
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct MoveToFront;
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Transpose;
+
+// Keeps the list ordered by access count, ties keep their relative order.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Count;
+
+// Lookups are linear scans, so an access costs the position of the element plus one
+// comparison and a miss costs the size of the list. The reordering is a rotation of the
+// scanned prefix, which does not change the asymptotic cost of the access.
+//
+// Since lookups reorder the list they take `&mut self`, so the list implements neither
+// `ReadonlySeq`, whose lookups take `&self` and must leave the order alone, nor `Seq`.
+#[derive(Debug, Clone)]
+pub struct SelfOrganizingList<T, P = MoveToFront> {
+    values: Vec<T>,
+    counts: Vec<usize>,
+    policy: P,
+    cost: usize,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Costs {
+    pub static_list: usize,
+    pub move_to_front: usize,
+    pub transpose: usize,
+    pub count: usize,
+}
+
+impl Policy for MoveToFront {
+    fn target(&self, _index: usize, _counts: &[usize]) -> usize {
+        0
+    }
+}
+
+impl Policy for Transpose {
+    fn target(&self, index: usize, _counts: &[usize]) -> usize {
+        index.saturating_sub(1)
+    }
+}
+
+impl Policy for Count {
+    fn target(&self, index: usize, counts: &[usize]) -> usize {
+        counts[..index].partition_point(|&count| count >= counts[index])
+    }
+}
+
+impl<T> SelfOrganizingList<T> {
+    pub fn new() -> SelfOrganizingList<T> {
+        SelfOrganizingList::with_policy(MoveToFront)
+    }
+}
+
+impl<T, P> SelfOrganizingList<T, P> where P: Policy {
+    pub fn with_policy(policy: P) -> SelfOrganizingList<T, P> {
+        SelfOrganizingList {
+            values: Vec::new(),
+            counts: Vec::new(),
+            policy,
+            cost: 0,
+        }
+    }
+
+    // New elements start at the back with no accesses.
+    pub fn push(&mut self, value: T) {
+        self.values.push(value);
+        self.counts.push(0);
+    }
+
+    pub fn get(&mut self, index: usize) -> Option<&T> {
+        if index >= self.values.len() {
+            return None;
+        }
+
+        self.cost += index + 1;
+        let index = self.reorder(index);
+        Some(&self.values[index])
+    }
+
+    // Returns the position the element was found at, before the access moved it.
+    pub fn index_of(&mut self, value: &T) -> Option<usize> where T: Eq {
+        match self.values.iter().position(|current| current.eq(value)) {
+            Some(index) => {
+                self.cost += index + 1;
+                self.reorder(index);
+                Some(index)
+            }
+            None => {
+                self.cost += self.values.len();
+                None
+            }
+        }
+    }
+
+    pub fn remove(&mut self, value: &T) -> Option<T> where T: Eq {
+        let index = self.values.iter().position(|current| current.eq(value))?;
+        self.counts.remove(index);
+        Some(self.values.remove(index))
+    }
+
+    pub fn size(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    // Iterates in the current order without counting as an access.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.values.iter()
+    }
+
+    // Total number of comparisons spent by `get` and `index_of`.
+    pub fn access_cost(&self) -> usize {
+        self.cost
+    }
+
+    pub fn reset_cost(&mut self) {
+        self.cost = 0;
+    }
+
+    fn reorder(&mut self, index: usize) -> usize {
+        self.counts[index] += 1;
+        let target = self.policy.target(index, &self.counts);
+        self.values[target..=index].rotate_right(1);
+        self.counts[target..=index].rotate_right(1);
+        target
+    }
+}
+
+impl<T, P> Extend<T> for SelfOrganizingList<T, P> where P: Policy {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T, P> FromIterator<T> for SelfOrganizingList<T, P> where P: Policy + Default {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut list = SelfOrganizingList::with_policy(P::default());
+        list.extend(iter);
+        list
+    }
+}
+
+fn replay<T, P>(policy: P, initial: &[T], trace: &[T]) -> usize where T: Eq + Clone, P: Policy {
+    let mut list = SelfOrganizingList::with_policy(policy);
+    list.extend(initial.iter().cloned());
+    for key in trace {
+        list.index_of(key);
+    }
+    list.access_cost()
+}
+
+// Replays the trace of lookups against every policy and against a static `LinkedList`
+// holding the elements in their initial order, using the same cost model.
+pub fn simulate<T>(initial: &[T], trace: &[T]) -> Costs where T: Eq + Clone {
+    let list: LinkedList<T> = initial.iter().cloned().collect();
+    let static_list = trace.iter()
+        .map(|key| list.index_of(key).map_or(list.size(), |index| index + 1))
+        .sum();
+
+    Costs {
+        static_list,
+        move_to_front: replay(MoveToFront, initial, trace),
+        transpose: replay(Transpose, initial, trace),
+        count: replay(Count, initial, trace),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    fn order<P>(list: &SelfOrganizingList<char, P>) -> String {
+        list.values.iter().collect()
+    }
+
+    #[test]
+    fn create_new() {
+        let mut list = SelfOrganizingList::<i32>::new();
+        assert!(list.is_empty());
+        assert_eq!(None, list.get(0));
+        assert_eq!(None, list.index_of(&1));
+        assert_eq!(0, list.access_cost());
+    }
+
+    #[test]
+    fn move_to_front() {
+        let mut list: SelfOrganizingList<char, MoveToFront> = "abcde".chars().collect();
+        assert_eq!(Some(3), list.index_of(&'d'));
+        assert_eq!("dabce", order(&list));
+        assert_eq!(Some(&'c'), list.get(3));
+        assert_eq!("cdabe", order(&list));
+        assert_eq!(4 + 4, list.access_cost());
+    }
+
+    #[test]
+    fn transpose() {
+        let mut list: SelfOrganizingList<char, Transpose> = "abcde".chars().collect();
+        assert_eq!(Some(3), list.index_of(&'d'));
+        assert_eq!(Some(2), list.index_of(&'d'));
+        assert_eq!(Some(0), list.index_of(&'a'));
+        assert_eq!("adbce", order(&list));
+        assert_eq!(None, list.index_of(&'z'));
+        assert_eq!(4 + 3 + 1 + 5, list.access_cost());
+    }
+
+    #[test]
+    fn count() {
+        let mut list: SelfOrganizingList<char, Count> = "abcde".chars().collect();
+        list.index_of(&'c');
+        list.index_of(&'e');
+        assert_eq!("ceabd", order(&list));
+        list.index_of(&'e');
+        assert_eq!("ecabd", order(&list));
+        list.index_of(&'b');
+        assert_eq!("ecbad", order(&list));
+
+        assert_eq!(Some('c'), list.remove(&'c'));
+        assert_eq!("ebad", order(&list));
+        list.index_of(&'a');
+        assert_eq!("ebad", order(&list));
+        list.index_of(&'a');
+        assert_eq!("eabd", order(&list));
+    }
+
+    #[test]
+    fn simulator() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let initial = (0..100).collect::<Vec<u32>>();
+
+        // Roughly Zipf distributed keys, the hottest ones start at the back of the list.
+        let trace = (0..20_000)
+            .map(|_| 99 - (100.0 * rng.gen::<f64>().powi(4)) as u32)
+            .collect::<Vec<_>>();
+        let costs = simulate(&initial, &trace);
+
+        println!("\n{:?}", costs);
+        assert!(costs.move_to_front < costs.static_list / 2);
+        assert!(costs.transpose < costs.static_list / 2);
+        assert!(costs.count < costs.static_list / 2);
+
+        let uniform = (0..20_000).map(|_| rng.gen_range(0..100)).collect::<Vec<_>>();
+        let costs = simulate(&initial, &uniform);
+        println!("{:?}", costs);
+        assert!(costs.move_to_front > costs.static_list * 9 / 10);
+    }
+}
@@ -0,0 +1,575 @@
+use std::fmt::{self, Debug, Formatter};
+use std::iter::FromIterator;
+use std::mem::MaybeUninit;
+use std::{ptr, slice};
+
+use crate::structures::types::*;
+
+// Only the first `len` slots of `values` are initialised.
+struct Node<T, const N: usize> {
+    values: [MaybeUninit<T>; N],
+    len: usize,
+    next: Option<Box<Node<T, N>>>,
+}
+
+// Every node except the last one is kept at least half full: removals merge a node
+// with its successor or borrow from it, insertions into a full node split it in two.
+pub struct UnrolledLinkedList<T, const N: usize = 16> {
+    head: Option<Box<Node<T, N>>>,
+    size: usize,
+}
+
+pub struct Iter<'a, T, const N: usize> {
+    values: slice::Iter<'a, T>,
+    next: Option<&'a Node<T, N>>,
+    size: usize,
+}
+
+pub struct IterMut<'a, T, const N: usize> {
+    values: slice::IterMut<'a, T>,
+    next: Option<&'a mut Node<T, N>>,
+    size: usize,
+}
+
+// Moves the values out of the head node from `index` on and unlinks the node once it is empty.
+pub struct IntoIter<T, const N: usize> {
+    list: UnrolledLinkedList<T, N>,
+    index: usize,
+}
+
+impl<T, const N: usize> Node<T, N> {
+    fn new() -> Box<Node<T, N>> {
+        Box::new(Node {
+            // An array of `MaybeUninit` does not need initialisation.
+            values: unsafe { MaybeUninit::uninit().assume_init() },
+            len: 0,
+            next: None,
+        })
+    }
+
+    fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.values.as_ptr() as *const T, self.len) }
+    }
+
+    fn split_mut(&mut self) -> (&mut [T], Option<&mut Node<T, N>>) {
+        let values = unsafe { slice::from_raw_parts_mut(self.values.as_mut_ptr() as *mut T, self.len) };
+        (values, self.next.as_deref_mut())
+    }
+
+    fn insert(&mut self, index: usize, value: T) {
+        debug_assert!(self.len < N && index <= self.len);
+        unsafe {
+            let slot = self.values.as_mut_ptr().add(index);
+            ptr::copy(slot, slot.add(1), self.len - index);
+            slot.write(MaybeUninit::new(value));
+        }
+        self.len += 1;
+    }
+
+    fn remove(&mut self, index: usize) -> T {
+        debug_assert!(index < self.len);
+        self.len -= 1;
+        unsafe {
+            let slot = self.values.as_mut_ptr().add(index);
+            let value = slot.read().assume_init();
+            ptr::copy(slot.add(1), slot, self.len - index);
+            value
+        }
+    }
+
+    // Moves the upper half of the values into a new node linked right after this one.
+    fn split(&mut self) {
+        let half = self.len / 2;
+        let mut node = Node::new();
+        unsafe {
+            ptr::copy_nonoverlapping(self.values.as_ptr().add(half), node.values.as_mut_ptr(), self.len - half);
+        }
+        node.len = self.len - half;
+        node.next = self.next.take();
+        self.len = half;
+        self.next = Some(node);
+    }
+
+    fn rebalance(&mut self) {
+        if self.len >= N / 2 {
+            return;
+        }
+
+        let next = match self.next.as_mut() {
+            Some(next) => next,
+            None => return,
+        };
+        if self.len + next.len <= N {
+            unsafe {
+                ptr::copy_nonoverlapping(next.values.as_ptr(), self.values.as_mut_ptr().add(self.len), next.len);
+            }
+            self.len += next.len;
+            next.len = 0;
+            self.next = next.next.take();
+        } else {
+            let value = next.remove(0);
+            self.insert(self.len, value);
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for Node<T, N> {
+    fn drop(&mut self) {
+        let values = ptr::slice_from_raw_parts_mut(self.values.as_mut_ptr() as *mut T, self.len);
+        unsafe { ptr::drop_in_place(values) };
+    }
+}
+
+impl<T, const N: usize> UnrolledLinkedList<T, N> {
+    pub fn new() -> UnrolledLinkedList<T, N> {
+        assert!(N >= 2, "node capacity must be at least 2");
+
+        UnrolledLinkedList {
+            head: None,
+            size: 0,
+        }
+    }
+
+    pub fn push(&mut self, value: T) {
+        let size = self.size;
+        self.insert(size, value).unwrap();
+    }
+
+    pub fn iter(&self) -> Iter<'_, T, N> {
+        Iter {
+            values: [].iter(),
+            next: self.head.as_deref(),
+            size: self.size,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, N> {
+        IterMut {
+            values: [].iter_mut(),
+            next: self.head.as_deref_mut(),
+            size: self.size,
+        }
+    }
+
+    fn node_at(&self, mut index: usize) -> Option<(&Node<T, N>, usize)> {
+        let mut current = self.head.as_deref();
+        while let Some(node) = current {
+            if index < node.len {
+                return Some((node, index));
+            }
+            index -= node.len;
+            current = node.next.as_deref();
+        }
+        None
+    }
+
+    #[cfg(test)]
+    fn node_sizes(&self) -> Vec<usize> {
+        let mut sizes = Vec::new();
+        let mut current = self.head.as_deref();
+        while let Some(node) = current {
+            sizes.push(node.len);
+            current = node.next.as_deref();
+        }
+        sizes
+    }
+}
+
+impl<T, const N: usize> ReadonlySeq<T> for UnrolledLinkedList<T, N> {
+    fn get(&self, index: usize) -> Option<&T> {
+        self.node_at(index).map(|(node, index)| &node.as_slice()[index])
+    }
+
+    fn index_of(&self, value: &T) -> Option<usize> where T: Eq {
+        self.iter().position(|current| current.eq(value))
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+}
+
+impl<T, const N: usize> Seq<T> for UnrolledLinkedList<T, N> {
+    // An index on the boundary of two nodes goes to the end of the first one. Appending to
+    // a full last node starts a new one instead of splitting, so sequential pushes fill nodes.
+    fn insert(&mut self, mut index: usize, value: T) -> Result<(), SeqError> {
+        if index > self.size {
+            return Err(SeqError::OutOfBound { index, size: self.size });
+        }
+
+        let mut node = &mut **self.head.get_or_insert_with(Node::new);
+        while index > node.len {
+            index -= node.len;
+            node = node.next.as_deref_mut().unwrap();
+        }
+
+        if node.len == N && index == N && node.next.is_none() {
+            node = &mut **node.next.insert(Node::new());
+            index = 0;
+        } else if node.len == N {
+            node.split();
+            if index > node.len {
+                index -= node.len;
+                node = node.next.as_deref_mut().unwrap();
+            }
+        }
+
+        node.insert(index, value);
+        self.size += 1;
+        Ok(())
+    }
+
+    fn remove_at(&mut self, mut index: usize) -> Option<T> {
+        if index >= self.size {
+            return None;
+        }
+
+        let mut link = &mut self.head;
+        while index >= link.as_ref().unwrap().len {
+            index -= link.as_ref().unwrap().len;
+            link = &mut link.as_mut().unwrap().next;
+        }
+
+        let node = link.as_mut().unwrap();
+        let value = node.remove(index);
+        if node.len == 0 {
+            let next = node.next.take();
+            *link = next;
+        } else {
+            node.rebalance();
+        }
+
+        self.size -= 1;
+        Some(value)
+    }
+
+    fn get_mut(&mut self, mut index: usize) -> Option<&mut T> {
+        let mut current = self.head.as_deref_mut();
+        while let Some(node) = current {
+            if index < node.len {
+                return Some(&mut node.split_mut().0[index]);
+            }
+            index -= node.len;
+            current = node.next.as_deref_mut();
+        }
+        None
+    }
+}
+
+impl<'a, T, const N: usize> Iterator for Iter<'a, T, N> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(value) = self.values.next() {
+                self.size -= 1;
+                return Some(value);
+            }
+
+            let node = self.next.take()?;
+            self.values = node.as_slice().iter();
+            self.next = node.next.as_deref();
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
+}
+
+impl<'a, T, const N: usize> ExactSizeIterator for Iter<'a, T, N> {}
+
+impl<'a, T, const N: usize> Iterator for IterMut<'a, T, N> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(value) = self.values.next() {
+                self.size -= 1;
+                return Some(value);
+            }
+
+            let (values, next) = self.next.take()?.split_mut();
+            self.values = values.iter_mut();
+            self.next = next;
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
+}
+
+impl<'a, T, const N: usize> ExactSizeIterator for IterMut<'a, T, N> {}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let node = self.list.head.as_mut()?;
+            if self.index < node.len {
+                let value = unsafe { node.values[self.index].as_ptr().read() };
+                self.index += 1;
+                self.list.size -= 1;
+                return Some(value);
+            }
+
+            // Every value of the node has been moved out, none of them may be dropped with it.
+            node.len = 0;
+            self.list.head = node.next.take();
+            self.index = 0;
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.size, Some(self.list.size))
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+// Drops what is left of the head node, the list drops the nodes after it.
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    fn drop(&mut self) {
+        if let Some(node) = self.list.head.as_mut() {
+            let remaining = unsafe { node.values.as_mut_ptr().add(self.index) } as *mut T;
+            let remaining = ptr::slice_from_raw_parts_mut(remaining, node.len - self.index);
+            node.len = 0;
+            unsafe { ptr::drop_in_place(remaining) };
+        }
+    }
+}
+
+impl<T, const N: usize> IntoIterator for UnrolledLinkedList<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self, index: 0 }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a UnrolledLinkedList<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut UnrolledLinkedList<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+// Walks to the last node once, then fills nodes completely.
+impl<T, const N: usize> Extend<T> for UnrolledLinkedList<T, N> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        let mut link = &mut self.head;
+        while link.as_ref().is_some_and(|node| node.next.is_some()) {
+            link = &mut link.as_mut().unwrap().next;
+        }
+
+        let mut node = &mut **link.get_or_insert_with(Node::new);
+        for value in iter {
+            if node.len == N {
+                node = &mut **node.next.insert(Node::new());
+            }
+            node.insert(node.len, value);
+            self.size += 1;
+        }
+
+        if self.size == 0 {
+            self.head = None;
+        }
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for UnrolledLinkedList<T, N> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut list = UnrolledLinkedList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T, const N: usize> Debug for UnrolledLinkedList<T, N> where T: Debug {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const N: usize> Drop for UnrolledLinkedList<T, N> {
+    fn drop(&mut self) {
+        let mut cur_link = self.head.take();
+        while let Some(mut node) = cur_link {
+            cur_link = node.next.take();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+    use std::time::Instant;
+
+    use crate::structures::lists::linked_list::LinkedList;
+
+    use super::*;
+
+    #[test]
+    fn create_new() {
+        let list = UnrolledLinkedList::<i32, 4>::new();
+        assert_eq!(0, list.size());
+        assert!(list.is_empty());
+        assert_eq!(None, list.get(0));
+    }
+
+    #[test]
+    fn seq() {
+        let mut list = UnrolledLinkedList::<i32, 4>::new();
+        for i in 0..10 {
+            list.push(i);
+        }
+        assert_eq!(vec![4, 4, 2], list.node_sizes());
+
+        list.insert(0, -1).unwrap();
+        list.insert(5, 100).unwrap();
+        assert_eq!(Err(SeqError::OutOfBound { index: 13, size: 12 }), list.insert(13, 0));
+        assert_eq!(vec![-1, 0, 1, 2, 3, 100, 4, 5, 6, 7, 8, 9], list.iter().copied().collect::<Vec<_>>());
+        assert_eq!(Some(&100), list.get(5));
+        assert_eq!(Some(5), list.index_of(&100));
+
+        *list.get_mut(5).unwrap() = 50;
+        assert_eq!(Some(50), list.remove_at(5));
+        assert_eq!(Some(-1), list.remove_at(0));
+        assert_eq!(None, list.remove_at(10));
+        assert_eq!((0..10).collect::<Vec<_>>(), list.iter().copied().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn split_and_merge() {
+        let mut list = UnrolledLinkedList::<i32, 8>::new();
+        for i in 0..64 {
+            list.insert(list.size() / 2, i).unwrap();
+        }
+        assert!(list.node_sizes().iter().all(|len| (4..=8).contains(len)));
+
+        while list.size() > 3 {
+            list.remove_at(list.size() / 3);
+            let sizes = list.node_sizes();
+            assert!(sizes[..sizes.len() - 1].iter().all(|&len| len >= 4));
+            assert_eq!(list.size(), sizes.iter().sum::<usize>());
+        }
+
+        while list.remove_at(0).is_some() {}
+        assert!(list.node_sizes().is_empty());
+    }
+
+    #[test]
+    fn against_vec() {
+        let mut list = UnrolledLinkedList::<usize, 5>::new();
+        let mut vec = Vec::new();
+        for i in 0..500 {
+            let index = (i * 7919) % (vec.len() + 1);
+            list.insert(index, i).unwrap();
+            vec.insert(index, i);
+            if i % 3 == 0 {
+                let index = (i * 104_729) % vec.len();
+                assert_eq!(Some(vec.remove(index)), list.remove_at(index));
+            }
+        }
+        assert_eq!(vec, list.into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn iter() {
+        let mut list: UnrolledLinkedList<i32, 3> = (0..10).collect();
+        assert_eq!(10, list.iter().len());
+        for value in &mut list {
+            *value *= 2;
+        }
+        list.extend(vec![20, 22]);
+        assert_eq!((0..12).map(|i| i * 2).collect::<Vec<_>>(), list.iter().copied().collect::<Vec<_>>());
+        assert_eq!("[0, 2, 4]", format!("{:?}", (0..3).map(|i| i * 2).collect::<UnrolledLinkedList<_, 3>>()));
+    }
+
+    #[test]
+    fn drops_elements() {
+        let value = Rc::new(());
+        let mut list = UnrolledLinkedList::<_, 4>::new();
+        for _ in 0..50 {
+            list.push(Rc::clone(&value));
+        }
+        list.remove_at(10);
+        assert_eq!(50, Rc::strong_count(&value));
+        drop(list);
+        assert_eq!(1, Rc::strong_count(&value));
+
+        let list: UnrolledLinkedList<_, 4> = (0..50).map(|_| Rc::clone(&value)).collect();
+        let mut iter = list.into_iter();
+        assert_eq!(6, iter.by_ref().take(6).count());
+        assert_eq!(44, iter.len());
+        assert_eq!(45, Rc::strong_count(&value));
+        drop(iter);
+        assert_eq!(1, Rc::strong_count(&value));
+    }
+
+    fn measure<F>(name: &str, run: F) -> usize where F: FnOnce() -> usize {
+        let start = Instant::now();
+        let result = run();
+        println!("{:<12}: {:>10?} ({})", name, start.elapsed(), result);
+        result
+    }
+
+    // Prints timings only, run it on demand with `cargo test benchmark -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn benchmark() {
+        const INSERTIONS: usize = 5_000;
+        const SCAN: usize = 1_000_000;
+
+        println!("\nMiddle insertions: {}", INSERTIONS);
+        let linked = measure("LinkedList", || {
+            let mut list = LinkedList::from_value(0);
+            for i in 0..INSERTIONS {
+                list.insert(list.size() / 2, i).unwrap();
+            }
+            list.size()
+        });
+        let vec = measure("Vec", || {
+            let mut vec = vec![0];
+            for i in 0..INSERTIONS {
+                vec.insert(vec.len() / 2, i);
+            }
+            vec.len()
+        });
+        let unrolled = measure("Unrolled", || {
+            let mut list = UnrolledLinkedList::<_, 64>::new();
+            for i in 0..INSERTIONS {
+                list.insert(list.size() / 2, i).unwrap();
+            }
+            list.size()
+        });
+        assert_eq!((INSERTIONS + 1, INSERTIONS + 1, INSERTIONS), (linked, vec, unrolled));
+
+        println!("\nSequential scan: {}", SCAN);
+        let list: LinkedList<usize> = (0..SCAN).collect();
+        let linked = measure("LinkedList", || list.iter().sum());
+        let vec: Vec<usize> = (0..SCAN).collect();
+        let summed = measure("Vec", || vec.iter().sum());
+        let unrolled: UnrolledLinkedList<usize, 64> = (0..SCAN).collect();
+        assert_eq!(linked, summed);
+        assert_eq!(summed, measure("Unrolled", || unrolled.iter().sum()));
+    }
+}
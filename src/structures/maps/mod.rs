@@ -0,0 +1 @@
+pub mod skip_list;
@@ -0,0 +1,421 @@
+use std::ops::{Bound, RangeBounds};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::structures::types::*;
+
+const MAX_LEVEL: usize = 32;
+const DEFAULT_PROBABILITY: f64 = 0.5;
+
+// Nodes live in a slab and refer to each other by index, `None` in a position argument
+// stands for the head, which has a forward link on every level.
+#[derive(Debug)]
+struct Node<K, V> {
+    key: K,
+    value: V,
+    forward: Vec<Option<usize>>,
+}
+
+#[derive(Debug)]
+pub struct SkipList<K, V> {
+    head: Vec<Option<usize>>,
+    nodes: Vec<Option<Node<K, V>>>,
+    free: Vec<usize>,
+    level: usize,
+    size: usize,
+    probability: f64,
+    rng: StdRng,
+}
+
+#[derive(Debug)]
+pub struct SkipSet<K> {
+    list: SkipList<K, ()>,
+}
+
+pub struct Iter<'a, K, V> {
+    list: &'a SkipList<K, V>,
+    current: Option<usize>,
+}
+
+pub struct Range<'a, K, V, R> {
+    iter: Iter<'a, K, V>,
+    bounds: R,
+}
+
+impl<K, V> SkipList<K, V> where K: Ord {
+    pub fn new() -> SkipList<K, V> {
+        SkipList::with_config(DEFAULT_PROBABILITY, rand::thread_rng().gen())
+    }
+
+    pub fn with_seed(seed: u64) -> SkipList<K, V> {
+        SkipList::with_config(DEFAULT_PROBABILITY, seed)
+    }
+
+    pub fn with_config(probability: f64, seed: u64) -> SkipList<K, V> {
+        assert!(probability > 0.0 && probability < 1.0, "level probability must be within (0, 1)");
+
+        SkipList {
+            head: vec![None; MAX_LEVEL],
+            nodes: Vec::new(),
+            free: Vec::new(),
+            level: 0,
+            size: 0,
+            probability,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            list: self,
+            current: self.head[0],
+        }
+    }
+
+    pub fn range<R>(&self, bounds: R) -> Range<'_, K, V, R> where R: RangeBounds<K> {
+        let current = match bounds.start_bound() {
+            Bound::Included(start) => self.next(self.lower_node(start), 0),
+            Bound::Excluded(start) => self.next(self.floor_node(start), 0),
+            Bound::Unbounded => self.head[0],
+        };
+
+        Range {
+            iter: Iter { list: self, current },
+            bounds,
+        }
+    }
+
+    fn node(&self, index: usize) -> &Node<K, V> {
+        self.nodes[index].as_ref().unwrap()
+    }
+
+    fn node_mut(&mut self, index: usize) -> &mut Node<K, V> {
+        self.nodes[index].as_mut().unwrap()
+    }
+
+    fn next(&self, position: Option<usize>, level: usize) -> Option<usize> {
+        match position {
+            Some(index) => self.node(index).forward[level],
+            None => self.head[level],
+        }
+    }
+
+    fn set_next(&mut self, position: Option<usize>, level: usize, next: Option<usize>) {
+        match position {
+            Some(index) => self.node_mut(index).forward[level] = next,
+            None => self.head[level] = next,
+        }
+    }
+
+    // The last position whose key is less than `key`.
+    fn lower_node(&self, key: &K) -> Option<usize> {
+        self.last_where(|current| current < key)
+    }
+
+    // The last position whose key is less than or equal to `key`.
+    fn floor_node(&self, key: &K) -> Option<usize> {
+        self.last_where(|current| current <= key)
+    }
+
+    // Keys that pass `before` have to form a prefix of the list.
+    fn last_where(&self, before: impl Fn(&K) -> bool) -> Option<usize> {
+        let mut current = None;
+        for level in (0..self.level).rev() {
+            while let Some(next) = self.next(current, level) {
+                if !before(&self.node(next).key) {
+                    break;
+                }
+                current = Some(next);
+            }
+        }
+        current
+    }
+
+    fn predecessors(&self, key: &K) -> [Option<usize>; MAX_LEVEL] {
+        let mut update = [None; MAX_LEVEL];
+        let mut current = None;
+        for level in (0..self.level).rev() {
+            while let Some(next) = self.next(current, level) {
+                if self.node(next).key < *key {
+                    current = Some(next);
+                } else {
+                    break;
+                }
+            }
+            update[level] = current;
+        }
+        update
+    }
+
+    fn find(&self, key: &K) -> Option<usize> {
+        self.next(self.lower_node(key), 0)
+            .filter(|index| self.node(*index).key == *key)
+    }
+
+    fn random_level(&mut self) -> usize {
+        let mut level = 1;
+        while level < MAX_LEVEL && self.rng.gen::<f64>() < self.probability {
+            level += 1;
+        }
+        level
+    }
+
+    fn allocate(&mut self, node: Node<K, V>) -> usize {
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = Some(node);
+                index
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    #[cfg(test)]
+    fn heights(&self) -> Vec<usize> {
+        let mut heights = Vec::new();
+        let mut current = self.head[0];
+        while let Some(index) = current {
+            heights.push(self.node(index).forward.len());
+            current = self.node(index).forward[0];
+        }
+        heights
+    }
+}
+
+impl<K, V> ReadonlyMap<K, V> for SkipList<K, V> where K: Ord {
+    fn get(&self, key: &K) -> Option<&V> {
+        self.find(key).map(|index| &self.node(index).value)
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl<K, V> Map<K, V> for SkipList<K, V> where K: Ord {
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let index = self.find(key)?;
+        Some(&mut self.node_mut(index).value)
+    }
+
+    fn put(&mut self, key: K, value: V) {
+        let update = self.predecessors(&key);
+        if let Some(index) = self.next(update[0], 0) {
+            if self.node(index).key == key {
+                self.node_mut(index).value = value;
+                return;
+            }
+        }
+
+        let level = self.random_level();
+        let forward = (0..level).map(|i| self.next(update[i], i)).collect();
+        let index = self.allocate(Node { key, value, forward });
+        for (i, position) in update.iter().enumerate().take(level) {
+            self.set_next(*position, i, Some(index));
+        }
+
+        self.level = self.level.max(level);
+        self.size += 1;
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        let update = self.predecessors(key);
+        let index = self.next(update[0], 0)
+            .filter(|index| self.node(*index).key == *key)?;
+
+        let node = self.nodes[index].take().unwrap();
+        for (i, next) in node.forward.into_iter().enumerate() {
+            self.set_next(update[i], i, next);
+        }
+        self.free.push(index);
+
+        while self.level > 0 && self.head[self.level - 1].is_none() {
+            self.level -= 1;
+        }
+        self.size -= 1;
+        Some(node.value)
+    }
+}
+
+impl<K> SkipSet<K> where K: Ord {
+    pub fn new() -> SkipSet<K> {
+        SkipSet { list: SkipList::new() }
+    }
+
+    pub fn with_config(probability: f64, seed: u64) -> SkipSet<K> {
+        SkipSet { list: SkipList::with_config(probability, seed) }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=&K> {
+        self.list.iter().map(|(key, _)| key)
+    }
+
+    pub fn range<R>(&self, range: R) -> impl Iterator<Item=&K> where R: RangeBounds<K> {
+        self.list.range(range).map(|(key, _)| key)
+    }
+}
+
+impl<K> ReadonlySet<K> for SkipSet<K> where K: Ord {
+    fn contains(&self, key: &K) -> bool {
+        self.list.find(key).is_some()
+    }
+
+    fn size(&self) -> usize {
+        self.list.size
+    }
+}
+
+impl<K> Set<K> for SkipSet<K> where K: Ord {
+    fn insert(&mut self, key: K) -> bool {
+        if self.contains(&key) {
+            return false;
+        }
+        self.list.put(key, ());
+        true
+    }
+
+    fn remove(&mut self, key: &K) -> bool {
+        self.list.remove(key).is_some()
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.list.nodes[self.current?].as_ref().unwrap();
+        self.current = node.forward[0];
+        Some((&node.key, &node.value))
+    }
+}
+
+impl<'a, K, V, R> Iterator for Range<'a, K, V, R> where K: Ord, R: RangeBounds<K> {
+    type Item = (&'a K, &'a V);
+
+    // Iteration starts inside the range, so the first key outside of it is past the end.
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.iter.next()?;
+        if self.bounds.contains(key) {
+            Some((key, value))
+        } else {
+            self.iter.current = None;
+            None
+        }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a SkipList<K, V> where K: Ord {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::compare_with_btree_map;
+
+    use super::*;
+
+    fn create_list() -> SkipList<i32, String> {
+        let mut list = SkipList::with_seed(7);
+        for key in [44, 17, 88, 32, 65, 97, 28, 54, 82, 29, 76, 80, 78].iter() {
+            list.put(*key, key.to_string());
+        }
+        list
+    }
+
+    #[test]
+    fn create() {
+        let list = SkipList::<i32, String>::new();
+        assert_eq!(0, list.size());
+        assert_eq!(None, list.get(&1));
+    }
+
+    #[test]
+    fn put_get_remove() {
+        let mut list = create_list();
+        assert_eq!(13, list.size());
+        assert_eq!(Some(&"54".to_string()), list.get(&54));
+        assert_eq!(None, list.get(&55));
+
+        list.put(54, "fifty four".to_string());
+        assert_eq!(13, list.size());
+        *list.get_mut(&17).unwrap() = "seventeen".to_string();
+        assert_eq!(Some(&"seventeen".to_string()), list.get(&17));
+
+        assert_eq!(Some("fifty four".to_string()), list.remove(&54));
+        assert_eq!(None, list.remove(&54));
+        assert_eq!(12, list.size());
+
+        assert_eq!(Ok("seventeen".to_string()), list.try_remove(&17));
+        assert_eq!(Err(MapError::KeyNotFound), list.try_remove(&17));
+        assert_eq!(Err(MapError::KeyNotFound), list.try_get_mut(&17));
+    }
+
+    #[test]
+    fn ordered_iteration() {
+        let list = create_list();
+        let keys = list.iter().map(|(key, _)| *key).collect::<Vec<_>>();
+        assert_eq!(vec![17, 28, 29, 32, 44, 54, 65, 76, 78, 80, 82, 88, 97], keys);
+    }
+
+    #[test]
+    fn range() {
+        let list = create_list();
+        fn keys<R>(range: Range<i32, String, R>) -> Vec<i32> where R: RangeBounds<i32> {
+            range.map(|(key, _)| *key).collect()
+        }
+
+        assert_eq!(vec![29, 32, 44], keys(list.range(29..54)));
+        assert_eq!(vec![29, 32, 44, 54], keys(list.range(29..=54)));
+        assert_eq!(vec![32, 44], keys(list.range(30..50)));
+        assert_eq!(vec![17, 28], keys(list.range(..29)));
+        assert_eq!(vec![88, 97], keys(list.range(83..)));
+        assert_eq!(vec![44, 54], keys(list.range((Bound::Excluded(32), Bound::Included(54)))));
+        assert!(keys(list.range(98..)).is_empty());
+    }
+
+    #[test]
+    fn deterministic() {
+        let heights = |seed| {
+            let mut list = SkipList::with_config(0.25, seed);
+            for key in 0..100 {
+                list.put(key, ());
+            }
+            list.heights()
+        };
+
+        assert_eq!(heights(42), heights(42));
+        assert_ne!(heights(42), heights(43));
+    }
+
+    #[test]
+    fn against_btree_map() {
+        let mut list = SkipList::with_seed(1);
+        let expected = compare_with_btree_map(&mut list, 1, |_, _, _, _| {});
+        assert!(list.iter().eq(expected.iter()));
+        assert!(list.range(100..200).eq(expected.range(100..200)));
+    }
+
+    #[test]
+    fn set() {
+        let mut set = SkipSet::with_config(0.5, 3);
+        assert!(set.insert(3));
+        assert!(set.insert(1));
+        assert!(set.insert(2));
+        assert!(!set.insert(2));
+        assert!(set.contains(&1));
+        assert_eq!(3, set.size());
+        assert!(set.remove(&1));
+        assert!(!set.remove(&1));
+        assert_eq!(vec![&2, &3], set.iter().collect::<Vec<_>>());
+        assert_eq!(vec![&3], set.range(3..).collect::<Vec<_>>());
+    }
+}
@@ -1,3 +1,9 @@
 mod lists;
 mod trees;
+mod heaps;
+mod maps;
+mod concurrent;
+mod arena;
+mod text;
+mod storage;
 pub mod types;
@@ -0,0 +1,907 @@
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Formatter};
+use std::ops::{Bound, RangeBounds};
+use std::path::Path;
+
+use crate::structures::storage::buffer_pool::{BufferPool, PageFormat};
+use crate::structures::storage::codec::{write_prefixed, Native, Reader};
+use crate::structures::storage::pager::{Pager, CHECKSUM_SIZE, PAGE_SIZE};
+use crate::structures::types::{Codec, Map, ReadonlyMap, StorageError};
+
+const MAGIC: &[u8; 8] = b"BPLUSTRE";
+const HEADER_PAGE: u64 = 0;
+// The header is never a node, so its page doubles as the end of the leaf chain and of the
+// free list.
+const NONE: u64 = HEADER_PAGE;
+
+const LEAF: u8 = 1;
+const INTERNAL: u8 = 2;
+const FREE: u8 = 3;
+
+// Checksum, kind, next page or first child and number of keys.
+const NODE_HEADER: usize = CHECKSUM_SIZE + 1 + 8 + 4;
+// Length prefixes of the key and the value.
+const LEAF_ENTRY: usize = 4 + 4;
+// Length prefix of the key and the child on its right.
+const INTERNAL_ENTRY: usize = 4 + 8;
+
+// Any four entries fit in a page, so halves of a split always fit and nodes refilled from a
+// sibling end up well above the underflow threshold.
+const ENTRY_LIMIT: usize = (PAGE_SIZE - NODE_HEADER) / 4;
+const UNDERFLOW: usize = PAGE_SIZE / 4;
+
+enum Node<K, V> {
+    Leaf { keys: Vec<K>, values: Vec<V>, next: u64 },
+    // Child `i` holds the keys from `keys[i - 1]` included to `keys[i]` excluded.
+    Internal { keys: Vec<K>, children: Vec<u64> },
+    Free { next: u64 },
+}
+
+struct NodeFormat<KC, VC> {
+    keys: KC,
+    values: VC,
+}
+
+// Ordered map stored in fixed-size pages of a single file. Values live in the leaves, which
+// are chained for range scans, while internal nodes only route by copies of keys. Nodes are
+// split and merged by their encoded size, so keys and values can be of any length up to a
+// quarter of a page.
+//
+// Pages are cached decoded in a buffer pool with a budget, and changes reach the file when
+// pages are evicted, on `flush` and on drop. The `try_` methods report storage errors, the
+// map methods panic on them. `try_get` copies the value out and keeps the pool within its
+// budget. `ReadonlyMap::get` lends the value from its leaf, so every leaf it reads stays
+// cached until the next change and a read-only workload through it is not bounded by the
+// budget; use `try_get` there.
+pub struct BPlusTree<K, V, KC = Native, VC = Native> where KC: Codec<K>, VC: Codec<V> {
+    pool: BufferPool<Node<K, V>, NodeFormat<KC, VC>>,
+    root: u64,
+    size: usize,
+    // First page of the list of pages freed by merges.
+    free: u64,
+}
+
+// Scans the leaf chain, pages that are not cached are read without being cached.
+pub struct Range<'a, K, V, KC, VC> where KC: Codec<K>, VC: Codec<V> {
+    tree: &'a BPlusTree<K, V, KC, VC>,
+    bounds: (Bound<K>, Bound<K>),
+    entries: VecDeque<(K, V)>,
+    next: u64,
+}
+
+// The child of an internal node whose keys can contain `key`.
+fn child_index<K>(keys: &[K], key: &K) -> usize where K: Ord {
+    match keys.binary_search(key) {
+        Ok(index) => index + 1,
+        Err(index) => index,
+    }
+}
+
+fn past_end<K>(end: &Bound<K>, key: &K) -> bool where K: Ord {
+    match end {
+        Bound::Included(end) => key > end,
+        Bound::Excluded(end) => key >= end,
+        Bound::Unbounded => false,
+    }
+}
+
+fn or_panic<T>(result: Result<T, StorageError>) -> T {
+    result.unwrap_or_else(|error| panic!("{}", error))
+}
+
+impl<KC, VC> NodeFormat<KC, VC> {
+    fn entry_sizes<K, V>(&self, node: &Node<K, V>) -> Vec<usize> where KC: Codec<K>, VC: Codec<V> {
+        match node {
+            Node::Leaf { keys, values, .. } => keys.iter().zip(values)
+                .map(|(key, value)| LEAF_ENTRY + self.keys.encoded_size(key) + self.values.encoded_size(value))
+                .collect(),
+            Node::Internal { keys, .. } => keys.iter()
+                .map(|key| INTERNAL_ENTRY + self.keys.encoded_size(key))
+                .collect(),
+            Node::Free { .. } => Vec::new(),
+        }
+    }
+
+    fn size<K, V>(&self, node: &Node<K, V>) -> usize where KC: Codec<K>, VC: Codec<V> {
+        NODE_HEADER + self.entry_sizes(node).iter().sum::<usize>()
+    }
+
+    // Splits a node in two halves of about the same encoded size. The right half goes to
+    // `right_page` and the returned key separates them in the parent.
+    fn split<K, V>(&self, node: Node<K, V>, right_page: u64) -> (Node<K, V>, K, Node<K, V>) where K: Clone, KC: Codec<K>, VC: Codec<V> {
+        let sizes = self.entry_sizes(&node);
+        let half = sizes.iter().sum::<usize>() / 2;
+        let (mut middle, mut used) = (0, 0);
+        while used < half {
+            used += sizes[middle];
+            middle += 1;
+        }
+
+        match node {
+            Node::Leaf { mut keys, mut values, next } => {
+                let middle = middle.clamp(1, keys.len() - 1);
+                let right_keys = keys.split_off(middle);
+                let separator = right_keys[0].clone();
+                let right = Node::Leaf { keys: right_keys, values: values.split_off(middle), next };
+                (Node::Leaf { keys, values, next: right_page }, separator, right)
+            }
+            Node::Internal { mut keys, mut children } => {
+                let middle = middle.clamp(1, keys.len() - 2);
+                let right = Node::Internal { keys: keys.split_off(middle + 1), children: children.split_off(middle + 1) };
+                let separator = keys.pop().unwrap();
+                (Node::Internal { keys, children }, separator, right)
+            }
+            Node::Free { .. } => unreachable!("free pages are never split"),
+        }
+    }
+
+    fn decode_node<K, V>(&self, reader: &mut Reader<'_>) -> Option<Node<K, V>> where KC: Codec<K>, VC: Codec<V> {
+        let kind = reader.u8()?;
+        let link = reader.u64()?;
+        let count = reader.u32()? as usize;
+        if count > PAGE_SIZE {
+            return None;
+        }
+        match kind {
+            LEAF => {
+                let mut keys = Vec::with_capacity(count);
+                let mut values = Vec::with_capacity(count);
+                for _ in 0..count {
+                    keys.push(self.keys.decode(reader.prefixed()?)?);
+                    values.push(self.values.decode(reader.prefixed()?)?);
+                }
+                Some(Node::Leaf { keys, values, next: link })
+            }
+            INTERNAL => {
+                let mut keys = Vec::with_capacity(count);
+                let mut children = Vec::with_capacity(count + 1);
+                children.push(link);
+                for _ in 0..count {
+                    keys.push(self.keys.decode(reader.prefixed()?)?);
+                    children.push(reader.u64()?);
+                }
+                Some(Node::Internal { keys, children })
+            }
+            FREE => Some(Node::Free { next: link }),
+            _ => None,
+        }
+    }
+}
+
+impl<K, V, KC, VC> PageFormat<Node<K, V>> for NodeFormat<KC, VC> where KC: Codec<K>, VC: Codec<V> {
+    fn decode(&self, page: u64, bytes: &[u8]) -> Result<Node<K, V>, StorageError> {
+        let mut reader = Reader::new(&bytes[CHECKSUM_SIZE..]);
+        self.decode_node(&mut reader).ok_or(StorageError::Corrupted { page })
+    }
+
+    fn encode(&self, node: &Node<K, V>, bytes: &mut [u8]) {
+        let mut encoded = Vec::with_capacity(PAGE_SIZE);
+        match node {
+            Node::Leaf { keys, values, next } => {
+                encoded.push(LEAF);
+                encoded.extend_from_slice(&next.to_le_bytes());
+                encoded.extend_from_slice(&(keys.len() as u32).to_le_bytes());
+                for (key, value) in keys.iter().zip(values) {
+                    write_prefixed(&self.keys, key, &mut encoded);
+                    write_prefixed(&self.values, value, &mut encoded);
+                }
+            }
+            Node::Internal { keys, children } => {
+                encoded.push(INTERNAL);
+                encoded.extend_from_slice(&children[0].to_le_bytes());
+                encoded.extend_from_slice(&(keys.len() as u32).to_le_bytes());
+                for (key, child) in keys.iter().zip(&children[1..]) {
+                    write_prefixed(&self.keys, key, &mut encoded);
+                    encoded.extend_from_slice(&child.to_le_bytes());
+                }
+            }
+            Node::Free { next } => {
+                encoded.push(FREE);
+                encoded.extend_from_slice(&next.to_le_bytes());
+                encoded.extend_from_slice(&0u32.to_le_bytes());
+            }
+        }
+        bytes[CHECKSUM_SIZE..CHECKSUM_SIZE + encoded.len()].copy_from_slice(&encoded);
+    }
+}
+
+impl<K, V> BPlusTree<K, V> where Native: Codec<K> + Codec<V> {
+    pub fn open(path: impl AsRef<Path>, budget: usize) -> Result<Self, StorageError> {
+        BPlusTree::open_with(path, budget, Native, Native)
+    }
+}
+
+impl<K, V, KC, VC> BPlusTree<K, V, KC, VC> where KC: Codec<K>, VC: Codec<V> {
+    // Opens the tree stored in the file or creates an empty one, keeping at most `budget`
+    // pages in memory between operations.
+    pub fn open_with(path: impl AsRef<Path>, budget: usize, keys: KC, values: VC) -> Result<Self, StorageError> {
+        let mut pager = Pager::open(path)?;
+        let format = NodeFormat { keys, values };
+        if pager.pages() == 0 {
+            pager.allocate();
+            let root = pager.allocate();
+            let mut tree = BPlusTree { pool: BufferPool::new(pager, format, budget), root, size: 0, free: NONE };
+            tree.pool.put(root, Node::Leaf { keys: Vec::new(), values: Vec::new(), next: NONE });
+            tree.flush()?;
+            return Ok(tree);
+        }
+
+        let header = pager.read(HEADER_PAGE)?;
+        let mut reader = Reader::new(&header[CHECKSUM_SIZE..]);
+        let corrupted = StorageError::Corrupted { page: HEADER_PAGE };
+        if reader.take(MAGIC.len()) != Some(MAGIC) || reader.u32() != Some(PAGE_SIZE as u32) {
+            return Err(corrupted);
+        }
+        match (reader.u64(), reader.u64(), reader.u64()) {
+            (Some(root), Some(size), Some(free)) => Ok(BPlusTree {
+                pool: BufferPool::new(pager, format, budget),
+                root,
+                size: size as usize,
+                free,
+            }),
+            _ => Err(corrupted),
+        }
+    }
+
+    // Writes every changed page and then the header, and syncs the file.
+    pub fn flush(&mut self) -> Result<(), StorageError> {
+        self.pool.flush()?;
+        let mut header = vec![0; PAGE_SIZE];
+        let mut fields = MAGIC.to_vec();
+        fields.extend_from_slice(&(PAGE_SIZE as u32).to_le_bytes());
+        for field in [self.root, self.size as u64, self.free].iter() {
+            fields.extend_from_slice(&field.to_le_bytes());
+        }
+        header[CHECKSUM_SIZE..CHECKSUM_SIZE + fields.len()].copy_from_slice(&fields);
+        self.pool.pager_mut().write(HEADER_PAGE, &mut header)?;
+        self.pool.pager().sync()
+    }
+
+    pub fn height(&self) -> usize {
+        let mut height = 1;
+        let mut page = self.root;
+        while let Node::Internal { children, .. } = &*or_panic(self.pool.pin(page)) {
+            height += 1;
+            page = children[0];
+        }
+        height
+    }
+
+    pub fn iter(&self) -> Range<'_, K, V, KC, VC> where K: Ord + Clone {
+        self.range(..)
+    }
+
+    pub fn range(&self, range: impl RangeBounds<K>) -> Range<'_, K, V, KC, VC> where K: Ord + Clone {
+        let bounds = (range.start_bound().cloned(), range.end_bound().cloned());
+        let mut page = self.root;
+        loop {
+            let child = or_panic(self.pool.read(page, |node| match node {
+                Node::Internal { keys, children } => Some(match &bounds.0 {
+                    Bound::Included(key) | Bound::Excluded(key) => children[child_index(keys, key)],
+                    Bound::Unbounded => children[0],
+                }),
+                _ => None,
+            }));
+            match child {
+                Some(child) => page = child,
+                None => break,
+            }
+        }
+        Range { tree: self, bounds, entries: VecDeque::new(), next: page }
+    }
+
+    // Checks the order and the bounds of the keys, that every node fits its page and that
+    // all but the root are filled above the underflow threshold, that all leaves are at the
+    // same depth and chained in order, and the free list.
+    pub fn is_valid(&self) -> bool where K: Ord {
+        let mut leaves = Vec::new();
+        let mut size = 0;
+        if self.check(self.root, None, None, &mut leaves, &mut size).is_none() || size != self.size {
+            return false;
+        }
+
+        let mut chained = Vec::new();
+        let mut leaf = leaves[0];
+        while leaf != NONE && chained.len() <= leaves.len() {
+            chained.push(leaf);
+            leaf = match self.pool.pin(leaf).as_deref() {
+                Ok(Node::Leaf { next, .. }) => *next,
+                _ => return false,
+            };
+        }
+
+        let mut free = self.free;
+        let mut pages = 0;
+        while free != NONE && pages < self.pool.pager().pages() {
+            free = match self.pool.pin(free).as_deref() {
+                Ok(Node::Free { next }) => *next,
+                _ => return false,
+            };
+            pages += 1;
+        }
+        chained == leaves && free == NONE
+    }
+
+    // Returns the depth of the subtree whose keys have to be in between `lower` included
+    // and `upper` excluded, collecting its leaves and counting its entries.
+    fn check(&self, page: u64, lower: Option<&K>, upper: Option<&K>, leaves: &mut Vec<u64>, size: &mut usize) -> Option<usize> where K: Ord {
+        let node = self.pool.pin(page).ok()?;
+        let node = &*node;
+        let keys = match node {
+            Node::Leaf { keys, .. } | Node::Internal { keys, .. } => keys,
+            Node::Free { .. } => return None,
+        };
+        let node_size = self.pool.format().size(node);
+        let valid = keys.windows(2).all(|pair| pair[0] < pair[1])
+            && keys.first().is_none_or(|first| lower.is_none_or(|lower| first >= lower))
+            && keys.last().is_none_or(|last| upper.is_none_or(|upper| last < upper))
+            && node_size <= PAGE_SIZE
+            && (page == self.root || node_size >= UNDERFLOW);
+        if !valid {
+            return None;
+        }
+
+        match node {
+            Node::Leaf { values, .. } => {
+                leaves.push(page);
+                *size += values.len();
+                (keys.len() == values.len()).then_some(0)
+            }
+            Node::Internal { children, .. } => {
+                if children.len() != keys.len() + 1 || (page == self.root && keys.is_empty()) {
+                    return None;
+                }
+                let mut depth = None;
+                for (index, child) in children.iter().enumerate() {
+                    let lower = if index == 0 { lower } else { Some(&keys[index - 1]) };
+                    let upper = keys.get(index).or(upper);
+                    let child_depth = self.check(*child, lower, upper, leaves, size)?;
+                    if depth.is_some_and(|depth| depth != child_depth) {
+                        return None;
+                    }
+                    depth = Some(child_depth);
+                }
+                depth.map(|depth| depth + 1)
+            }
+            Node::Free { .. } => None,
+        }
+    }
+
+    pub fn try_get(&self, key: &K) -> Result<Option<V>, StorageError> where K: Ord, V: Clone {
+        let page = self.find_leaf(key)?;
+        self.leaf(page, |keys, values| keys.binary_search(key).ok().map(|index| values[index].clone()))
+    }
+
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<(), StorageError> where K: Ord + Clone {
+        let format = self.pool.format();
+        let size = LEAF_ENTRY + format.keys.encoded_size(&key) + format.values.encoded_size(&value);
+        if size > ENTRY_LIMIT {
+            return Err(StorageError::EntryTooLarge { size, limit: ENTRY_LIMIT });
+        }
+
+        self.pool.evict()?;
+        if self.insert_into(self.root, key, value)? {
+            self.size += 1;
+        }
+        self.fix_root()?;
+        self.pool.evict()
+    }
+
+    // Returns whether the key is new. The node changes in both cases, so every node on
+    // the path is checked on the way back.
+    fn insert_into(&mut self, page: u64, key: K, value: V) -> Result<bool, StorageError> where K: Ord + Clone {
+        match self.child_of(page, &key)? {
+            Some((index, child)) => {
+                let inserted = self.insert_into(child, key, value)?;
+                self.fix_child(page, index)?;
+                Ok(inserted)
+            }
+            None => {
+                let (keys, values) = self.leaf_mut(page)?;
+                match keys.binary_search(&key) {
+                    Ok(index) => {
+                        values[index] = value;
+                        Ok(false)
+                    }
+                    Err(index) => {
+                        keys.insert(index, key);
+                        values.insert(index, value);
+                        Ok(true)
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn try_remove(&mut self, key: &K) -> Result<Option<V>, StorageError> where K: Ord + Clone {
+        self.pool.evict()?;
+        let removed = self.remove_from(self.root, key)?;
+        if removed.is_some() {
+            self.size -= 1;
+            self.fix_root()?;
+        }
+        self.pool.evict()?;
+        Ok(removed)
+    }
+
+    fn find_leaf(&self, key: &K) -> Result<u64, StorageError> where K: Ord {
+        let mut page = self.root;
+        while let Some((_, child)) = self.child_of(page, key)? {
+            page = child;
+        }
+        Ok(page)
+    }
+
+    // The leaf stays pinned until the next change, so the value can be borrowed from the pool.
+    fn find(&self, key: &K) -> Result<Option<&V>, StorageError> where K: Ord {
+        let page = self.find_leaf(key)?;
+        match self.pool.get(page)? {
+            Node::Leaf { keys, values, .. } => Ok(keys.binary_search(key).ok().map(|index| &values[index])),
+            _ => Err(StorageError::Corrupted { page }),
+        }
+    }
+
+    fn find_mut(&mut self, key: &K) -> Result<Option<&mut V>, StorageError> where K: Ord {
+        self.pool.evict()?;
+        let page = self.find_leaf(key)?;
+        match self.leaf(page, |keys, _| keys.binary_search(key))? {
+            Ok(index) => Ok(Some(&mut self.leaf_mut(page)?.1[index])),
+            Err(_) => Ok(None),
+        }
+    }
+
+    fn remove_from(&mut self, page: u64, key: &K) -> Result<Option<V>, StorageError> where K: Ord + Clone {
+        match self.child_of(page, key)? {
+            Some((index, child)) => {
+                let removed = self.remove_from(child, key)?;
+                if removed.is_some() {
+                    self.fix_child(page, index)?;
+                }
+                Ok(removed)
+            }
+            None => match self.leaf(page, |keys, _| keys.binary_search(key))? {
+                Ok(index) => {
+                    let (keys, values) = self.leaf_mut(page)?;
+                    keys.remove(index);
+                    Ok(Some(values.remove(index)))
+                }
+                Err(_) => Ok(None),
+            },
+        }
+    }
+
+    // Splits the child when it outgrew its page, or refills it from a sibling when it fell
+    // below the threshold. Either way the parent changes and is checked by its own parent.
+    fn fix_child(&mut self, parent: u64, index: usize) -> Result<(), StorageError> where K: Ord + Clone {
+        let child = self.internal(parent, |_, children| children[index])?;
+        let size = self.pool.format().size(&*self.pool.pin(child)?);
+        if size > PAGE_SIZE {
+            self.split_child(parent, index)
+        } else if size < UNDERFLOW {
+            self.rebalance(parent, index)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn split_child(&mut self, parent: u64, index: usize) -> Result<(), StorageError> where K: Clone {
+        let child = self.internal(parent, |_, children| children[index])?;
+        let node = self.pool.take(child)?;
+        let right_page = self.allocate()?;
+        let (left, separator, right) = self.pool.format().split(node, right_page);
+        self.pool.put(child, left);
+        self.pool.put(right_page, right);
+
+        let (keys, children) = self.internal_mut(parent)?;
+        keys.insert(index, separator);
+        children.insert(index + 1, right_page);
+        Ok(())
+    }
+
+    // Merges the child with a sibling, and splits them again in the middle when both do not
+    // fit in one page.
+    fn rebalance(&mut self, parent: u64, index: usize) -> Result<(), StorageError> where K: Ord + Clone {
+        let (keys, children) = self.internal_mut(parent)?;
+        if children.len() < 2 {
+            return Ok(());
+        }
+        let index = index.min(children.len() - 2);
+        let separator = keys.remove(index);
+        let (left_page, right_page) = (children[index], children.remove(index + 1));
+
+        let left = self.pool.take(left_page)?;
+        let right = self.pool.take(right_page)?;
+        let merged = match (left, right) {
+            (Node::Leaf { mut keys, mut values, .. }, Node::Leaf { keys: right_keys, values: right_values, next }) => {
+                keys.extend(right_keys);
+                values.extend(right_values);
+                Node::Leaf { keys, values, next }
+            }
+            (Node::Internal { mut keys, mut children }, Node::Internal { keys: right_keys, children: right_children }) => {
+                keys.push(separator);
+                keys.extend(right_keys);
+                children.extend(right_children);
+                Node::Internal { keys, children }
+            }
+            _ => return Err(StorageError::Corrupted { page: right_page }),
+        };
+
+        let size = self.pool.format().size(&merged);
+        self.pool.put(left_page, merged);
+        self.free_page(right_page);
+        if size > PAGE_SIZE {
+            self.split_child(parent, index)?;
+        }
+        Ok(())
+    }
+
+    // Grows the tree by a level when the root outgrew its page, and shrinks it when the
+    // root is left with a single child.
+    fn fix_root(&mut self) -> Result<(), StorageError> where K: Ord + Clone {
+        if self.pool.format().size(&*self.pool.pin(self.root)?) > PAGE_SIZE {
+            let root = self.allocate()?;
+            self.pool.put(root, Node::Internal { keys: Vec::new(), children: vec![self.root] });
+            self.root = root;
+            return self.split_child(root, 0);
+        }
+
+        let only_child = match &*self.pool.pin(self.root)? {
+            Node::Internal { keys, children } if keys.is_empty() => Some(children[0]),
+            _ => None,
+        };
+        if let Some(child) = only_child {
+            self.free_page(self.root);
+            self.root = child;
+        }
+        Ok(())
+    }
+
+    // Reuses a freed page if there is one, the caller has to put a node in it.
+    fn allocate(&mut self) -> Result<u64, StorageError> {
+        if self.free == NONE {
+            return Ok(self.pool.pager_mut().allocate());
+        }
+        let page = self.free;
+        match self.pool.take(page)? {
+            Node::Free { next } => self.free = next,
+            _ => return Err(StorageError::Corrupted { page }),
+        }
+        Ok(page)
+    }
+
+    fn free_page(&mut self, page: u64) {
+        self.pool.put(page, Node::Free { next: self.free });
+        self.free = page;
+    }
+
+    // The child to descend into from an internal node, `None` for a leaf.
+    fn child_of(&self, page: u64, key: &K) -> Result<Option<(usize, u64)>, StorageError> where K: Ord {
+        match &*self.pool.pin(page)? {
+            Node::Internal { keys, children } => {
+                let index = child_index(keys, key);
+                Ok(Some((index, children[index])))
+            }
+            Node::Leaf { .. } => Ok(None),
+            Node::Free { .. } => Err(StorageError::Corrupted { page }),
+        }
+    }
+
+    fn leaf<R>(&self, page: u64, read: impl FnOnce(&[K], &[V]) -> R) -> Result<R, StorageError> {
+        match &*self.pool.pin(page)? {
+            Node::Leaf { keys, values, .. } => Ok(read(keys, values)),
+            _ => Err(StorageError::Corrupted { page }),
+        }
+    }
+
+    fn leaf_mut(&mut self, page: u64) -> Result<(&mut Vec<K>, &mut Vec<V>), StorageError> {
+        match self.pool.get_mut(page)? {
+            Node::Leaf { keys, values, .. } => Ok((keys, values)),
+            _ => Err(StorageError::Corrupted { page }),
+        }
+    }
+
+    fn internal<R>(&self, page: u64, read: impl FnOnce(&[K], &[u64]) -> R) -> Result<R, StorageError> {
+        match &*self.pool.pin(page)? {
+            Node::Internal { keys, children } => Ok(read(keys, children)),
+            _ => Err(StorageError::Corrupted { page }),
+        }
+    }
+
+    fn internal_mut(&mut self, page: u64) -> Result<(&mut Vec<K>, &mut Vec<u64>), StorageError> {
+        match self.pool.get_mut(page)? {
+            Node::Internal { keys, children } => Ok((keys, children)),
+            _ => Err(StorageError::Corrupted { page }),
+        }
+    }
+}
+
+impl<K, V, KC, VC> ReadonlyMap<K, V> for BPlusTree<K, V, KC, VC> where K: Ord, KC: Codec<K>, VC: Codec<V> {
+    // Not bounded by the budget, see `try_get`.
+    fn get(&self, key: &K) -> Option<&V> {
+        or_panic(self.find(key))
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl<K, V, KC, VC> Map<K, V> for BPlusTree<K, V, KC, VC> where K: Ord + Clone, KC: Codec<K>, VC: Codec<V> {
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        or_panic(self.find_mut(key))
+    }
+
+    fn put(&mut self, key: K, value: V) {
+        or_panic(self.try_insert(key, value))
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        or_panic(self.try_remove(key))
+    }
+}
+
+impl<K, V, KC, VC> Debug for BPlusTree<K, V, KC, VC> where K: Ord + Clone + Debug, V: Clone + Debug, KC: Codec<K>, VC: Codec<V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, KC, VC> Drop for BPlusTree<K, V, KC, VC> where KC: Codec<K>, VC: Codec<V> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+impl<'a, K, V, KC, VC> Iterator for Range<'a, K, V, KC, VC> where K: Ord + Clone, V: Clone, KC: Codec<K>, VC: Codec<V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.entries.is_empty() && self.next != NONE {
+            let (bounds, entries) = (&self.bounds, &mut self.entries);
+            let page = self.next;
+            self.next = or_panic(self.tree.pool.read(page, |node| match node {
+                Node::Leaf { keys, values, next } => {
+                    for (key, value) in keys.iter().zip(values) {
+                        if past_end(&bounds.1, key) {
+                            return Ok(NONE);
+                        }
+                        if bounds.contains(key) {
+                            entries.push_back((key.clone(), value.clone()));
+                        }
+                    }
+                    Ok(*next)
+                }
+                _ => Err(StorageError::Corrupted { page }),
+            }).and_then(|next| next));
+        }
+        self.entries.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::fs;
+
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::helpers::TempPath;
+
+    use super::*;
+
+    fn value(key: u64, length: usize) -> String {
+        format!("{:0width$}", key, width = length)
+    }
+
+    fn create_tree(path: &TempPath, keys: impl Iterator<Item=u64>) -> BPlusTree<u64, String> {
+        let mut tree = BPlusTree::open(&**path, 8).unwrap();
+        for key in keys {
+            tree.put(key, value(key, 100));
+        }
+        tree
+    }
+
+    fn keys(tree: &BPlusTree<u64, String>) -> Vec<u64> {
+        tree.iter().map(|(key, _)| key).collect()
+    }
+
+    #[test]
+    fn create() {
+        let path = TempPath::new("b-plus-tree-create");
+        let mut tree = BPlusTree::<u64, String>::open(&*path, 4).unwrap();
+        assert_eq!(0, tree.size());
+        assert_eq!(None, tree.get(&1));
+        assert!(matches!(tree.try_get(&1), Ok(None)));
+        assert!(matches!(tree.try_remove(&1), Ok(None)));
+        assert!(matches!(tree.try_insert(1, value(1, PAGE_SIZE / 4)), Err(StorageError::EntryTooLarge { .. })));
+        assert_eq!(1, tree.height());
+        assert_eq!(None, tree.iter().next());
+        assert!(tree.is_valid());
+    }
+
+    #[test]
+    fn put_get_remove() {
+        let path = TempPath::new("b-plus-tree-put-get-remove");
+        let mut tree = create_tree(&path, [44, 17, 88, 32, 65, 97, 28, 54, 82, 29, 76, 80, 78].iter().copied());
+        assert!(tree.is_valid());
+        assert_eq!(13, tree.size());
+        assert_eq!(Some(&value(54, 100)), tree.get(&54));
+
+        tree.put(54, String::from("a"));
+        tree.get_mut(&17).unwrap().push('b');
+        assert_eq!(13, tree.size());
+        assert_eq!(Some(&String::from("a")), tree.get(&54));
+        assert_eq!(Some(&format!("{}b", value(17, 100))), tree.get(&17));
+
+        assert_eq!(Some(value(44, 100)), tree.remove(&44));
+        assert_eq!(None, tree.remove(&44));
+        assert_eq!(None, tree.get_mut(&44));
+        assert!(tree.is_valid());
+        assert_eq!(vec![17, 28, 29, 32, 54, 65, 76, 78, 80, 82, 88, 97], keys(&tree));
+    }
+
+    #[test]
+    fn split_merge() {
+        let path = TempPath::new("b-plus-tree-split-merge");
+        let mut tree = create_tree(&path, 0..10_000);
+        assert!(tree.is_valid());
+        assert_eq!(3, tree.height());
+        assert!(keys(&tree).into_iter().eq(0..10_000));
+
+        for key in (0..10_000).filter(|key| key % 1000 != 0) {
+            assert_eq!(Some(value(key, 100)), tree.remove(&key));
+        }
+        assert!(tree.is_valid());
+        assert_eq!(1, tree.height());
+        assert_eq!(vec![0, 1000, 2000, 3000, 4000, 5000, 6000, 7000, 8000, 9000], keys(&tree));
+
+        // Pages freed by the merges are reused before the file grows.
+        let pages = tree.pool.pager().pages();
+        for key in 0..2000 {
+            tree.put(key, value(key, 100));
+        }
+        assert!(tree.is_valid());
+        assert_eq!(pages, tree.pool.pager().pages());
+    }
+
+    #[test]
+    fn variable_sizes() {
+        let path = TempPath::new("b-plus-tree-variable-sizes");
+        let mut tree = BPlusTree::<String, Vec<u8>>::open(&*path, 8).unwrap();
+        for key in 0..300 {
+            tree.put(value(key, key as usize % 500), vec![0; key as usize * 3 % 400]);
+            assert!(tree.is_valid());
+        }
+        for key in (0..300).rev() {
+            tree.put(value(key, key as usize % 500), vec![1; 7]);
+            assert!(tree.is_valid());
+        }
+        for key in 0..300 {
+            assert_eq!(Some(vec![1; 7]), tree.remove(&value(key, key as usize % 500)));
+            assert!(tree.is_valid());
+        }
+        assert_eq!(0, tree.size());
+        assert_eq!(1, tree.height());
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the limit")]
+    fn entry_too_large() {
+        let path = TempPath::new("b-plus-tree-too-large");
+        let mut tree = BPlusTree::<u64, String>::open(&*path, 4).unwrap();
+        tree.put(1, value(1, PAGE_SIZE / 4));
+    }
+
+    #[test]
+    fn range() {
+        let path = TempPath::new("b-plus-tree-range");
+        let tree = create_tree(&path, (0..500).map(|key| key * 2));
+        let range = |range: (Bound<u64>, Bound<u64>)| tree.range(range).map(|(key, _)| key).collect::<Vec<_>>();
+
+        assert_eq!(vec![10, 12, 14], range((Bound::Included(10), Bound::Excluded(16))));
+        assert_eq!(vec![12, 14, 16], range((Bound::Excluded(10), Bound::Included(16))));
+        assert_eq!(vec![12, 14], range((Bound::Included(11), Bound::Included(15))));
+        assert_eq!(vec![0, 2], range((Bound::Unbounded, Bound::Excluded(4))));
+        assert_eq!(vec![996, 998], range((Bound::Excluded(994), Bound::Unbounded)));
+        assert!(range((Bound::Excluded(10), Bound::Excluded(12))).is_empty());
+        assert!(range((Bound::Included(2000), Bound::Unbounded)).is_empty());
+        assert!(tree.range(100..900).map(|(key, _)| key).eq((50..450).map(|key| key * 2)));
+    }
+
+    #[test]
+    fn buffer_pool_budget() {
+        let path = TempPath::new("b-plus-tree-budget");
+        let mut tree = create_tree(&path, 0..2000);
+        assert!(tree.pool.cached() <= tree.pool.budget());
+
+        // A scan reads the leaves around the pool, lookups release their path as they go.
+        assert_eq!(2000, tree.iter().count());
+        assert!(tree.pool.cached() <= tree.pool.budget());
+        for key in 0..2000 {
+            assert_eq!(Some(value(key, 100)), tree.try_get(&key).unwrap());
+            assert!(tree.pool.cached() <= tree.pool.budget());
+        }
+
+        // Borrowed values keep their leaf until the next change, but reading the same leaf
+        // again does not pin it twice.
+        assert!((0..2000).all(|_| tree.get(&1234).is_some()));
+        assert!(tree.pool.cached() <= tree.pool.budget());
+        assert!((0..2000).all(|key| tree.get(&key).is_some()));
+        tree.remove(&0);
+        assert!(tree.pool.cached() <= tree.pool.budget());
+    }
+
+    #[test]
+    fn reopen() {
+        let path = TempPath::new("b-plus-tree-reopen");
+        drop(create_tree(&path, 0..2000));
+
+        let mut tree = BPlusTree::<u64, String>::open(&*path, 8).unwrap();
+        assert!(tree.is_valid());
+        assert_eq!(2000, tree.size());
+        assert_eq!(Some(&value(1234, 100)), tree.get(&1234));
+        for key in 0..1000 {
+            tree.remove(&key);
+        }
+        tree.flush().unwrap();
+        drop(tree);
+
+        let tree = BPlusTree::<u64, String>::open(&*path, 8).unwrap();
+        assert!(tree.is_valid());
+        assert!(keys(&tree).into_iter().eq(1000..2000));
+    }
+
+    #[test]
+    fn corruption() {
+        let path = TempPath::new("b-plus-tree-corruption");
+        drop(create_tree(&path, 0..10));
+        let bytes = fs::read(&*path).unwrap();
+
+        let mut corrupted = bytes.clone();
+        corrupted[PAGE_SIZE + 200] ^= 0x10;
+        fs::write(&*path, &corrupted).unwrap();
+        let tree = BPlusTree::<u64, String>::open(&*path, 8).unwrap();
+        assert!(matches!(tree.try_get(&1), Err(StorageError::Corrupted { page: 1 })));
+        assert!(!tree.is_valid());
+        drop(tree);
+
+        let mut corrupted = bytes;
+        corrupted[20] ^= 0x01;
+        fs::write(&*path, &corrupted).unwrap();
+        assert!(matches!(BPlusTree::<u64, String>::open(&*path, 8), Err(StorageError::Corrupted { page: 0 })));
+    }
+
+    #[test]
+    fn against_btree_map() {
+        let path = TempPath::new("b-plus-tree-against-btree-map");
+        let mut rng = StdRng::seed_from_u64(49);
+        let mut tree = BPlusTree::<u64, String>::open(&*path, 6).unwrap();
+        let mut expected = BTreeMap::new();
+
+        for step in 0..5000 {
+            let key = rng.gen_range(0..500);
+            match rng.gen_range(0..10) {
+                0..=5 => {
+                    let value = value(key, rng.gen_range(0..300));
+                    tree.put(key, value.clone());
+                    expected.insert(key, value);
+                }
+                6..=8 => assert_eq!(expected.remove(&key), tree.remove(&key)),
+                _ => {
+                    let end = key + rng.gen_range(0..50);
+                    assert!(tree.range(key..end).eq(expected.range(key..end).map(|(key, value)| (*key, value.clone()))));
+                }
+            }
+            assert!(tree.is_valid());
+            assert_eq!(expected.len(), tree.size());
+
+            if step % 1000 == 999 {
+                drop(tree);
+                tree = BPlusTree::open(&*path, 6).unwrap();
+            }
+        }
+
+        assert!(tree.iter().eq(expected.into_iter()));
+    }
+}
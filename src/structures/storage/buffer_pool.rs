@@ -0,0 +1,352 @@
+use std::cell::{Cell, RefCell};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::mem;
+use std::ops::Deref;
+use std::ptr::NonNull;
+
+use crate::structures::storage::pager::{Pager, PAGE_SIZE};
+use crate::structures::types::StorageError;
+
+// How the pool turns the pages it caches into bytes and back. `encode` gets a zeroed page
+// whose first `CHECKSUM_SIZE` bytes belong to the pager.
+pub trait PageFormat<T> {
+    fn decode(&self, page: u64, bytes: &[u8]) -> Result<T, StorageError>;
+    fn encode(&self, value: &T, bytes: &mut [u8]);
+}
+
+struct Frame<T> {
+    value: Box<T>,
+    dirty: bool,
+    used: u64,
+    pins: usize,
+}
+
+// Least recently used cache of decoded pages in front of a pager, dirty pages are written
+// back when they are evicted or flushed.
+//
+// Pages in use are pinned and only unpinned pages are evicted, so loading a page through
+// `&self` keeps the pool within its budget unless every cached page is pinned. A page handed
+// out by `get` stays pinned until the next eviction through `&mut self`, which proves the
+// reference is gone.
+pub struct BufferPool<T, F> {
+    pager: Pager,
+    format: F,
+    frames: RefCell<HashMap<u64, Frame<T>>>,
+    // Pages by the tick they were last used at, the first one is the next to evict.
+    recency: RefCell<BTreeMap<u64, u64>>,
+    clock: Cell<u64>,
+    budget: usize,
+}
+
+// A page pinned in the pool, it is not evicted before the guard is dropped.
+pub struct Pinned<'a, T, F> {
+    pool: &'a BufferPool<T, F>,
+    page: u64,
+    value: NonNull<T>,
+}
+
+impl<T, F> BufferPool<T, F> where F: PageFormat<T> {
+    pub fn new(pager: Pager, format: F, budget: usize) -> Self {
+        assert!(budget > 0, "buffer pool needs room for at least one page");
+        BufferPool {
+            pager,
+            format,
+            frames: RefCell::new(HashMap::new()),
+            recency: RefCell::new(BTreeMap::new()),
+            clock: Cell::new(0),
+            budget,
+        }
+    }
+
+    pub fn pager(&self) -> &Pager {
+        &self.pager
+    }
+
+    pub fn pager_mut(&mut self) -> &mut Pager {
+        &mut self.pager
+    }
+
+    pub fn format(&self) -> &F {
+        &self.format
+    }
+
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    pub fn cached(&self) -> usize {
+        self.frames.borrow().len()
+    }
+
+    pub fn pin(&self, page: u64) -> Result<Pinned<'_, T, F>, StorageError> {
+        let value = {
+            let mut frames = self.frames.borrow_mut();
+            let frame = match frames.entry(page) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(Frame { value: Box::new(self.load(page)?), dirty: false, used: 0, pins: 0 }),
+            };
+            self.touch(page, frame);
+            frame.pins += 1;
+            NonNull::from(&*frame.value)
+        };
+        let pinned = Pinned { pool: self, page, value };
+        self.shrink()?;
+        Ok(pinned)
+    }
+
+    // Unbounded: every distinct page handed out stays pinned until the next eviction through
+    // `&mut self`, so a run of reads over many pages takes the pool past its budget. Reads
+    // that can copy what they need should use `pin` or `read`, which keep to the budget.
+    pub fn get(&self, page: u64) -> Result<&T, StorageError> {
+        let pinned = self.pin(page)?;
+        let value = pinned.value;
+        mem::forget(pinned);
+        // The page stays pinned until the next eviction through `&mut self` and a boxed
+        // page does not move when the map grows, so it outlives this borrow of the pool.
+        Ok(unsafe { &*value.as_ptr() })
+    }
+
+    // Reads a page without caching it when it is not cached already, so a long scan does
+    // not push the working set out of the pool.
+    pub fn read<R>(&self, page: u64, read: impl FnOnce(&T) -> R) -> Result<R, StorageError> {
+        let mut frames = self.frames.borrow_mut();
+        match frames.get_mut(&page) {
+            Some(frame) => {
+                self.touch(page, frame);
+                Ok(read(&frame.value))
+            }
+            None => {
+                drop(frames);
+                Ok(read(&self.load(page)?))
+            }
+        }
+    }
+
+    pub fn get_mut(&mut self, page: u64) -> Result<&mut T, StorageError> {
+        let frame = match self.frames.get_mut().entry(page) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let value = self.format.decode(page, &self.pager.read(page)?)?;
+                entry.insert(Frame { value: Box::new(value), dirty: false, used: 0, pins: 0 })
+            }
+        };
+        frame.dirty = true;
+        Self::touch_frame(&self.clock, self.recency.get_mut(), page, frame);
+        Ok(&mut frame.value)
+    }
+
+    // Takes a page out of the pool, it has to be put back before the pool is flushed.
+    pub fn take(&mut self, page: u64) -> Result<T, StorageError> {
+        match self.frames.get_mut().remove(&page) {
+            Some(frame) => {
+                self.recency.get_mut().remove(&frame.used);
+                Ok(*frame.value)
+            }
+            None => self.load(page),
+        }
+    }
+
+    // Caches a new or changed page, it is written back on eviction.
+    pub fn put(&mut self, page: u64, value: T) {
+        let mut frame = Frame { value: Box::new(value), dirty: true, used: 0, pins: 0 };
+        Self::touch_frame(&self.clock, self.recency.get_mut(), page, &mut frame);
+        if let Some(old) = self.frames.get_mut().insert(page, frame) {
+            self.recency.get_mut().remove(&old.used);
+        }
+    }
+
+    // Releases the pages pinned by `get`, then writes back and drops the least recently used
+    // pages until the pool fits its budget.
+    pub fn evict(&mut self) -> Result<(), StorageError> {
+        for frame in self.frames.get_mut().values_mut() {
+            frame.pins = 0;
+        }
+        self.shrink()
+    }
+
+    // Writes back every dirty page and syncs the file.
+    pub fn flush(&mut self) -> Result<(), StorageError> {
+        for (page, frame) in self.frames.get_mut().iter_mut().filter(|(_, frame)| frame.dirty) {
+            Self::write(&self.pager, &self.format, *page, &frame.value)?;
+            frame.dirty = false;
+        }
+        self.pager.sync()
+    }
+
+    // Writes back and drops the least recently used pages that are not pinned until the pool
+    // fits its budget.
+    fn shrink(&self) -> Result<(), StorageError> {
+        let mut frames = self.frames.borrow_mut();
+        let mut recency = self.recency.borrow_mut();
+        let excess = frames.len().saturating_sub(self.budget);
+        let victims = recency.iter()
+            .filter(|(_, page)| frames[page].pins == 0)
+            .map(|(&used, &page)| (used, page))
+            .take(excess)
+            .collect::<Vec<_>>();
+
+        for (used, page) in victims {
+            let frame = &frames[&page];
+            if frame.dirty {
+                Self::write(&self.pager, &self.format, page, &frame.value)?;
+            }
+            recency.remove(&used);
+            frames.remove(&page);
+        }
+        Ok(())
+    }
+
+    fn load(&self, page: u64) -> Result<T, StorageError> {
+        self.format.decode(page, &self.pager.read(page)?)
+    }
+
+    fn touch(&self, page: u64, frame: &mut Frame<T>) {
+        Self::touch_frame(&self.clock, &mut self.recency.borrow_mut(), page, frame);
+    }
+
+    fn touch_frame(clock: &Cell<u64>, recency: &mut BTreeMap<u64, u64>, page: u64, frame: &mut Frame<T>) {
+        recency.remove(&frame.used);
+        clock.set(clock.get() + 1);
+        frame.used = clock.get();
+        recency.insert(frame.used, page);
+    }
+
+    fn write(pager: &Pager, format: &F, page: u64, value: &T) -> Result<(), StorageError> {
+        let mut bytes = vec![0; PAGE_SIZE];
+        format.encode(value, &mut bytes);
+        pager.write(page, &mut bytes)
+    }
+}
+
+impl<'a, T, F> Deref for Pinned<'a, T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { self.value.as_ref() }
+    }
+}
+
+impl<'a, T, F> Drop for Pinned<'a, T, F> {
+    fn drop(&mut self) {
+        if let Some(frame) = self.pool.frames.borrow_mut().get_mut(&self.page) {
+            frame.pins -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::convert::TryInto;
+
+    use crate::helpers::TempPath;
+    use crate::structures::storage::pager::CHECKSUM_SIZE;
+
+    use super::*;
+
+    // Pages holding a single number, counting how many were decoded.
+    #[derive(Default)]
+    struct Numbers {
+        decoded: Cell<usize>,
+    }
+
+    impl PageFormat<u64> for Numbers {
+        fn decode(&self, _: u64, bytes: &[u8]) -> Result<u64, StorageError> {
+            self.decoded.set(self.decoded.get() + 1);
+            Ok(u64::from_le_bytes(bytes[CHECKSUM_SIZE..CHECKSUM_SIZE + 8].try_into().unwrap()))
+        }
+
+        fn encode(&self, value: &u64, bytes: &mut [u8]) {
+            bytes[CHECKSUM_SIZE..CHECKSUM_SIZE + 8].copy_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    fn create_pool(path: &TempPath, pages: u64, budget: usize) -> BufferPool<u64, Numbers> {
+        let mut pool = BufferPool::new(Pager::open(&**path).unwrap(), Numbers::default(), budget);
+        for page in 0..pages {
+            let allocated = pool.pager_mut().allocate();
+            pool.put(allocated, page * 10);
+            pool.evict().unwrap();
+        }
+        pool.flush().unwrap();
+        pool
+    }
+
+    fn decoded(pool: &BufferPool<u64, Numbers>) -> usize {
+        pool.format().decoded.get()
+    }
+
+    #[test]
+    fn least_recently_used() {
+        let path = TempPath::new("buffer-pool");
+        let pool = create_pool(&path, 5, 3);
+        assert_eq!(3, pool.cached());
+        assert_eq!(0, decoded(&pool));
+
+        // Pages 2, 3 and 4 are cached, touching 2 makes 3 the next to go.
+        assert_eq!(20, *pool.pin(2).unwrap());
+        assert_eq!(0, *pool.pin(0).unwrap());
+        assert_eq!(1, decoded(&pool));
+        assert_eq!(3, pool.cached());
+
+        assert_eq!(40, *pool.pin(4).unwrap());
+        assert_eq!(20, *pool.pin(2).unwrap());
+        assert_eq!(1, decoded(&pool));
+        assert_eq!(30, *pool.pin(3).unwrap());
+        assert_eq!(2, decoded(&pool));
+        assert_eq!(3, pool.cached());
+    }
+
+    #[test]
+    fn pinned_pages_stay() {
+        let path = TempPath::new("buffer-pool-pinned");
+        let mut pool = create_pool(&path, 5, 2);
+        let pinned = (0..3).map(|page| pool.pin(page).unwrap()).collect::<Vec<_>>();
+        assert_eq!(vec![0, 10, 20], pinned.iter().map(|page| **page).collect::<Vec<_>>());
+        assert_eq!(3, pool.cached());
+
+        drop(pinned);
+        assert_eq!(30, *pool.pin(3).unwrap());
+        assert_eq!(2, pool.cached());
+
+        // Pages handed out by `get` stay until the next eviction.
+        let pages = (0..4).map(|page| pool.get(page).unwrap()).collect::<Vec<_>>();
+        assert_eq!(vec![&0, &10, &20, &30], pages);
+        assert_eq!(4, pool.cached());
+        pool.evict().unwrap();
+        assert_eq!(2, pool.cached());
+    }
+
+    #[test]
+    fn write_back() {
+        let path = TempPath::new("buffer-pool-write-back");
+        let mut pool = create_pool(&path, 4, 2);
+        *pool.get_mut(0).unwrap() = 1;
+        let page = pool.take(1).unwrap();
+        pool.put(1, page + 1);
+        pool.evict().unwrap();
+
+        // Page 0 was the least recently used and has been written back.
+        pool.get(2).unwrap();
+        pool.evict().unwrap();
+        assert_eq!(1, pool.read(0, |page| *page).unwrap());
+        assert_eq!(2, pool.cached());
+
+        pool.flush().unwrap();
+        drop(pool);
+        let pool = BufferPool::new(Pager::open(&*path).unwrap(), Numbers::default(), 2);
+        let pages = (0..4).map(|page| *pool.get(page).unwrap()).collect::<Vec<_>>();
+        assert_eq!(vec![1, 11, 20, 30], pages);
+    }
+
+    #[test]
+    fn read_does_not_cache() {
+        let path = TempPath::new("buffer-pool-read");
+        let pool = create_pool(&path, 4, 2);
+        assert_eq!(0, pool.read(0, |page| *page).unwrap());
+        assert_eq!(0, pool.read(0, |page| *page).unwrap());
+        assert_eq!(2, decoded(&pool));
+        assert_eq!(2, pool.cached());
+    }
+}
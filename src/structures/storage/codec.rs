@@ -0,0 +1,128 @@
+use std::convert::TryInto;
+
+use crate::structures::types::Codec;
+
+// Fixed-width little-endian integers, UTF-8 strings and raw bytes, stored as they are.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Native;
+
+macro_rules! native_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl Codec<$integer> for Native {
+                fn encode(&self, value: &$integer, bytes: &mut Vec<u8>) {
+                    bytes.extend_from_slice(&value.to_le_bytes());
+                }
+
+                fn decode(&self, bytes: &[u8]) -> Option<$integer> {
+                    Some(<$integer>::from_le_bytes(bytes.try_into().ok()?))
+                }
+
+                fn encoded_size(&self, _: &$integer) -> usize {
+                    std::mem::size_of::<$integer>()
+                }
+            }
+        )*
+    };
+}
+
+native_integer!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+impl Codec<String> for Native {
+    fn encode(&self, value: &String, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(value.as_bytes());
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Option<String> {
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+
+    fn encoded_size(&self, value: &String) -> usize {
+        value.len()
+    }
+}
+
+impl Codec<Vec<u8>> for Native {
+    fn encode(&self, value: &Vec<u8>, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(value);
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Option<Vec<u8>> {
+        Some(bytes.to_vec())
+    }
+
+    fn encoded_size(&self, value: &Vec<u8>) -> usize {
+        value.len()
+    }
+}
+
+// Reads the fields of an encoded page or record, every read fails once the bytes run out.
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Reader<'a> {
+        Reader { bytes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn take(&mut self, length: usize) -> Option<&'a [u8]> {
+        let taken = self.bytes.get(..length)?;
+        self.bytes = &self.bytes[length..];
+        Some(taken)
+    }
+
+    pub fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    pub fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    pub fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?))
+    }
+
+    pub fn prefixed(&mut self) -> Option<&'a [u8]> {
+        let length = self.u32()? as usize;
+        self.take(length)
+    }
+}
+
+// Writes the encoded value after its length, the counterpart of `Reader::prefixed`.
+pub fn write_prefixed<T>(codec: &impl Codec<T>, value: &T, bytes: &mut Vec<u8>) {
+    let start = bytes.len();
+    bytes.extend_from_slice(&[0; 4]);
+    codec.encode(value, bytes);
+    let length = (bytes.len() - start - 4) as u32;
+    bytes[start..start + 4].copy_from_slice(&length.to_le_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<T, C>(codec: C, value: T) -> Vec<u8> where C: Codec<T>, T: PartialEq + std::fmt::Debug {
+        let mut bytes = Vec::new();
+        codec.encode(&value, &mut bytes);
+        assert_eq!(bytes.len(), codec.encoded_size(&value));
+        assert_eq!(Some(value), codec.decode(&bytes));
+        bytes
+    }
+
+    #[test]
+    fn native() {
+        assert_eq!(vec![1, 2, 0, 0], round_trip(Native, 0x0201u32));
+        assert_eq!(8, round_trip(Native, -5i64).len());
+        assert_eq!(b"key".to_vec(), round_trip(Native, String::from("key")));
+        assert_eq!(vec![0, 255], round_trip(Native, vec![0u8, 255]));
+
+        assert_eq!(None, Codec::<u32>::decode(&Native, &[1, 2, 3]));
+        assert_eq!(None, Codec::<String>::decode(&Native, &[0xFF]));
+    }
+}
@@ -0,0 +1,6 @@
+pub mod checksum;
+pub mod codec;
+pub mod pager;
+pub mod buffer_pool;
+pub mod b_plus_tree;
+pub mod write_ahead_log;
@@ -0,0 +1,131 @@
+use std::convert::TryInto;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::structures::storage::checksum::crc32;
+use crate::structures::types::StorageError;
+
+pub const PAGE_SIZE: usize = 4096;
+
+// Bytes at the start of every page taken by its checksum.
+pub const CHECKSUM_SIZE: usize = 4;
+
+// A single file split into fixed-size pages, each starting with the CRC-32 of the rest of
+// the page, so a torn or flipped page is reported instead of being decoded.
+pub struct Pager {
+    file: File,
+    pages: u64,
+}
+
+impl Pager {
+    pub fn open(path: impl AsRef<Path>) -> Result<Pager, StorageError> {
+        let file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
+        let length = file.metadata()?.len();
+        let pages = length / PAGE_SIZE as u64;
+        if length % PAGE_SIZE as u64 != 0 {
+            return Err(StorageError::Corrupted { page: pages });
+        }
+        Ok(Pager { file, pages })
+    }
+
+    pub fn pages(&self) -> u64 {
+        self.pages
+    }
+
+    // Reserves the page after the last one, it is part of the file once it is written.
+    pub fn allocate(&mut self) -> u64 {
+        self.pages += 1;
+        self.pages - 1
+    }
+
+    pub fn read(&self, page: u64) -> Result<Vec<u8>, StorageError> {
+        if page >= self.pages {
+            return Err(StorageError::Corrupted { page });
+        }
+        let mut bytes = vec![0; PAGE_SIZE];
+        let mut file = &self.file;
+        file.seek(SeekFrom::Start(page * PAGE_SIZE as u64))?;
+        file.read_exact(&mut bytes)?;
+
+        let (checksum, content) = bytes.split_at(CHECKSUM_SIZE);
+        if u32::from_le_bytes(checksum.try_into().unwrap()) != crc32(content) {
+            return Err(StorageError::Corrupted { page });
+        }
+        Ok(bytes)
+    }
+
+    // Fills in the checksum of the page before writing it.
+    pub fn write(&self, page: u64, bytes: &mut [u8]) -> Result<(), StorageError> {
+        assert_eq!(PAGE_SIZE, bytes.len(), "page has to be exactly {} bytes", PAGE_SIZE);
+        assert!(page < self.pages, "page {} is not allocated", page);
+        let checksum = crc32(&bytes[CHECKSUM_SIZE..]);
+        bytes[..CHECKSUM_SIZE].copy_from_slice(&checksum.to_le_bytes());
+
+        let mut file = &self.file;
+        file.seek(SeekFrom::Start(page * PAGE_SIZE as u64))?;
+        file.write_all(bytes)?;
+        Ok(())
+    }
+
+    pub fn sync(&self) -> Result<(), StorageError> {
+        self.file.sync_data()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::helpers::TempPath;
+
+    use super::*;
+
+    fn page(fill: u8) -> Vec<u8> {
+        let mut bytes = vec![fill; PAGE_SIZE];
+        bytes[..CHECKSUM_SIZE].fill(0);
+        bytes
+    }
+
+    #[test]
+    fn write_read_reopen() {
+        let path = TempPath::new("pager");
+        let mut pager = Pager::open(&*path).unwrap();
+        assert_eq!(0, pager.pages());
+
+        assert_eq!(0, pager.allocate());
+        assert_eq!(1, pager.allocate());
+        pager.write(1, &mut page(7)).unwrap();
+        pager.write(0, &mut page(3)).unwrap();
+        assert_eq!(page(7)[CHECKSUM_SIZE..], pager.read(1).unwrap()[CHECKSUM_SIZE..]);
+        drop(pager);
+
+        let pager = Pager::open(&*path).unwrap();
+        assert_eq!(2, pager.pages());
+        assert_eq!(page(3)[CHECKSUM_SIZE..], pager.read(0).unwrap()[CHECKSUM_SIZE..]);
+        assert!(matches!(pager.read(2), Err(StorageError::Corrupted { page: 2 })));
+    }
+
+    #[test]
+    fn corruption() {
+        let path = TempPath::new("pager-corruption");
+        let mut pager = Pager::open(&*path).unwrap();
+        for fill in 0..3 {
+            let page = pager.allocate();
+            pager.write(page, &mut self::page(fill)).unwrap();
+        }
+        drop(pager);
+
+        let mut bytes = fs::read(&*path).unwrap();
+        bytes[PAGE_SIZE + 100] ^= 1;
+        fs::write(&*path, &bytes).unwrap();
+        let pager = Pager::open(&*path).unwrap();
+        assert!(pager.read(0).is_ok());
+        assert!(matches!(pager.read(1), Err(StorageError::Corrupted { page: 1 })));
+        assert!(pager.read(2).is_ok());
+
+        fs::write(&*path, &bytes[..PAGE_SIZE + 10]).unwrap();
+        assert!(matches!(Pager::open(&*path), Err(StorageError::Corrupted { page: 1 })));
+    }
+}
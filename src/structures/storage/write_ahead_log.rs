@@ -0,0 +1,653 @@
+use std::ffi::OsString;
+use std::fmt::Debug;
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use crate::structures::arena::binary_search_tree::ArenaBinarySearchTree;
+use crate::structures::storage::b_plus_tree::BPlusTree;
+use crate::structures::storage::checksum::crc32;
+use crate::structures::storage::codec::{write_prefixed, Native, Reader};
+use crate::structures::trees::simple_binary_tree::{BinaryTree, TraverseDirection};
+use crate::structures::types::{Codec, Map, ReadonlyMap, StorageError};
+
+const PUT: u8 = 1;
+const REMOVE: u8 = 2;
+
+// Length of the payload and its checksum.
+const RECORD_HEADER: usize = 4 + 4;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SyncPolicy {
+    // Every record is on disk before the call that wrote it returns.
+    Always,
+    // Syncs after this many records, a crash loses at most the ones since the last sync.
+    Every(usize),
+    // Leaves it to the operating system, only checkpoints and drops sync.
+    Never,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Config {
+    pub sync: SyncPolicy,
+    // Records after which the map is snapshot and the log emptied, `None` leaves it to
+    // explicit calls to `checkpoint`.
+    pub checkpoint_every: Option<usize>,
+}
+
+// Maps whose entries a checkpoint can write out. Maps iterable by reference get it for free.
+pub trait Snapshot<K, V> {
+    fn for_each_entry(&self, visit: impl FnMut(&K, &V));
+}
+
+struct RecordFormat<KC, VC> {
+    keys: KC,
+    values: VC,
+}
+
+struct Log {
+    file: File,
+    length: u64,
+    records: usize,
+    unsynced: usize,
+    sync: SyncPolicy,
+}
+
+// Makes any map durable by appending every change to a log file before applying it. A
+// record is its payload length, the CRC-32 of the payload and the payload itself, so a
+// record torn by a crash is recognised and cut off when the log is replayed. Checkpoints
+// write all entries to a snapshot next to the log, after which the log starts over.
+//
+// A `Map` whose `put` and `remove` panic on storage errors. `get_mut` panics too: a change
+// made through a plain reference could not be logged before the caller sees it succeed, so
+// values are changed in place through `try_update`, which logs the new value before it is
+// stored.
+pub struct WriteAheadLog<M, K, V, KC = Native, VC = Native> where M: Map<K, V>, KC: Codec<K>, VC: Codec<V> {
+    map: M,
+    format: RecordFormat<KC, VC>,
+    log: Log,
+    path: PathBuf,
+    checkpoint_every: Option<usize>,
+    marker: PhantomData<(K, V)>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config { sync: SyncPolicy::Always, checkpoint_every: Some(10_000) }
+    }
+}
+
+impl<M, K, V> Snapshot<K, V> for M where for<'a> &'a M: IntoIterator<Item=(&'a K, &'a V)> {
+    fn for_each_entry(&self, mut visit: impl FnMut(&K, &V)) {
+        for (key, value) in self {
+            visit(key, value);
+        }
+    }
+}
+
+impl<K, V, KC, VC> Snapshot<K, V> for BPlusTree<K, V, KC, VC> where K: Ord + Clone, V: Clone, KC: Codec<K>, VC: Codec<V> {
+    fn for_each_entry(&self, mut visit: impl FnMut(&K, &V)) {
+        for (key, value) in self.iter() {
+            visit(&key, &value);
+        }
+    }
+}
+
+impl<K, V> Snapshot<K, V> for ArenaBinarySearchTree<K, V> where K: Ord {
+    fn for_each_entry(&self, mut visit: impl FnMut(&K, &V)) {
+        for (key, value) in self.iter() {
+            visit(key, value);
+        }
+    }
+}
+
+impl<K, V> Snapshot<K, V> for BinaryTree<K, V> where K: Ord + Debug, V: Debug {
+    fn for_each_entry(&self, mut visit: impl FnMut(&K, &V)) {
+        self.traverse(&mut visit, TraverseDirection::LNR);
+    }
+}
+
+impl<KC, VC> RecordFormat<KC, VC> {
+    // A removal is a record without a value.
+    fn encode<K, V>(&self, key: &K, value: Option<&V>, bytes: &mut Vec<u8>) where KC: Codec<K>, VC: Codec<V> {
+        let start = bytes.len();
+        bytes.extend_from_slice(&[0; RECORD_HEADER]);
+        match value {
+            Some(value) => {
+                bytes.push(PUT);
+                write_prefixed(&self.keys, key, bytes);
+                write_prefixed(&self.values, value, bytes);
+            }
+            None => {
+                bytes.push(REMOVE);
+                write_prefixed(&self.keys, key, bytes);
+            }
+        }
+
+        let payload = start + RECORD_HEADER;
+        let length = (bytes.len() - payload) as u32;
+        let checksum = crc32(&bytes[payload..]);
+        bytes[start..start + 4].copy_from_slice(&length.to_le_bytes());
+        bytes[start + 4..payload].copy_from_slice(&checksum.to_le_bytes());
+    }
+
+    // Applies the records in order up to the first one that is incomplete or fails its
+    // checksum, and returns where the valid records end and how many there are.
+    fn replay<M, K, V>(&self, bytes: &[u8], map: &mut M) -> Result<(usize, usize), StorageError> where M: Map<K, V>, KC: Codec<K>, VC: Codec<V> {
+        let (mut offset, mut records) = (0, 0);
+        loop {
+            let mut reader = Reader::new(&bytes[offset..]);
+            let payload = match (reader.u32(), reader.u32()) {
+                (Some(length), Some(checksum)) => match reader.take(length as usize) {
+                    Some(payload) if crc32(payload) == checksum => payload,
+                    _ => return Ok((offset, records)),
+                },
+                _ => return Ok((offset, records)),
+            };
+            self.apply(payload, map).ok_or(StorageError::InvalidRecord { offset: offset as u64 })?;
+            offset += RECORD_HEADER + payload.len();
+            records += 1;
+        }
+    }
+
+    fn apply<M, K, V>(&self, payload: &[u8], map: &mut M) -> Option<()> where M: Map<K, V>, KC: Codec<K>, VC: Codec<V> {
+        let mut reader = Reader::new(payload);
+        let kind = reader.u8()?;
+        let key = self.keys.decode(reader.prefixed()?)?;
+        match kind {
+            PUT => {
+                let value = self.values.decode(reader.prefixed()?)?;
+                reader.is_empty().then(|| map.put(key, value))
+            }
+            REMOVE => reader.is_empty().then(|| {
+                map.remove(&key);
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl Log {
+    fn append(&mut self, record: &[u8]) -> Result<(), StorageError> {
+        // A record that was only partly written would hide every record after it.
+        if let Err(error) = self.file.write_all(record) {
+            self.file.set_len(self.length)?;
+            self.file.seek(SeekFrom::Start(self.length))?;
+            return Err(error.into());
+        }
+        self.length += record.len() as u64;
+        self.records += 1;
+        self.unsynced += 1;
+
+        match self.sync {
+            SyncPolicy::Always => self.sync(),
+            SyncPolicy::Every(records) if self.unsynced >= records => self.sync(),
+            _ => Ok(()),
+        }
+    }
+
+    fn sync(&mut self) -> Result<(), StorageError> {
+        self.file.sync_data()?;
+        self.unsynced = 0;
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<(), StorageError> {
+        self.file.set_len(0)?;
+        self.file.seek(SeekFrom::Start(0))?;
+        self.length = 0;
+        self.records = 0;
+        self.sync()
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn or_panic<T>(result: Result<T, StorageError>) -> T {
+    result.unwrap_or_else(|error| panic!("{}", error))
+}
+
+impl<M, K, V> WriteAheadLog<M, K, V> where M: Map<K, V>, Native: Codec<K> + Codec<V> {
+    pub fn open(path: impl AsRef<Path>, map: M, config: Config) -> Result<Self, StorageError> {
+        WriteAheadLog::open_with(path, map, config, Native, Native)
+    }
+}
+
+impl<M, K, V, KC, VC> WriteAheadLog<M, K, V, KC, VC> where M: Map<K, V>, KC: Codec<K>, VC: Codec<V> {
+    // Rebuilds the state from the snapshot and the log into `map`, which is expected to be
+    // empty, and truncates the log after its last valid record.
+    pub fn open_with(path: impl AsRef<Path>, mut map: M, config: Config, keys: KC, values: VC) -> Result<Self, StorageError> {
+        let path = path.as_ref().to_path_buf();
+        let format = RecordFormat { keys, values };
+
+        // Snapshots are renamed into place once complete, so anything invalid in one is not
+        // a torn write.
+        match fs::read(with_suffix(&path, ".snapshot")) {
+            Ok(bytes) => {
+                let (end, _) = format.replay(&bytes, &mut map)?;
+                if end != bytes.len() {
+                    return Err(StorageError::InvalidRecord { offset: end as u64 });
+                }
+            }
+            Err(error) if error.kind() == ErrorKind::NotFound => {}
+            Err(error) => return Err(error.into()),
+        }
+
+        let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(&path)?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        let (end, records) = format.replay(&bytes, &mut map)?;
+        if end < bytes.len() {
+            file.set_len(end as u64)?;
+            file.sync_data()?;
+        }
+        file.seek(SeekFrom::Start(end as u64))?;
+
+        Ok(WriteAheadLog {
+            map,
+            format,
+            log: Log { file, length: end as u64, records, unsynced: 0, sync: config.sync },
+            path,
+            checkpoint_every: config.checkpoint_every,
+            marker: PhantomData,
+        })
+    }
+
+    pub fn map(&self) -> &M {
+        &self.map
+    }
+
+    // Forces the records written so far to disk, whatever the sync policy.
+    pub fn sync(&mut self) -> Result<(), StorageError> {
+        self.log.sync()
+    }
+
+    // Replaces the snapshot with the current entries and empties the log. A crash before the
+    // new snapshot is renamed into place keeps the old snapshot and the log, a crash after it
+    // replays a log whose changes the snapshot already has, which yields the same entries.
+    pub fn checkpoint(&mut self) -> Result<(), StorageError> where M: Snapshot<K, V> {
+        let mut bytes = Vec::new();
+        let format = &self.format;
+        self.map.for_each_entry(|key, value| format.encode(key, Some(value), &mut bytes));
+
+        let temporary = with_suffix(&self.path, ".snapshot.tmp");
+        let mut file = File::create(&temporary)?;
+        file.write_all(&bytes)?;
+        file.sync_all()?;
+        fs::rename(&temporary, with_suffix(&self.path, ".snapshot"))?;
+        if let Some(directory) = self.path.parent().and_then(|parent| File::open(parent).ok()) {
+            directory.sync_all()?;
+        }
+        self.log.clear()
+    }
+
+    pub fn try_put(&mut self, key: K, value: V) -> Result<(), StorageError> where M: Snapshot<K, V> {
+        let mut record = Vec::new();
+        self.format.encode(&key, Some(&value), &mut record);
+        self.log.append(&record)?;
+        self.map.put(key, value);
+        self.checkpoint_if_due()
+    }
+
+    // Changes a copy of the value, which is logged before it replaces the value in the map,
+    // so a failed write leaves the map as it was. Returns `None` when the key is absent.
+    pub fn try_update<R>(&mut self, key: &K, update: impl FnOnce(&mut V) -> R) -> Result<Option<R>, StorageError> where M: Snapshot<K, V>, V: Clone {
+        let mut value = match self.map.get(key) {
+            Some(value) => value.clone(),
+            None => return Ok(None),
+        };
+        let result = update(&mut value);
+
+        let mut record = Vec::new();
+        self.format.encode(key, Some(&value), &mut record);
+        self.log.append(&record)?;
+        if let Some(current) = self.map.get_mut(key) {
+            *current = value;
+        }
+        self.checkpoint_if_due()?;
+        Ok(Some(result))
+    }
+
+    pub fn try_remove(&mut self, key: &K) -> Result<Option<V>, StorageError> where M: Snapshot<K, V> {
+        if self.map.get(key).is_none() {
+            return Ok(None);
+        }
+        let mut record = Vec::new();
+        self.format.encode::<K, V>(key, None, &mut record);
+        self.log.append(&record)?;
+        let removed = self.map.remove(key);
+        self.checkpoint_if_due()?;
+        Ok(removed)
+    }
+
+    fn checkpoint_if_due(&mut self) -> Result<(), StorageError> where M: Snapshot<K, V> {
+        if self.checkpoint_every.is_some_and(|records| self.log.records >= records) {
+            self.checkpoint()?;
+        }
+        Ok(())
+    }
+}
+
+impl<M, K, V, KC, VC> ReadonlyMap<K, V> for WriteAheadLog<M, K, V, KC, VC> where M: Map<K, V>, KC: Codec<K>, VC: Codec<V> {
+    fn get(&self, key: &K) -> Option<&V> {
+        self.map.get(key)
+    }
+
+    fn size(&self) -> usize {
+        self.map.size()
+    }
+}
+
+impl<M, K, V, KC, VC> Map<K, V> for WriteAheadLog<M, K, V, KC, VC> where M: Map<K, V> + Snapshot<K, V>, KC: Codec<K>, VC: Codec<V> {
+    fn get_mut(&mut self, _: &K) -> Option<&mut V> {
+        panic!("changes through get_mut cannot be logged, use try_update")
+    }
+
+    fn put(&mut self, key: K, value: V) {
+        or_panic(self.try_put(key, value))
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        or_panic(self.try_remove(key))
+    }
+}
+
+impl<M, K, V, KC, VC> Drop for WriteAheadLog<M, K, V, KC, VC> where M: Map<K, V>, KC: Codec<K>, VC: Codec<V> {
+    fn drop(&mut self) {
+        let _ = self.sync();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::helpers::TempPath;
+    use crate::structures::maps::skip_list::SkipList;
+
+    use super::*;
+
+    type LoggedMap = WriteAheadLog<SkipList<u64, String>, u64, String>;
+
+    const MANUAL: Config = Config { sync: SyncPolicy::Always, checkpoint_every: None };
+
+    fn open(path: &Path, config: Config) -> LoggedMap {
+        WriteAheadLog::open(path, SkipList::with_seed(50), config).unwrap()
+    }
+
+    fn entries(log: &LoggedMap) -> Vec<(u64, String)> {
+        log.map().iter().map(|(key, value)| (*key, value.clone())).collect()
+    }
+
+    fn length(path: &Path) -> u64 {
+        fs::metadata(path).unwrap().len()
+    }
+
+    #[test]
+    fn put_get_remove() {
+        let path = TempPath::new("wal");
+        let mut log = open(&path, MANUAL);
+        log.put(1, String::from("one"));
+        log.put(2, String::from("two"));
+        log.put(1, String::from("uno"));
+        assert_eq!(Some(String::from("two")), log.remove(&2));
+        assert_eq!(None, log.remove(&2));
+        assert_eq!(Some(&String::from("uno")), log.get(&1));
+        assert_eq!(1, log.size());
+        assert_eq!(4, log.log.records);
+        drop(log);
+
+        let log = open(&path, MANUAL);
+        assert_eq!(vec![(1, String::from("uno"))], entries(&log));
+    }
+
+    #[test]
+    fn update() {
+        let path = TempPath::new("wal-update");
+        let mut log = open(&path, MANUAL);
+        log.put(1, String::from("a"));
+        assert_eq!(Some(2), log.try_update(&1, |value| {
+            value.push('b');
+            value.len()
+        }).unwrap());
+        assert_eq!(2, log.log.records);
+        assert_eq!(0, log.log.unsynced);
+        log.put(2, String::from("c"));
+        log.try_update(&2, |value| value.push('d')).unwrap();
+        assert_eq!(None, log.try_update(&3, |value| value.push('e')).unwrap());
+        assert_eq!(4, log.log.records);
+        drop(log);
+
+        let log = open(&path, MANUAL);
+        assert_eq!(vec![(1, String::from("ab")), (2, String::from("cd"))], entries(&log));
+    }
+
+    #[test]
+    #[should_panic(expected = "use try_update")]
+    fn get_mut() {
+        let mut log = open(&TempPath::new("wal-get-mut"), MANUAL);
+        log.put(1, String::from("a"));
+        log.get_mut(&1);
+    }
+
+    // Anything that takes a map can be given the logged one.
+    #[test]
+    fn as_map() {
+        fn fill(map: &mut impl Map<u64, String>) {
+            for key in 0..10 {
+                map.put(key, key.to_string());
+            }
+            map.remove(&3);
+        }
+
+        let path = TempPath::new("wal-as-map");
+        let mut log = open(&path, MANUAL);
+        fill(&mut log);
+        assert_eq!(11, log.log.records);
+        drop(log);
+        assert_eq!(9, open(&path, MANUAL).size());
+    }
+
+    #[test]
+    fn sync_policy() {
+        let path = TempPath::new("wal-sync-policy");
+        let mut log = open(&path, Config { sync: SyncPolicy::Every(3), checkpoint_every: None });
+        log.put(1, String::new());
+        log.put(2, String::new());
+        assert_eq!(2, log.log.unsynced);
+        log.put(3, String::new());
+        assert_eq!(0, log.log.unsynced);
+
+        let mut log = open(&TempPath::new("wal-sync-never"), Config { sync: SyncPolicy::Never, checkpoint_every: None });
+        log.put(1, String::new());
+        log.put(2, String::new());
+        assert_eq!(2, log.log.unsynced);
+        log.sync().unwrap();
+        assert_eq!(0, log.log.unsynced);
+    }
+
+    #[test]
+    fn checkpoint() {
+        let path = TempPath::new("wal-checkpoint");
+        let mut log = open(&path, Config { sync: SyncPolicy::Always, checkpoint_every: Some(100) });
+        for key in 0..250 {
+            log.put(key % 120, key.to_string());
+        }
+        assert_eq!(50, log.log.records);
+        assert!(fs::metadata(with_suffix(&path, ".snapshot")).is_ok());
+        let expected = entries(&log);
+        drop(log);
+
+        let mut log = open(&path, MANUAL);
+        assert_eq!(expected, entries(&log));
+        assert_eq!(50, log.log.records);
+        log.checkpoint().unwrap();
+        assert_eq!(0, length(&path));
+        drop(log);
+        assert_eq!(expected, entries(&open(&path, MANUAL)));
+        fs::remove_file(with_suffix(&path, ".snapshot")).unwrap();
+    }
+
+    #[test]
+    fn crash_during_checkpoint() {
+        let path = TempPath::new("wal-crash-during-checkpoint");
+        let mut log = open(&path, MANUAL);
+        for key in 0..20 {
+            log.put(key, key.to_string());
+        }
+        log.checkpoint().unwrap();
+        for key in 10..30 {
+            log.remove(&key);
+            log.put(key + 5, String::from("changed"));
+        }
+        let bytes = fs::read(&*path).unwrap();
+
+        // The new snapshot is in place but the log was not emptied yet.
+        log.checkpoint().unwrap();
+        let expected = entries(&log);
+        drop(log);
+        fs::write(&*path, &bytes).unwrap();
+        assert_eq!(expected, entries(&open(&path, MANUAL)));
+        fs::remove_file(with_suffix(&path, ".snapshot")).unwrap();
+    }
+
+    #[test]
+    fn crash_at_every_offset() {
+        let path = TempPath::new("wal-crash");
+        let mut rng = StdRng::seed_from_u64(50);
+        let mut log = open(&path, MANUAL);
+        let mut ends = vec![0];
+        let mut states = vec![Vec::new()];
+        for _ in 0..40 {
+            let key = rng.gen_range(0..10);
+            match rng.gen_range(0..3) {
+                0 => {
+                    log.remove(&key);
+                }
+                1 => {
+                    log.try_update(&key, |value| value.push('!')).unwrap();
+                }
+                _ => log.put(key, "x".repeat(rng.gen_range(0..8))),
+            }
+            log.sync().unwrap();
+            if length(&path) != *ends.last().unwrap() {
+                ends.push(length(&path));
+                states.push(entries(&log));
+            }
+        }
+        drop(log);
+        let bytes = fs::read(&*path).unwrap();
+        assert_eq!(bytes.len() as u64, *ends.last().unwrap());
+
+        for offset in 0..=bytes.len() {
+            let crashed = TempPath::new("wal-crashed");
+            fs::write(&*crashed, &bytes[..offset]).unwrap();
+            let complete = ends.iter().rposition(|end| *end <= offset as u64).unwrap();
+
+            let mut log = open(&crashed, MANUAL);
+            assert_eq!(states[complete], entries(&log));
+            assert_eq!(ends[complete], length(&crashed));
+
+            // Records appended after the recovery are not hidden behind the torn one.
+            log.put(100, String::from("after"));
+            drop(log);
+            let log = open(&crashed, MANUAL);
+            assert_eq!(states[complete].len() + 1, log.size());
+            assert_eq!(Some(&String::from("after")), log.get(&100));
+        }
+    }
+
+    #[test]
+    fn damaged_record() {
+        let path = TempPath::new("wal-damaged");
+        let mut log = open(&path, MANUAL);
+        log.put(1, String::from("one"));
+        let end = length(&path) as usize;
+        log.put(2, String::from("two"));
+        log.put(3, String::from("three"));
+        drop(log);
+
+        let mut bytes = fs::read(&*path).unwrap();
+        bytes[end + RECORD_HEADER + 2] ^= 0x40;
+        fs::write(&*path, &bytes).unwrap();
+        let log = open(&path, MANUAL);
+        assert_eq!(vec![(1, String::from("one"))], entries(&log));
+        assert_eq!(end as u64, length(&path));
+    }
+
+    #[test]
+    fn invalid_record() {
+        let path = TempPath::new("wal-invalid");
+        let mut log = open(&path, MANUAL);
+        log.put(1, String::from("one"));
+        drop(log);
+
+        // The checksum holds, but the value is not a number.
+        let log = WriteAheadLog::open(&*path, SkipList::<u64, u64>::new(), MANUAL);
+        assert!(matches!(log, Err(StorageError::InvalidRecord { offset: 0 })));
+    }
+
+    #[test]
+    fn b_plus_tree() {
+        let path = TempPath::new("wal-b-plus-tree");
+        let open = |tree: &TempPath| WriteAheadLog::open(&*path, BPlusTree::<u64, String>::open(&**tree, 4).unwrap(), MANUAL).unwrap();
+        let tree = TempPath::new("wal-b-plus-tree-pages");
+        let mut log = open(&tree);
+        for key in 0..300 {
+            log.put(key, key.to_string());
+        }
+        for key in (0..300).step_by(3) {
+            log.remove(&key);
+        }
+        log.try_update(&1, |value| value.push('!')).unwrap();
+        log.checkpoint().unwrap();
+        log.put(1000, String::from("after"));
+        let expected = log.map().iter().collect::<Vec<_>>();
+        assert_eq!(Some(&String::from("1!")), log.get(&1));
+        drop(log);
+
+        // The snapshot and the log rebuild the tree into a fresh file.
+        let log = open(&TempPath::new("wal-b-plus-tree-rebuilt"));
+        assert_eq!(201, log.size());
+        assert!(log.map().is_valid());
+        assert_eq!(expected, log.map().iter().collect::<Vec<_>>());
+        drop(log);
+        fs::remove_file(with_suffix(&path, ".snapshot")).unwrap();
+    }
+
+    #[test]
+    fn against_btree_map() {
+        let path = TempPath::new("wal-against-btree-map");
+        let mut rng = StdRng::seed_from_u64(50);
+        let config = Config { sync: SyncPolicy::Every(10), checkpoint_every: Some(300) };
+        let mut log = open(&path, config);
+        let mut expected = BTreeMap::new();
+
+        for step in 0..5000 {
+            let key = rng.gen_range(0..200);
+            if rng.gen_bool(0.6) {
+                log.put(key, key.to_string());
+                expected.insert(key, key.to_string());
+            } else {
+                assert_eq!(expected.remove(&key), log.remove(&key));
+            }
+            assert_eq!(expected.len(), log.size());
+
+            if step % 500 == 499 {
+                drop(log);
+                log = open(&path, config);
+                assert!(entries(&log).into_iter().eq(expected.clone()));
+            }
+        }
+        drop(log);
+        fs::remove_file(with_suffix(&path, ".snapshot")).unwrap();
+    }
+}
@@ -0,0 +1,730 @@
+use std::cell::OnceCell;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::ops::{Bound, RangeBounds};
+use std::rc::Rc;
+use std::str;
+
+use crate::structures::types::*;
+
+// Longest leaf in bytes.
+const MAX_LEAF: usize = 1024;
+
+// Leaves hold UTF-8 text and cache how many chars and newlines it has, so lookups by char or
+// line only decode the leaf they end in. `ReadonlySeq<char>` has to hand out references, a
+// leaf decodes its chars for that on the first `get` that reaches it.
+// Nodes are immutable and shared: edits rebuild only the path to the change, so cloning a
+// rope or slicing it is cheap.
+enum Node {
+    Leaf {
+        text: Box<str>,
+        chars: usize,
+        newlines: usize,
+        decoded: OnceCell<Box<[char]>>,
+    },
+    Branch {
+        left: Rc<Node>,
+        right: Rc<Node>,
+        chars: usize,
+        bytes: usize,
+        newlines: usize,
+        height: usize,
+    },
+}
+
+// AVL balanced tree of char chunks. Concatenation joins two trees along the spine of the
+// taller one, splitting descends once and joins the pieces on the way back, both in O(log n).
+#[derive(Clone)]
+pub struct Rope {
+    root: Rc<Node>,
+}
+
+pub struct Chunks<'a> {
+    stack: Vec<&'a Node>,
+}
+
+pub struct Chars<'a> {
+    chunks: Chunks<'a>,
+    current: str::Chars<'a>,
+}
+
+pub struct Lines<'a> {
+    chars: Chars<'a>,
+    done: bool,
+}
+
+impl Node {
+    fn leaf(text: &str) -> Rc<Node> {
+        Rc::new(Node::Leaf {
+            text: text.into(),
+            chars: text.chars().count(),
+            newlines: text.matches('\n').count(),
+            decoded: OnceCell::new(),
+        })
+    }
+
+    fn branch(left: Rc<Node>, right: Rc<Node>) -> Rc<Node> {
+        Rc::new(Node::Branch {
+            chars: left.chars() + right.chars(),
+            bytes: left.bytes() + right.bytes(),
+            newlines: left.newlines() + right.newlines(),
+            height: left.height().max(right.height()) + 1,
+            left,
+            right,
+        })
+    }
+
+    fn empty() -> Rc<Node> {
+        Node::leaf("")
+    }
+
+    fn chars(&self) -> usize {
+        match self {
+            Node::Leaf { chars, .. } | Node::Branch { chars, .. } => *chars,
+        }
+    }
+
+    fn bytes(&self) -> usize {
+        match self {
+            Node::Leaf { text, .. } => text.len(),
+            Node::Branch { bytes, .. } => *bytes,
+        }
+    }
+
+    fn newlines(&self) -> usize {
+        match self {
+            Node::Leaf { newlines, .. } | Node::Branch { newlines, .. } => *newlines,
+        }
+    }
+
+    fn height(&self) -> usize {
+        match self {
+            Node::Leaf { .. } => 0,
+            Node::Branch { height, .. } => *height,
+        }
+    }
+
+    fn children(&self) -> (Rc<Node>, Rc<Node>) {
+        match self {
+            Node::Branch { left, right, .. } => (Rc::clone(left), Rc::clone(right)),
+            Node::Leaf { .. } => unreachable!(),
+        }
+    }
+
+    // Builds a perfectly balanced tree over the chunks.
+    fn build(mut leaves: Vec<Rc<Node>>) -> Rc<Node> {
+        match leaves.len() {
+            0 => Node::empty(),
+            1 => leaves.pop().unwrap(),
+            len => {
+                let right = leaves.split_off(len / 2);
+                Node::branch(Node::build(leaves), Node::build(right))
+            }
+        }
+    }
+}
+
+// Byte offset of the char at `index` in `text`, the length of the text past its last char.
+fn byte_offset(text: &str, index: usize) -> usize {
+    text.char_indices().nth(index).map_or(text.len(), |(offset, _)| offset)
+}
+
+fn rotate_left(node: Rc<Node>) -> Rc<Node> {
+    let (a, right) = node.children();
+    let (b, c) = right.children();
+    Node::branch(Node::branch(a, b), c)
+}
+
+fn rotate_right(node: Rc<Node>) -> Rc<Node> {
+    let (left, c) = node.children();
+    let (a, b) = left.children();
+    Node::branch(a, Node::branch(b, c))
+}
+
+fn join(left: Rc<Node>, right: Rc<Node>) -> Rc<Node> {
+    if left.chars() == 0 {
+        return right;
+    }
+    if right.chars() == 0 {
+        return left;
+    }
+
+    if let (Node::Leaf { text: first, .. }, Node::Leaf { text: second, .. }) = (&*left, &*right) {
+        if first.len() + second.len() <= MAX_LEAF {
+            return Node::leaf(&[&**first, &**second].concat());
+        }
+    }
+
+    if left.height() > right.height() + 1 {
+        join_right(left, right)
+    } else if right.height() > left.height() + 1 {
+        join_left(left, right)
+    } else {
+        Node::branch(left, right)
+    }
+}
+
+// Descends the right spine of the taller left tree until the heights match.
+fn join_right(left: Rc<Node>, right: Rc<Node>) -> Rc<Node> {
+    let (a, c) = left.children();
+    if c.height() <= right.height() + 1 {
+        let joined = Node::branch(c, right);
+        if joined.height() <= a.height() + 1 {
+            Node::branch(a, joined)
+        } else {
+            rotate_left(Node::branch(a, rotate_right(joined)))
+        }
+    } else {
+        let joined = join_right(c, right);
+        if joined.height() <= a.height() + 1 {
+            Node::branch(a, joined)
+        } else {
+            rotate_left(Node::branch(a, joined))
+        }
+    }
+}
+
+fn join_left(left: Rc<Node>, right: Rc<Node>) -> Rc<Node> {
+    let (c, a) = right.children();
+    if c.height() <= left.height() + 1 {
+        let joined = Node::branch(left, c);
+        if joined.height() <= a.height() + 1 {
+            Node::branch(joined, a)
+        } else {
+            rotate_right(Node::branch(rotate_left(joined), a))
+        }
+    } else {
+        let joined = join_left(left, c);
+        if joined.height() <= a.height() + 1 {
+            Node::branch(joined, a)
+        } else {
+            rotate_right(Node::branch(joined, a))
+        }
+    }
+}
+
+fn split(node: &Rc<Node>, at: usize) -> (Rc<Node>, Rc<Node>) {
+    if at == 0 {
+        return (Node::empty(), Rc::clone(node));
+    }
+    if at >= node.chars() {
+        return (Rc::clone(node), Node::empty());
+    }
+
+    match &**node {
+        Node::Leaf { text, .. } => {
+            let (first, second) = text.split_at(byte_offset(text, at));
+            (Node::leaf(first), Node::leaf(second))
+        }
+        Node::Branch { left, right, .. } => {
+            if at <= left.chars() {
+                let (first, second) = split(left, at);
+                (first, join(second, Rc::clone(right)))
+            } else {
+                let (first, second) = split(right, at - left.chars());
+                (join(Rc::clone(left), first), second)
+            }
+        }
+    }
+}
+
+// Cuts the text into leaves of at most `MAX_LEAF` bytes, at char boundaries.
+fn leaves(mut text: &str) -> Vec<Rc<Node>> {
+    let mut leaves = Vec::new();
+    while !text.is_empty() {
+        let mut end = text.len().min(MAX_LEAF);
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (chunk, rest) = text.split_at(end);
+        leaves.push(Node::leaf(chunk));
+        text = rest;
+    }
+    leaves
+}
+
+impl Rope {
+    pub fn new() -> Rope {
+        Rope { root: Node::empty() }
+    }
+
+    pub fn len_bytes(&self) -> usize {
+        self.root.bytes()
+    }
+
+    pub fn len_lines(&self) -> usize {
+        self.root.newlines() + 1
+    }
+
+    fn bounds<R>(&self, range: R) -> Result<(usize, usize), SeqError> where R: RangeBounds<usize> {
+        let size = self.size();
+        let after = |index: usize| index.checked_add(1).ok_or(SeqError::OutOfBound { index, size });
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => after(start)?,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => after(end)?,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => size,
+        };
+
+        if end > size {
+            Err(SeqError::OutOfBound { index: end, size })
+        } else if start > end {
+            Err(SeqError::OutOfBound { index: start, size })
+        } else {
+            Ok((start, end))
+        }
+    }
+
+    pub fn insert(&mut self, index: usize, text: &str) -> Result<(), SeqError> {
+        let size = self.size();
+        if index > size {
+            return Err(SeqError::OutOfBound { index, size });
+        }
+
+        let (left, right) = split(&self.root, index);
+        let middle = Node::build(leaves(text));
+        self.root = join(join(left, middle), right);
+        Ok(())
+    }
+
+    pub fn delete<R>(&mut self, range: R) -> Result<(), SeqError> where R: RangeBounds<usize> {
+        let (start, end) = self.bounds(range)?;
+        let (left, rest) = split(&self.root, start);
+        let (_, right) = split(&rest, end - start);
+        self.root = join(left, right);
+        Ok(())
+    }
+
+    pub fn slice<R>(&self, range: R) -> Result<Rope, SeqError> where R: RangeBounds<usize> {
+        let (start, end) = self.bounds(range)?;
+        let (_, rest) = split(&self.root, start);
+        let (middle, _) = split(&rest, end - start);
+        Ok(Rope { root: middle })
+    }
+
+    pub fn concat(&self, other: &Rope) -> Rope {
+        Rope { root: join(Rc::clone(&self.root), Rc::clone(&other.root)) }
+    }
+
+    pub fn append(&mut self, other: &Rope) {
+        self.root = join(Rc::clone(&self.root), Rc::clone(&other.root));
+    }
+
+    // Byte offset of the char at `index`, `len_bytes` for the end of the rope.
+    pub fn char_to_byte(&self, mut index: usize) -> Option<usize> {
+        if index > self.size() {
+            return None;
+        }
+
+        let mut node = &*self.root;
+        let mut offset = 0;
+        loop {
+            match node {
+                Node::Leaf { text, .. } => {
+                    return Some(offset + byte_offset(text, index));
+                }
+                Node::Branch { left, right, .. } => {
+                    if index < left.chars() {
+                        node = left;
+                    } else {
+                        index -= left.chars();
+                        offset += left.bytes();
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    // Index of the char starting at byte `offset`, `None` if the offset is not a char boundary.
+    pub fn byte_to_char(&self, mut offset: usize) -> Option<usize> {
+        if offset > self.len_bytes() {
+            return None;
+        }
+
+        let mut node = &*self.root;
+        let mut index = 0;
+        loop {
+            match node {
+                Node::Leaf { text, .. } => {
+                    return text.get(..offset).map(|prefix| index + prefix.chars().count());
+                }
+                Node::Branch { left, right, .. } => {
+                    if offset < left.bytes() {
+                        node = left;
+                    } else {
+                        offset -= left.bytes();
+                        index += left.chars();
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn is_char_boundary(&self, offset: usize) -> bool {
+        self.byte_to_char(offset).is_some()
+    }
+
+    // Line of the char at `index`, lines are separated by '\n'.
+    pub fn char_to_line(&self, mut index: usize) -> Option<usize> {
+        if index > self.size() {
+            return None;
+        }
+
+        let mut node = &*self.root;
+        let mut line = 0;
+        loop {
+            match node {
+                Node::Leaf { text, .. } => {
+                    return Some(line + text[..byte_offset(text, index)].matches('\n').count());
+                }
+                Node::Branch { left, right, .. } => {
+                    if index < left.chars() {
+                        node = left;
+                    } else {
+                        index -= left.chars();
+                        line += left.newlines();
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    // Index of the first char of `line`.
+    pub fn line_to_char(&self, line: usize) -> Option<usize> {
+        if line == 0 {
+            return Some(0);
+        }
+        if line > self.root.newlines() {
+            return None;
+        }
+
+        // Looks for the newline that ends the previous line.
+        let mut remaining = line - 1;
+        let mut node = &*self.root;
+        let mut index = 0;
+        loop {
+            match node {
+                Node::Leaf { text, .. } => {
+                    let (position, _) = text.match_indices('\n').nth(remaining).unwrap();
+                    return Some(index + text[..position].chars().count() + 1);
+                }
+                Node::Branch { left, right, .. } => {
+                    if remaining < left.newlines() {
+                        node = left;
+                    } else {
+                        remaining -= left.newlines();
+                        index += left.chars();
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    // Rebuilds the tree with full leaves, useful after many small edits fragmented it.
+    pub fn rebalance(&mut self) {
+        self.root = Node::build(leaves(&self.to_string()));
+    }
+
+    pub fn chunks(&self) -> Chunks<'_> {
+        Chunks { stack: vec![&*self.root] }
+    }
+
+    pub fn chars(&self) -> Chars<'_> {
+        Chars {
+            chunks: self.chunks(),
+            current: "".chars(),
+        }
+    }
+
+    // Lines without their terminating '\n', a trailing newline yields a final empty line.
+    pub fn lines(&self) -> Lines<'_> {
+        Lines {
+            chars: self.chars(),
+            done: false,
+        }
+    }
+
+    #[cfg(test)]
+    fn height(&self) -> usize {
+        self.root.height()
+    }
+
+    #[cfg(test)]
+    fn check(&self) {
+        fn check(node: &Node) {
+            if let Node::Branch { left, right, .. } = node {
+                assert!(left.height().abs_diff(right.height()) <= 1, "unbalanced node");
+                assert!(left.chars() > 0 && right.chars() > 0, "empty child");
+                check(left);
+                check(right);
+            }
+        }
+        check(&self.root);
+    }
+}
+
+impl ReadonlySeq<char> for Rope {
+    fn get(&self, mut index: usize) -> Option<&char> {
+        let mut node = &*self.root;
+        loop {
+            match node {
+                Node::Leaf { text, decoded, .. } => {
+                    return decoded.get_or_init(|| text.chars().collect()).get(index);
+                }
+                Node::Branch { left, right, .. } => {
+                    if index < left.chars() {
+                        node = left;
+                    } else {
+                        index -= left.chars();
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    fn index_of(&self, value: &char) -> Option<usize> {
+        self.chars().position(|c| c == *value)
+    }
+
+    fn size(&self) -> usize {
+        self.root.chars()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.root.chars() == 0
+    }
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.stack.pop() {
+            match node {
+                Node::Leaf { text, .. } if !text.is_empty() => return Some(text),
+                Node::Leaf { .. } => {}
+                Node::Branch { left, right, .. } => {
+                    self.stack.push(right);
+                    self.stack.push(left);
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<'a> Iterator for Chars<'a> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(c) = self.current.next() {
+                return Some(c);
+            }
+            self.current = self.chunks.next()?.chars();
+        }
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let mut line = String::new();
+        for c in &mut self.chars {
+            if c == '\n' {
+                return Some(line);
+            }
+            line.push(c);
+        }
+        self.done = true;
+        Some(line)
+    }
+}
+
+impl From<&str> for Rope {
+    fn from(text: &str) -> Self {
+        Rope { root: Node::build(leaves(text)) }
+    }
+}
+
+impl Display for Rope {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for chunk in self.chunks() {
+            f.write_str(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for Rope {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.to_string(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    fn char_range(text: &str, start: usize, end: usize) -> std::ops::Range<usize> {
+        let byte = |index| text.char_indices().map(|(offset, _)| offset).chain(Some(text.len())).nth(index).unwrap();
+        byte(start)..byte(end)
+    }
+
+    #[test]
+    fn create_new() {
+        let rope = Rope::new();
+        assert!(rope.is_empty());
+        assert_eq!(0, rope.size());
+        assert_eq!(1, rope.len_lines());
+        assert_eq!(None, rope.get(0));
+        assert_eq!("", rope.to_string());
+        assert_eq!(vec![String::new()], rope.lines().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn edit() {
+        let mut rope = Rope::from("Hello world");
+        rope.insert(5, ", dear").unwrap();
+        assert_eq!("Hello, dear world", rope.to_string());
+        rope.delete(5..11).unwrap();
+        assert_eq!("Hello world", rope.to_string());
+        rope.insert(11, "!").unwrap();
+        rope.delete(..6).unwrap();
+        assert_eq!("world!", rope.to_string());
+
+        assert_eq!(Err(SeqError::OutOfBound { index: 7, size: 6 }), rope.insert(7, "x"));
+        assert_eq!(Err(SeqError::OutOfBound { index: 8, size: 6 }), rope.delete(2..8));
+        assert_eq!(Err(SeqError::OutOfBound { index: usize::MAX, size: 6 }), rope.delete(..=usize::MAX));
+        let excluded = (Bound::Excluded(usize::MAX), Bound::Unbounded);
+        assert_eq!(Err(SeqError::OutOfBound { index: usize::MAX, size: 6 }), rope.slice(excluded).map(|_| ()));
+        assert_eq!(Some(&'w'), rope.get(0));
+        assert_eq!(Some(5), rope.index_of(&'!'));
+    }
+
+    #[test]
+    fn slice_and_concat() {
+        let rope = Rope::from("αβγ δεζ");
+        let first = rope.slice(..3).unwrap();
+        let second = rope.slice(4..).unwrap();
+        assert_eq!("αβγ", first.to_string());
+        assert_eq!("δεζ", second.to_string());
+        assert_eq!("δεζαβγ", second.concat(&first).to_string());
+        assert_eq!("αβγ δεζ", rope.to_string());
+
+        let mut text = first.clone();
+        text.append(&Rope::from("!"));
+        assert_eq!("αβγ!", text.to_string());
+        assert_eq!("αβγ", first.to_string());
+    }
+
+    #[test]
+    fn utf8() {
+        let rope = Rope::from("aé日😀b");
+        assert_eq!(5, rope.size());
+        assert_eq!(1 + 2 + 3 + 4 + 1, rope.len_bytes());
+        assert_eq!(Some(3), rope.char_to_byte(2));
+        assert_eq!(Some(11), rope.char_to_byte(5));
+        assert_eq!(Some(3), rope.byte_to_char(6));
+        assert_eq!(None, rope.byte_to_char(7));
+        assert!(!rope.is_char_boundary(2));
+        assert!(rope.is_char_boundary(11));
+        assert_eq!(Some(&'😀'), rope.get(3));
+        assert_eq!(vec!["aé日😀b"], rope.chunks().collect::<Vec<_>>());
+
+        // Leaves end at a char boundary, three byte chars fill at most 1023 of the 1024 bytes.
+        let rope = Rope::from("日".repeat(1000).as_str());
+        assert_eq!(vec![1023, 1023, 954], rope.chunks().map(str::len).collect::<Vec<_>>());
+        assert_eq!(Some(&'日'), rope.get(999));
+        assert_eq!(Some(341), rope.byte_to_char(1023));
+    }
+
+    #[test]
+    fn lines() {
+        let rope = Rope::from("first\nsecond\n\nfourth");
+        assert_eq!(4, rope.len_lines());
+        assert_eq!(vec!["first", "second", "", "fourth"], rope.lines().collect::<Vec<_>>());
+        assert_eq!(Some(0), rope.line_to_char(0));
+        assert_eq!(Some(6), rope.line_to_char(1));
+        assert_eq!(Some(14), rope.line_to_char(3));
+        assert_eq!(None, rope.line_to_char(4));
+        assert_eq!(Some(1), rope.char_to_line(6));
+        assert_eq!(Some(0), rope.char_to_line(5));
+        assert_eq!(Some(3), rope.char_to_line(20));
+    }
+
+    #[test]
+    fn against_string() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let alphabet = ['a', 'b', 'é', '日', '😀', '\n', ' '];
+        let mut rope = Rope::new();
+        let mut expected = String::new();
+
+        for _ in 0..1000 {
+            let size = expected.chars().count();
+            if rng.gen_bool(0.6) || size == 0 {
+                let index = rng.gen_range(0..=size);
+                let text = (0..rng.gen_range(1..300))
+                    .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+                    .collect::<String>();
+                rope.insert(index, &text).unwrap();
+                expected.insert_str(char_range(&expected, index, index).start, &text);
+            } else {
+                let start = rng.gen_range(0..size);
+                let end = rng.gen_range(start..=size.min(start + 400));
+                rope.delete(start..end).unwrap();
+                expected.replace_range(char_range(&expected, start, end), "");
+            }
+
+            rope.check();
+            assert_eq!(expected.chars().count(), rope.size());
+            assert_eq!(expected.len(), rope.len_bytes());
+            assert_eq!(expected.matches('\n').count() + 1, rope.len_lines());
+        }
+
+        assert_eq!(expected, rope.to_string());
+        assert!(rope.lines().eq(expected.split('\n').map(String::from)));
+        let size = rope.size();
+        let slice = rope.slice(size / 3..size / 2).unwrap();
+        assert_eq!(&expected[char_range(&expected, size / 3, size / 2)], slice.to_string());
+    }
+
+    #[test]
+    fn large_buffer() {
+        let line = "the quick brown fox jumps over the lazy dog\n";
+        let mut rope = Rope::from(line.repeat(25_000).as_str());
+        assert_eq!(25_001, rope.len_lines());
+        let height = rope.height();
+
+        for i in 0..1000 {
+            let index = rope.line_to_char(i * 20).unwrap();
+            rope.insert(index, "// ").unwrap();
+            rope.delete(index + 3..index + 7).unwrap();
+        }
+        rope.check();
+        assert!(rope.height() <= height + 2);
+        assert_eq!(Some(0), rope.index_of(&'/'));
+
+        rope.rebalance();
+        rope.check();
+        assert!(rope.chunks().all(|chunk| chunk.len() <= MAX_LEAF));
+        assert_eq!(rope.to_string(), rope.chunks().collect::<String>());
+        assert_eq!(25_001, rope.lines().count());
+    }
+}
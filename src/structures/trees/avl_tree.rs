@@ -0,0 +1,420 @@
+use std::cmp::{self, Ordering};
+use std::fmt::{self, Debug, Formatter};
+use std::mem;
+
+use crate::structures::types::{Map, ReadonlyMap};
+
+type Link<K, V> = Option<Box<Node<K, V>>>;
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    height: usize,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+// Heights of the two subtrees of every node differ by at most one, so the tree height stays
+// below 1.44 * log2(n + 2) and every operation is O(log n). The recursion of `put` and
+// `remove` is bounded by that height.
+pub struct AvlTree<K, V> {
+    root: Link<K, V>,
+    size: usize,
+    rotations: usize,
+}
+
+pub struct Iter<'a, K, V> {
+    stack: Vec<&'a Node<K, V>>,
+    size: usize,
+}
+
+impl<K, V> Node<K, V> {
+    fn new_link(key: K, value: V) -> Box<Node<K, V>> {
+        Box::new(Node {
+            key,
+            value,
+            height: 1,
+            left: None,
+            right: None,
+        })
+    }
+
+    fn update(&mut self) {
+        self.height = 1 + cmp::max(height(&self.left), height(&self.right));
+    }
+
+    fn balance(&self) -> isize {
+        height(&self.left) as isize - height(&self.right) as isize
+    }
+}
+
+fn height<K, V>(link: &Link<K, V>) -> usize {
+    link.as_ref().map_or(0, |node| node.height)
+}
+
+fn rotate_left<K, V>(mut node: Box<Node<K, V>>, rotations: &mut usize) -> Box<Node<K, V>> {
+    let mut right = node.right.take().unwrap();
+    node.right = right.left.take();
+    node.update();
+    right.left = Some(node);
+    right.update();
+    *rotations += 1;
+    right
+}
+
+fn rotate_right<K, V>(mut node: Box<Node<K, V>>, rotations: &mut usize) -> Box<Node<K, V>> {
+    let mut left = node.left.take().unwrap();
+    node.left = left.right.take();
+    node.update();
+    left.right = Some(node);
+    left.update();
+    *rotations += 1;
+    left
+}
+
+// Restores the balance of a node whose subtrees differ in height by at most two.
+fn rebalance<K, V>(mut node: Box<Node<K, V>>, rotations: &mut usize) -> Box<Node<K, V>> {
+    node.update();
+    match node.balance() {
+        2 => {
+            // Left-right case turns into the left-left case first.
+            if node.left.as_ref().unwrap().balance() < 0 {
+                node.left = node.left.take().map(|left| rotate_left(left, rotations));
+            }
+            rotate_right(node, rotations)
+        }
+        -2 => {
+            // Right-left case turns into the right-right case first.
+            if node.right.as_ref().unwrap().balance() > 0 {
+                node.right = node.right.take().map(|right| rotate_right(right, rotations));
+            }
+            rotate_left(node, rotations)
+        }
+        _ => node,
+    }
+}
+
+fn insert<K, V>(link: Link<K, V>, key: K, value: V, rotations: &mut usize) -> (Box<Node<K, V>>, Option<V>) where K: Ord {
+    let mut node = match link {
+        Some(node) => node,
+        None => return (Node::new_link(key, value), None),
+    };
+
+    let replaced = match key.cmp(&node.key) {
+        Ordering::Equal => {
+            let replaced = mem::replace(&mut node.value, value);
+            return (node, Some(replaced));
+        }
+        Ordering::Less => {
+            let (left, replaced) = insert(node.left.take(), key, value, rotations);
+            node.left = Some(left);
+            replaced
+        }
+        Ordering::Greater => {
+            let (right, replaced) = insert(node.right.take(), key, value, rotations);
+            node.right = Some(right);
+            replaced
+        }
+    };
+    (rebalance(node, rotations), replaced)
+}
+
+fn remove<K, V>(link: Link<K, V>, key: &K, rotations: &mut usize) -> (Link<K, V>, Option<V>) where K: Ord {
+    let mut node = match link {
+        Some(node) => node,
+        None => return (None, None),
+    };
+
+    let removed = match key.cmp(&node.key) {
+        Ordering::Equal => {
+            let Node { value, left, right, .. } = *node;
+            let link = match (left, right) {
+                (None, right) => right,
+                (left, None) => left,
+                // The minimum of the right subtree takes the place of the removed node.
+                (left, Some(right)) => {
+                    let (right, mut min) = remove_min(right, rotations);
+                    min.left = left;
+                    min.right = right;
+                    Some(rebalance(min, rotations))
+                }
+            };
+            return (link, Some(value));
+        }
+        Ordering::Less => {
+            let (left, removed) = remove(node.left.take(), key, rotations);
+            node.left = left;
+            removed
+        }
+        Ordering::Greater => {
+            let (right, removed) = remove(node.right.take(), key, rotations);
+            node.right = right;
+            removed
+        }
+    };
+    (Some(rebalance(node, rotations)), removed)
+}
+
+fn remove_min<K, V>(mut node: Box<Node<K, V>>, rotations: &mut usize) -> (Link<K, V>, Box<Node<K, V>>) {
+    match node.left.take() {
+        Some(left) => {
+            let (left, min) = remove_min(left, rotations);
+            node.left = left;
+            (Some(rebalance(node, rotations)), min)
+        }
+        None => (node.right.take(), node),
+    }
+}
+
+// Returns the height and the number of nodes of a valid subtree whose keys lie strictly
+// between `lower` and `upper`.
+fn check<'a, K, V>(link: &'a Link<K, V>, lower: Option<&'a K>, upper: Option<&'a K>) -> Option<(usize, usize)> where K: Ord {
+    let node = match link {
+        Some(node) => node,
+        None => return Some((0, 0)),
+    };
+
+    if lower.is_some_and(|lower| node.key <= *lower) || upper.is_some_and(|upper| node.key >= *upper) {
+        return None;
+    }
+
+    let (left_height, left_size) = check(&node.left, lower, Some(&node.key))?;
+    let (right_height, right_size) = check(&node.right, Some(&node.key), upper)?;
+    let height = 1 + cmp::max(left_height, right_height);
+    if left_height.abs_diff(right_height) > 1 || node.height != height {
+        return None;
+    }
+    Some((height, left_size + right_size + 1))
+}
+
+impl<K, V> AvlTree<K, V> {
+    pub fn new() -> Self {
+        AvlTree {
+            root: None,
+            size: 0,
+            rotations: 0,
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        height(&self.root)
+    }
+
+    // Number of rotations performed since the tree was created, a double rotation counts
+    // as two.
+    pub fn rotations(&self) -> usize {
+        self.rotations
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let mut iter = Iter {
+            stack: Vec::new(),
+            size: self.size,
+        };
+        iter.push_left(&self.root);
+        iter
+    }
+
+    // Checks the search order, the stored heights, the balance of every node and the size.
+    pub fn is_valid(&self) -> bool where K: Ord {
+        check(&self.root, None, None).is_some_and(|(_, size)| size == self.size)
+    }
+}
+
+impl<K, V> ReadonlyMap<K, V> for AvlTree<K, V> where K: Ord {
+    fn get(&self, key: &K) -> Option<&V> {
+        let mut current = &self.root;
+        while let Some(node) = current {
+            current = match key.cmp(&node.key) {
+                Ordering::Equal => return Some(&node.value),
+                Ordering::Less => &node.left,
+                Ordering::Greater => &node.right,
+            };
+        }
+        None
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl<K, V> Map<K, V> for AvlTree<K, V> where K: Ord {
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let mut current = &mut self.root;
+        while let Some(node) = current {
+            current = match key.cmp(&node.key) {
+                Ordering::Equal => return Some(&mut node.value),
+                Ordering::Less => &mut node.left,
+                Ordering::Greater => &mut node.right,
+            };
+        }
+        None
+    }
+
+    fn put(&mut self, key: K, value: V) {
+        let (root, replaced) = insert(self.root.take(), key, value, &mut self.rotations);
+        self.root = Some(root);
+        if replaced.is_none() {
+            self.size += 1;
+        }
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        let (root, removed) = remove(self.root.take(), key, &mut self.rotations);
+        self.root = root;
+        if removed.is_some() {
+            self.size -= 1;
+        }
+        removed
+    }
+}
+
+impl<K, V> Debug for AvlTree<K, V> where K: Debug, V: Debug {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+    fn push_left(&mut self, mut link: &'a Link<K, V>) {
+        while let Some(node) = link {
+            self.stack.push(node);
+            link = &node.left;
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_left(&node.right);
+        self.size -= 1;
+        Some((&node.key, &node.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+impl<'a, K, V> IntoIterator for &'a AvlTree<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::helpers::compare_with_btree_map;
+
+    use super::*;
+
+    fn create_tree(keys: &[i32]) -> AvlTree<i32, String> {
+        let mut tree = AvlTree::new();
+        for key in keys {
+            tree.put(*key, key.to_string());
+            assert!(tree.is_valid());
+        }
+        tree
+    }
+
+    fn root(tree: &AvlTree<i32, String>) -> i32 {
+        tree.root.as_ref().unwrap().key
+    }
+
+    fn keys(tree: &AvlTree<i32, String>) -> Vec<i32> {
+        tree.iter().map(|(key, _)| *key).collect()
+    }
+
+    #[test]
+    fn create() {
+        let tree = AvlTree::<i32, String>::new();
+        assert_eq!(0, tree.size());
+        assert_eq!(0, tree.height());
+        assert_eq!(None, tree.get(&1));
+        assert!(tree.is_valid());
+    }
+
+    #[test]
+    fn insert_rotations() {
+        // Left-left, right-right, left-right and right-left cases.
+        for order in [[3, 2, 1], [1, 2, 3], [3, 1, 2], [1, 3, 2]].iter() {
+            let tree = create_tree(order);
+            assert_eq!(2, root(&tree));
+            assert_eq!(2, tree.height());
+            assert_eq!(vec![1, 2, 3], keys(&tree));
+        }
+    }
+
+    #[test]
+    fn remove_rotations() {
+        let cases: [(&[i32], i32, i32, usize); 5] = [
+            (&[4, 2, 5, 1], 5, 2, 2),
+            (&[4, 2, 5, 3], 5, 3, 2),
+            (&[2, 1, 4, 5], 1, 4, 2),
+            (&[2, 1, 4, 3], 1, 3, 2),
+            // The left child stays balanced, only a single rotation is possible.
+            (&[4, 2, 5, 1, 3], 5, 2, 3),
+        ];
+        for (order, removed, expected, height) in cases.iter() {
+            let mut tree = create_tree(order);
+            assert_eq!(Some(removed.to_string()), tree.remove(removed));
+            assert!(tree.is_valid());
+            assert_eq!(*expected, root(&tree));
+            assert_eq!(*height, tree.height());
+        }
+    }
+
+    #[test]
+    fn put_get_remove() {
+        let mut tree = create_tree(&[44, 17, 88, 32, 65, 97, 28, 54, 82, 29, 76, 80, 78]);
+        assert_eq!(13, tree.size());
+        assert_eq!(Some(&"54".to_string()), tree.get(&54));
+
+        tree.put(54, "fifty four".to_string());
+        *tree.get_mut(&17).unwrap() = "seventeen".to_string();
+        assert_eq!(13, tree.size());
+        assert_eq!(Some(&"fifty four".to_string()), tree.get(&54));
+
+        assert_eq!(Some("44".to_string()), tree.remove(&44));
+        assert_eq!(Some("65".to_string()), tree.remove(&65));
+        assert_eq!(None, tree.remove(&65));
+        assert!(tree.is_valid());
+        assert_eq!(vec![17, 28, 29, 32, 54, 76, 78, 80, 82, 88, 97], keys(&tree));
+        assert_eq!(format!("{:?}", tree.iter().collect::<BTreeMap<_, _>>()), format!("{:?}", tree));
+    }
+
+    #[test]
+    fn sorted_insertion_stays_balanced() {
+        let mut tree = AvlTree::new();
+        for key in 0..10_000 {
+            tree.put(key, ());
+        }
+        assert!(tree.is_valid());
+        assert!(tree.height() <= 14 * 144 / 100);
+
+        for key in 0..9_000 {
+            tree.remove(&key);
+        }
+        assert!(tree.is_valid());
+        assert!(tree.height() <= 10 * 144 / 100);
+        assert!(tree.iter().map(|(key, _)| *key).eq(9_000..10_000));
+    }
+
+    #[test]
+    fn against_btree_map() {
+        let mut tree = AvlTree::new();
+        let expected = compare_with_btree_map(&mut tree, 44, |tree, _, _, _| assert!(tree.is_valid()));
+        assert!(tree.iter().eq(expected.iter()));
+    }
+}
@@ -0,0 +1,682 @@
+use std::fmt::{self, Debug, Formatter};
+use std::mem;
+use std::ops::{Bound, RangeBounds};
+
+use crate::structures::types::{Map, NavigableMap, ReadonlyMap};
+
+struct Node<K, V> {
+    keys: Vec<K>,
+    values: Vec<V>,
+    // Empty for leaves, one more than the keys otherwise.
+    children: Vec<Node<K, V>>,
+}
+
+// Like the standard library map, `B` is the minimum degree: every node but the root holds
+// between `B - 1` and `2 * B - 1` keys, kept sorted in one vector, so a lookup touches
+// O(log_B n) nodes and scans short contiguous arrays. Insertion splits full nodes and
+// removal refills thin nodes on the way down, so neither has to walk back up.
+pub struct BTree<K, V, const B: usize = 6> {
+    root: Node<K, V>,
+    size: usize,
+}
+
+// Both ends keep the path to the next entry they yield, each level holding a node and a
+// position in it. The front yields `keys[index]`, the back yields `keys[index - 1]`.
+pub struct Range<'a, K, V> {
+    front: Vec<(&'a Node<K, V>, usize)>,
+    back: Vec<(&'a Node<K, V>, usize)>,
+}
+
+impl<K, V> Node<K, V> {
+    fn new(capacity: usize) -> Node<K, V> {
+        Node {
+            keys: Vec::with_capacity(capacity),
+            values: Vec::with_capacity(capacity),
+            children: Vec::new(),
+        }
+    }
+
+    fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    // Splits the full child at `index` around its median, which moves up into this node.
+    fn split_child(&mut self, index: usize, minimum: usize) {
+        let child = &mut self.children[index];
+        let mut right = Node::new(child.keys.capacity());
+        right.keys = child.keys.split_off(minimum);
+        right.values = child.values.split_off(minimum);
+        if !child.is_leaf() {
+            right.children = child.children.split_off(minimum);
+        }
+        let key = child.keys.pop().unwrap();
+        let value = child.values.pop().unwrap();
+
+        self.keys.insert(index, key);
+        self.values.insert(index, value);
+        self.children.insert(index + 1, right);
+    }
+
+    // Moves the separator at `index` and the right child into the left child.
+    fn merge_children(&mut self, index: usize) {
+        let right = self.children.remove(index + 1);
+        let key = self.keys.remove(index);
+        let value = self.values.remove(index);
+
+        let left = &mut self.children[index];
+        left.keys.push(key);
+        left.values.push(value);
+        left.keys.extend(right.keys);
+        left.values.extend(right.values);
+        left.children.extend(right.children);
+    }
+
+    // Rotates the last entry of the left sibling through the separator into the child.
+    fn borrow_from_left(&mut self, index: usize) {
+        let (left, right) = self.children.split_at_mut(index);
+        let (left, child) = (&mut left[index - 1], &mut right[0]);
+
+        let key = mem::replace(&mut self.keys[index - 1], left.keys.pop().unwrap());
+        let value = mem::replace(&mut self.values[index - 1], left.values.pop().unwrap());
+        child.keys.insert(0, key);
+        child.values.insert(0, value);
+        if let Some(grandchild) = left.children.pop() {
+            child.children.insert(0, grandchild);
+        }
+    }
+
+    // Rotates the first entry of the right sibling through the separator into the child.
+    fn borrow_from_right(&mut self, index: usize) {
+        let (left, right) = self.children.split_at_mut(index + 1);
+        let (child, right) = (&mut left[index], &mut right[0]);
+
+        let key = mem::replace(&mut self.keys[index], right.keys.remove(0));
+        let value = mem::replace(&mut self.values[index], right.values.remove(0));
+        child.keys.push(key);
+        child.values.push(value);
+        if !right.is_leaf() {
+            child.children.push(right.children.remove(0));
+        }
+    }
+
+    // Makes sure the child at `index` holds at least `minimum` keys before descending into
+    // it and returns where the child ended up.
+    fn fill_child(&mut self, index: usize, minimum: usize) -> usize {
+        if self.children[index].keys.len() >= minimum {
+            index
+        } else if index > 0 && self.children[index - 1].keys.len() >= minimum {
+            self.borrow_from_left(index);
+            index
+        } else if index + 1 < self.children.len() && self.children[index + 1].keys.len() >= minimum {
+            self.borrow_from_right(index);
+            index
+        } else if index + 1 < self.children.len() {
+            self.merge_children(index);
+            index
+        } else {
+            self.merge_children(index - 1);
+            index - 1
+        }
+    }
+
+    fn remove_first(&mut self, minimum: usize) -> (K, V) {
+        if self.is_leaf() {
+            return (self.keys.remove(0), self.values.remove(0));
+        }
+        let index = self.fill_child(0, minimum);
+        self.children[index].remove_first(minimum)
+    }
+
+    fn remove_last(&mut self, minimum: usize) -> (K, V) {
+        if self.is_leaf() {
+            return (self.keys.pop().unwrap(), self.values.pop().unwrap());
+        }
+        let index = self.fill_child(self.children.len() - 1, minimum);
+        self.children[index].remove_last(minimum)
+    }
+
+    fn remove(&mut self, key: &K, minimum: usize) -> Option<V> where K: Ord {
+        match self.keys.binary_search(key) {
+            Ok(index) if self.is_leaf() => {
+                self.keys.remove(index);
+                Some(self.values.remove(index))
+            }
+            // The entry is replaced by its predecessor or successor when a neighbouring child
+            // can spare a key, otherwise both children are merged around it.
+            Ok(index) => {
+                if self.children[index].keys.len() >= minimum {
+                    let (key, value) = self.children[index].remove_last(minimum);
+                    self.keys[index] = key;
+                    Some(mem::replace(&mut self.values[index], value))
+                } else if self.children[index + 1].keys.len() >= minimum {
+                    let (key, value) = self.children[index + 1].remove_first(minimum);
+                    self.keys[index] = key;
+                    Some(mem::replace(&mut self.values[index], value))
+                } else {
+                    self.merge_children(index);
+                    self.children[index].remove(key, minimum)
+                }
+            }
+            Err(_) if self.is_leaf() => None,
+            Err(index) => {
+                let index = self.fill_child(index, minimum);
+                self.children[index].remove(key, minimum)
+            }
+        }
+    }
+
+    // Returns the depth of the leaves of a valid subtree whose keys lie strictly between
+    // `lower` and `upper`, and counts its keys.
+    fn check(&self, lower: Option<&K>, upper: Option<&K>, minimum: usize, capacity: usize, size: &mut usize) -> Option<usize> where K: Ord {
+        let sorted = self.keys.windows(2).all(|pair| pair[0] < pair[1])
+            && self.keys.first().is_none_or(|first| lower.is_none_or(|lower| first > lower))
+            && self.keys.last().is_none_or(|last| upper.is_none_or(|upper| last < upper));
+        if !sorted
+            || self.keys.len() < minimum
+            || self.keys.len() > capacity
+            || self.keys.len() != self.values.len()
+            || (!self.is_leaf() && self.children.len() != self.keys.len() + 1) {
+            return None;
+        }
+
+        *size += self.keys.len();
+        let mut depth = None;
+        for (index, child) in self.children.iter().enumerate() {
+            let lower = if index == 0 { lower } else { Some(&self.keys[index - 1]) };
+            let upper = self.keys.get(index).or(upper);
+            let child_depth = child.check(lower, upper, capacity / 2, capacity, size)?;
+            if depth.is_some_and(|depth| depth != child_depth) {
+                return None;
+            }
+            depth = Some(child_depth);
+        }
+        Some(depth.map_or(0, |depth| depth + 1))
+    }
+}
+
+impl<K, V, const B: usize> BTree<K, V, B> {
+    const CAPACITY: usize = 2 * B - 1;
+
+    pub fn new() -> Self {
+        assert!(B >= 2, "minimum degree must be at least 2");
+        BTree {
+            root: Node::new(Self::CAPACITY),
+            size: 0,
+        }
+    }
+
+    pub fn iter(&self) -> Range<'_, K, V> where K: Ord {
+        self.range(..)
+    }
+
+    pub fn height(&self) -> usize {
+        let mut height = 1;
+        let mut node = &self.root;
+        while let Some(child) = node.children.first() {
+            height += 1;
+            node = child;
+        }
+        height
+    }
+
+    // Checks the order of the keys, the number of keys and children of every node and that
+    // all leaves are at the same depth.
+    pub fn is_valid(&self) -> bool where K: Ord {
+        let mut size = 0;
+        let minimum = if self.root.is_leaf() { 0 } else { 1 };
+        self.root.check(None, None, minimum, Self::CAPACITY, &mut size).is_some() && size == self.size
+    }
+
+    // The root is never left empty with a child, the tree shrinks by one level instead.
+    fn shrink(&mut self) {
+        if self.root.keys.is_empty() && !self.root.is_leaf() {
+            self.root = self.root.children.pop().unwrap();
+        }
+    }
+}
+
+impl<K, V, const B: usize> ReadonlyMap<K, V> for BTree<K, V, B> where K: Ord {
+    fn get(&self, key: &K) -> Option<&V> {
+        let mut node = &self.root;
+        loop {
+            match node.keys.binary_search(key) {
+                Ok(index) => return Some(&node.values[index]),
+                Err(_) if node.is_leaf() => return None,
+                Err(index) => node = &node.children[index],
+            }
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl<K, V, const B: usize> Map<K, V> for BTree<K, V, B> where K: Ord {
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let mut node = &mut self.root;
+        loop {
+            match node.keys.binary_search(key) {
+                Ok(index) => return Some(&mut node.values[index]),
+                Err(_) if node.is_leaf() => return None,
+                Err(index) => node = &mut node.children[index],
+            }
+        }
+    }
+
+    // Full nodes are split on the way down, so there is always room for the new key
+    // and for a median moving up into the parent.
+    fn put(&mut self, key: K, value: V) {
+        if self.root.keys.len() == Self::CAPACITY {
+            let root = mem::replace(&mut self.root, Node::new(Self::CAPACITY));
+            self.root.children.push(root);
+            self.root.split_child(0, B);
+        }
+
+        let mut node = &mut self.root;
+        loop {
+            let mut index = match node.keys.binary_search(&key) {
+                Ok(index) => {
+                    node.values[index] = value;
+                    return;
+                }
+                Err(index) => index,
+            };
+
+            if node.is_leaf() {
+                node.keys.insert(index, key);
+                node.values.insert(index, value);
+                self.size += 1;
+                return;
+            }
+
+            if node.children[index].keys.len() == Self::CAPACITY {
+                node.split_child(index, B);
+                if key > node.keys[index] {
+                    index += 1;
+                } else if key == node.keys[index] {
+                    node.values[index] = value;
+                    return;
+                }
+            }
+            node = &mut node.children[index];
+        }
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        let removed = self.root.remove(key, B);
+        self.shrink();
+        if removed.is_some() {
+            self.size -= 1;
+        }
+        removed
+    }
+}
+
+impl<K, V, const B: usize> NavigableMap<K, V> for BTree<K, V, B> where K: Ord {
+    type Range<'a> = Range<'a, K, V> where K: 'a, V: 'a;
+
+    fn floor(&self, key: &K) -> Option<(&K, &V)> {
+        self.range((Bound::Unbounded, Bound::Included(key))).next_back()
+    }
+
+    fn ceiling(&self, key: &K) -> Option<(&K, &V)> {
+        self.range((Bound::Included(key), Bound::Unbounded)).next()
+    }
+
+    fn lower(&self, key: &K) -> Option<(&K, &V)> {
+        self.range((Bound::Unbounded, Bound::Excluded(key))).next_back()
+    }
+
+    fn higher(&self, key: &K) -> Option<(&K, &V)> {
+        self.range((Bound::Excluded(key), Bound::Unbounded)).next()
+    }
+
+    fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.iter().next()
+    }
+
+    fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.iter().next_back()
+    }
+
+    fn pop_first(&mut self) -> Option<(K, V)> {
+        if self.size == 0 {
+            return None;
+        }
+        let entry = self.root.remove_first(B);
+        self.shrink();
+        self.size -= 1;
+        Some(entry)
+    }
+
+    fn pop_last(&mut self) -> Option<(K, V)> {
+        if self.size == 0 {
+            return None;
+        }
+        let entry = self.root.remove_last(B);
+        self.shrink();
+        self.size -= 1;
+        Some(entry)
+    }
+
+    fn range(&self, range: impl RangeBounds<K>) -> Self::Range<'_> {
+        let mut front = Vec::new();
+        let mut node = &self.root;
+        loop {
+            let index = match range.start_bound() {
+                Bound::Included(key) => match node.keys.binary_search(key) {
+                    Ok(index) => {
+                        front.push((node, index));
+                        break;
+                    }
+                    Err(index) => index,
+                },
+                Bound::Excluded(key) => match node.keys.binary_search(key) {
+                    Ok(index) => index + 1,
+                    Err(index) => index,
+                },
+                Bound::Unbounded => 0,
+            };
+            front.push((node, index));
+            match node.children.get(index) {
+                Some(child) => node = child,
+                None => break,
+            }
+        }
+
+        let mut back = Vec::new();
+        let mut node = &self.root;
+        loop {
+            let index = match range.end_bound() {
+                Bound::Included(key) => match node.keys.binary_search(key) {
+                    Ok(index) => {
+                        back.push((node, index + 1));
+                        break;
+                    }
+                    Err(index) => index,
+                },
+                Bound::Excluded(key) => match node.keys.binary_search(key) {
+                    Ok(index) => index,
+                    Err(index) => index,
+                },
+                Bound::Unbounded => node.keys.len(),
+            };
+            back.push((node, index));
+            match node.children.get(index) {
+                Some(child) => node = child,
+                None => break,
+            }
+        }
+
+        let mut range = Range { front, back };
+        range.skip_exhausted();
+        match (range.front.last(), range.back.last()) {
+            (Some((first, i)), Some((last, j))) if first.keys[*i] <= last.keys[*j - 1] => range,
+            _ => Range { front: Vec::new(), back: Vec::new() },
+        }
+    }
+}
+
+impl<K, V, const B: usize> Debug for BTree<K, V, B> where K: Ord + Debug, V: Debug {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, K, V> Range<'a, K, V> {
+    // Drops the levels whose node has nothing left to yield in that direction.
+    fn skip_exhausted(&mut self) {
+        while self.front.last().is_some_and(|(node, index)| *index == node.keys.len()) {
+            self.front.pop();
+        }
+        while self.back.last().is_some_and(|(_, index)| *index == 0) {
+            self.back.pop();
+        }
+    }
+
+    // Both ends are about to yield the same entry, which is the last one.
+    fn meets(&self) -> bool {
+        match (self.front.last(), self.back.last()) {
+            (Some((first, i)), Some((last, j))) => std::ptr::eq(*first, *last) && *i + 1 == *j,
+            _ => false,
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (node, index) = *self.front.last()?;
+        if self.meets() {
+            self.front.clear();
+            self.back.clear();
+        } else {
+            self.front.last_mut().unwrap().1 += 1;
+            let mut child = node.children.get(index + 1);
+            while let Some(node) = child {
+                self.front.push((node, 0));
+                child = node.children.first();
+            }
+            self.skip_exhausted();
+        }
+        Some((&node.keys[index], &node.values[index]))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Range<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (node, index) = *self.back.last()?;
+        if self.meets() {
+            self.front.clear();
+            self.back.clear();
+        } else {
+            self.back.last_mut().unwrap().1 -= 1;
+            let mut child = node.children.get(index - 1);
+            while let Some(node) = child {
+                self.back.push((node, node.keys.len()));
+                child = node.children.last();
+            }
+            self.skip_exhausted();
+        }
+        Some((&node.keys[index - 1], &node.values[index - 1]))
+    }
+}
+
+impl<'a, K, V, const B: usize> IntoIterator for &'a BTree<K, V, B> where K: Ord {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Range<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::compare_with_btree_map;
+
+    use super::*;
+
+    fn create_tree<const B: usize>(keys: impl Iterator<Item=i32>) -> BTree<i32, i32, B> {
+        let mut tree = BTree::new();
+        for key in keys {
+            tree.put(key, key * 10);
+            assert!(tree.is_valid());
+        }
+        tree
+    }
+
+    fn keys<const B: usize>(tree: &BTree<i32, i32, B>) -> Vec<i32> {
+        tree.iter().map(|(key, _)| *key).collect()
+    }
+
+    #[test]
+    fn create() {
+        let tree = BTree::<i32, i32>::new();
+        assert_eq!(0, tree.size());
+        assert_eq!(None, tree.get(&1));
+        assert_eq!(1, tree.height());
+        assert_eq!(None, tree.iter().next());
+        assert!(tree.is_valid());
+    }
+
+    #[test]
+    #[should_panic(expected = "minimum degree must be at least 2")]
+    fn create_too_small() {
+        BTree::<i32, i32, 1>::new();
+    }
+
+    #[test]
+    fn put_get_remove() {
+        let mut tree = create_tree::<2>([44, 17, 88, 32, 65, 97, 28, 54, 82, 29, 76, 80, 78].iter().copied());
+        assert_eq!(13, tree.size());
+        assert_eq!(Some(&540), tree.get(&54));
+
+        tree.put(54, 1);
+        *tree.get_mut(&17).unwrap() = 2;
+        assert_eq!(13, tree.size());
+        assert_eq!(Some(&1), tree.get(&54));
+        assert_eq!(Some(&2), tree.get(&17));
+
+        assert_eq!(Some(440), tree.remove(&44));
+        assert_eq!(None, tree.remove(&44));
+        assert!(tree.is_valid());
+        assert_eq!(vec![17, 28, 29, 32, 54, 65, 76, 78, 80, 82, 88, 97], keys(&tree));
+    }
+
+    #[test]
+    fn split() {
+        let mut tree = create_tree::<2>(1..=3);
+        assert_eq!(1, tree.height());
+
+        // The full root splits around its median before the fourth key goes in.
+        tree.put(4, 40);
+        assert!(tree.is_valid());
+        assert_eq!(2, tree.height());
+        assert_eq!(vec![2], tree.root.keys);
+        assert_eq!(vec![1], tree.root.children[0].keys);
+        assert_eq!(vec![3, 4], tree.root.children[1].keys);
+
+        let tree = create_tree::<3>(0..1000);
+        assert!(tree.height() <= 6);
+    }
+
+    #[test]
+    fn borrow() {
+        let mut tree = create_tree::<2>(1..=4);
+        tree.put(0, 0);
+        assert_eq!(vec![0, 1], tree.root.children[0].keys);
+
+        // The right child is refilled from its left sibling through the separator.
+        assert_eq!(Some(40), tree.remove(&4));
+        assert_eq!(Some(30), tree.remove(&3));
+        assert!(tree.is_valid());
+        assert_eq!(vec![1], tree.root.keys);
+        assert_eq!(vec![0], tree.root.children[0].keys);
+        assert_eq!(vec![2], tree.root.children[1].keys);
+
+        // And the left one from its right sibling.
+        tree.put(3, 30);
+        assert_eq!(Some(0), tree.remove(&0));
+        assert!(tree.is_valid());
+        assert_eq!(vec![2], tree.root.keys);
+        assert_eq!(vec![1], tree.root.children[0].keys);
+        assert_eq!(vec![3], tree.root.children[1].keys);
+    }
+
+    #[test]
+    fn merge() {
+        let mut tree = create_tree::<2>(1..=4);
+        assert_eq!(Some(40), tree.remove(&4));
+        assert_eq!(2, tree.height());
+
+        // Both children are minimal, so they merge with the separator and the root shrinks.
+        assert_eq!(Some(20), tree.remove(&2));
+        assert!(tree.is_valid());
+        assert_eq!(1, tree.height());
+        assert_eq!(vec![1, 3], tree.root.keys);
+
+        let mut tree = create_tree::<2>(0..1000);
+        for key in 0..1000 {
+            assert_eq!(Some(key * 10), tree.remove(&key));
+            assert!(tree.is_valid());
+        }
+        assert_eq!(0, tree.size());
+        assert_eq!(1, tree.height());
+    }
+
+    #[test]
+    fn range() {
+        let tree = create_tree::<2>((0..100).map(|key| key * 2));
+        let range = |range: (Bound<i32>, Bound<i32>)| {
+            tree.range((range.0.as_ref(), range.1.as_ref())).map(|(key, _)| *key).collect::<Vec<_>>()
+        };
+
+        assert_eq!(vec![10, 12, 14], range((Bound::Included(10), Bound::Excluded(16))));
+        assert_eq!(vec![12, 14, 16], range((Bound::Excluded(10), Bound::Included(16))));
+        assert_eq!(vec![12, 14], range((Bound::Included(11), Bound::Included(15))));
+        assert_eq!(vec![0, 2], range((Bound::Unbounded, Bound::Excluded(4))));
+        assert_eq!(vec![196, 198], range((Bound::Excluded(194), Bound::Unbounded)));
+        assert_eq!(vec![10], range((Bound::Included(10), Bound::Included(10))));
+        assert!(range((Bound::Excluded(10), Bound::Excluded(12))).is_empty());
+        assert!(range((Bound::Included(11), Bound::Included(11))).is_empty());
+        assert!(range((Bound::Included(300), Bound::Unbounded)).is_empty());
+
+        let mut range = tree.range(10..=20);
+        assert_eq!(Some((&10, &100)), range.next());
+        assert_eq!(Some((&20, &200)), range.next_back());
+        assert_eq!(vec![12, 14, 16, 18], range.map(|(key, _)| *key).collect::<Vec<_>>());
+        assert!(tree.range(..).rev().map(|(key, _)| *key).eq((0..100).rev().map(|key| key * 2)));
+    }
+
+    #[test]
+    fn navigable() {
+        let mut tree = create_tree::<3>((0..10).map(|key| key * 10));
+        assert_eq!(Some((&30, &300)), tree.floor(&35));
+        assert_eq!(Some((&30, &300)), tree.floor(&30));
+        assert_eq!(Some((&40, &400)), tree.ceiling(&35));
+        assert_eq!(Some((&20, &200)), tree.lower(&30));
+        assert_eq!(Some((&40, &400)), tree.higher(&30));
+        assert_eq!(None, tree.lower(&0));
+        assert_eq!(None, tree.higher(&90));
+        assert_eq!(Some((&0, &0)), tree.first_key_value());
+        assert_eq!(Some((&90, &900)), tree.last_key_value());
+
+        assert_eq!(Some((0, 0)), tree.pop_first());
+        assert_eq!(Some((90, 900)), tree.pop_last());
+        assert!(tree.is_valid());
+        assert_eq!(8, tree.size());
+        while tree.pop_first().is_some() {
+            assert!(tree.is_valid());
+        }
+        assert_eq!(None, tree.pop_last());
+    }
+
+    fn against_btree_map<const B: usize>() {
+        let mut tree = BTree::<i32, i32, B>::new();
+        let expected = compare_with_btree_map(&mut tree, 48, |tree, expected, _, key| {
+            let end = key + 25;
+            assert!(tree.range(key..end).eq(expected.range(key..end)));
+            assert!(tree.range(key..=end).rev().eq(expected.range(key..=end).rev()));
+            assert!(tree.is_valid());
+        });
+        assert!(tree.iter().eq(expected.iter()));
+    }
+
+    #[test]
+    fn against_btree_map_order_2() {
+        against_btree_map::<2>();
+    }
+
+    #[test]
+    fn against_btree_map_order_3() {
+        against_btree_map::<3>();
+    }
+
+    #[test]
+    fn against_btree_map_order_6() {
+        against_btree_map::<6>();
+    }
+}
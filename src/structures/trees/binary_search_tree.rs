@@ -1,166 +1,249 @@
-use std::borrow::BorrowMut;
-use std::cell::UnsafeCell;
 use std::cmp::Ordering;
-use std::fmt::Debug;
-use std::mem::{MaybeUninit, replace};
-use std::rc::{Rc, Weak};
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::{Bound, RangeBounds};
+use std::ptr::NonNull;
 
-use crate::structures::types::{Map, ReadonlyMap};
+use crate::structures::types::{Map, NavigableMap, ReadonlyMap};
 
-use self::RemovingStatus::{FoundLeft, FoundRight, FoundRoot, Searching, SearchingLeft, SearchingRight};
+type Link<K, V> = Option<NonNull<Node<K, V>>>;
 
-#[derive(Debug)]
 struct Node<K, V> {
     key: K,
-    value: MaybeUninit<V>,
-    parent: Option<Weak<UnsafeCell<Self>>>,
-    left: Option<Rc<UnsafeCell<Self>>>,
-    right: Option<Rc<UnsafeCell<Self>>>,
+    value: V,
+    parent: Link<K, V>,
+    left: Link<K, V>,
+    right: Link<K, V>,
 }
 
-#[derive(Debug)]
+// The tree owns every node through raw pointers: nodes are allocated with `Box` and freed
+// exactly once, either by `remove` or by `Drop`. References handed out borrow the tree,
+// so no `&mut` to a node can coexist with another reference to it.
 pub struct BinarySearchTree<K, V> {
-    root: Option<Rc<UnsafeCell<Node<K, V>>>>,
+    root: Link<K, V>,
     size: usize,
+    marker: PhantomData<Box<Node<K, V>>>,
 }
 
+// In-order iterators walk the parent links, so they need O(1) extra space.
+pub struct Iter<'a, K, V> {
+    front: Link<K, V>,
+    back: Link<K, V>,
+    size: usize,
+    marker: PhantomData<&'a Node<K, V>>,
+}
+
+pub struct IterMut<'a, K, V> {
+    front: Link<K, V>,
+    back: Link<K, V>,
+    size: usize,
+    marker: PhantomData<&'a mut Node<K, V>>,
+}
+
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+pub struct Values<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+pub struct IntoIter<K, V> {
+    front: Link<K, V>,
+    back: Link<K, V>,
+    tree: BinarySearchTree<K, V>,
+}
+
+// Both ends stay inside the range, the iterator is exhausted once they meet.
+pub struct Range<'a, K, V> {
+    front: Link<K, V>,
+    back: Link<K, V>,
+    marker: PhantomData<&'a Node<K, V>>,
+}
+
+pub struct PreOrder<'a, K, V> {
+    next: Option<(NonNull<Node<K, V>>, usize)>,
+    size: usize,
+    marker: PhantomData<&'a Node<K, V>>,
+}
+
+pub struct PostOrder<'a, K, V> {
+    next: Link<K, V>,
+    size: usize,
+    marker: PhantomData<&'a Node<K, V>>,
+}
+
+// Without a queue every level is found by a depth limited pre-order walk from the root,
+// which trades O(n * h) time for O(1) extra space.
+pub struct LevelOrder<'a, K, V> {
+    root: Link<K, V>,
+    current: Option<(NonNull<Node<K, V>>, usize)>,
+    level: usize,
+    size: usize,
+    marker: PhantomData<&'a Node<K, V>>,
+}
+
+unsafe impl<K: Send, V: Send> Send for BinarySearchTree<K, V> {}
+
+unsafe impl<K: Sync, V: Sync> Sync for BinarySearchTree<K, V> {}
+
 impl<K, V> Node<K, V> {
-    fn new(key: K, value: V, parent: Option<Weak<UnsafeCell<Self>>>) -> Self {
-        Node {
+    fn new_link(key: K, value: V, parent: Link<K, V>) -> NonNull<Node<K, V>> {
+        NonNull::from(Box::leak(Box::new(Node {
             key,
-            value: MaybeUninit::new(value),
+            value,
             parent,
             left: None,
             right: None,
-        }
-    }
-
-    fn new_link(key: K, value: V, parent: Option<Weak<UnsafeCell<Self>>>) -> Option<Rc<UnsafeCell<Self>>> {
-        Some(Rc::new(UnsafeCell::new(Self::new(key, value, parent))))
-    }
-
-    fn select_left_mut(&mut self) -> &mut Option<Rc<UnsafeCell<Self>>> {
-        &mut self.left
-    }
-
-    fn select_right_mut(&mut self) -> &mut Option<Rc<UnsafeCell<Self>>> {
-        &mut self.right
+        })))
     }
 }
 
 impl<K, V> BinarySearchTree<K, V> {
-    fn new() -> Self {
+    pub fn new() -> Self {
         BinarySearchTree {
             root: None,
             size: 0,
+            marker: PhantomData,
         }
     }
-}
 
-fn get_ref<K, V>(rc: &Rc<UnsafeCell<Node<K, V>>>) -> &Node<K, V> {
-    unsafe { &*(**rc).get() }
-}
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            front: self.root.map(leftmost),
+            back: self.root.map(rightmost),
+            size: self.size,
+            marker: PhantomData,
+        }
+    }
 
-fn get_mut<K, V>(rc: &mut Rc<UnsafeCell<Node<K, V>>>) -> &mut Node<K, V> {
-    unsafe { &mut *(**rc).get() }
-}
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            front: self.root.map(leftmost),
+            back: self.root.map(rightmost),
+            size: self.size,
+            marker: PhantomData,
+        }
+    }
 
-fn rebalance<K, V>(
-    node: &mut Node<K, V>,
-    kind: NodeKind,
-) -> Option<Rc<UnsafeCell<Node<K, V>>>> {
-    *&mut node.parent = None;
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
 
-    let node_selector = match kind {
-        NodeKind::Root |
-        NodeKind::Left => Node::select_left_mut,
-        NodeKind::Right => Node::select_right_mut,
-    };
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
 
-    let mut right = node.right.take();
-    let mut left = node.left.take();
+    pub fn pre_order(&self) -> PreOrder<'_, K, V> {
+        PreOrder {
+            next: self.root.map(|root| (root, 0)),
+            size: self.size,
+            marker: PhantomData,
+        }
+    }
 
-    let mut curr = match kind {
-        NodeKind::Root => {
-            match (right.is_some(), left.is_some()) {
-                (true, false) => return right,
-                (false, true) => return left,
-                (false, false) => return None,
-                (true, true) => &mut right,
-            }
+    pub fn post_order(&self) -> PostOrder<'_, K, V> {
+        PostOrder {
+            next: self.root.map(first_post_order),
+            size: self.size,
+            marker: PhantomData,
         }
-        NodeKind::Left => &mut right,
-        NodeKind::Right => &mut left,
-    };
-
-    loop {
-        let found = match curr {
-            Some(rc) => {
-                curr = node_selector(get_mut(rc));
-                curr.as_mut()
-                    .map(|rc| node_selector(get_mut(rc)).is_none())
-                    .unwrap_or(true)
-            }
-            None => return None,
-        };
+    }
 
-        if found { break; }
+    pub fn level_order(&self) -> LevelOrder<'_, K, V> {
+        LevelOrder {
+            root: self.root,
+            current: None,
+            level: 0,
+            size: self.size,
+            marker: PhantomData,
+        }
+    }
+
+    fn find(&self, key: &K) -> Link<K, V> where K: Ord {
+        let mut current = self.root;
+        while let Some(node) = current {
+            let node_ref = unsafe { node.as_ref() };
+            current = match key.cmp(&node_ref.key) {
+                Ordering::Equal => return Some(node),
+                Ordering::Less => node_ref.left,
+                Ordering::Greater => node_ref.right,
+            };
+        }
+        None
     }
 
-    return curr.take()
-        .map(|mut next_parent| {
-            let link = node_selector(get_mut(&mut next_parent));
-            *link = match kind {
-                NodeKind::Root |
-                NodeKind::Left => left,
-                NodeKind::Right => right,
+    // Least node whose key lies above the lower `bound`.
+    fn lower_bound(&self, bound: Bound<&K>) -> Link<K, V> where K: Ord {
+        let mut candidate = None;
+        let mut current = self.root;
+        while let Some(node) = current {
+            let key = unsafe { &(*node.as_ptr()).key };
+            let inside = match bound {
+                Bound::Included(bound) => key >= bound,
+                Bound::Excluded(bound) => key > bound,
+                Bound::Unbounded => true,
             };
-            next_parent
-        });
-}
-
-fn remove_node<K, V>(
-    link: &mut Option<Rc<UnsafeCell<Node<K, V>>>>,
-    kind: NodeKind,
-) -> Option<V> {
-    let owned_link = link.take();
-    match owned_link {
-        Some(mut rc) => {
-            let mut node = get_mut(&mut rc);
-            let next = rebalance(node, kind);
-            *link = next;
-            if let Some(mut rc) = link.as_mut() {
-                let child = get_mut(rc);
-                *&mut child.parent = node.parent.take();
+            if inside {
+                candidate = Some(node);
+                current = left(node);
+            } else {
+                current = right(node);
             }
+        }
+        candidate
+    }
 
-            return Some(
-                unsafe {
-                    replace(
-                        &mut node.value,
-                        MaybeUninit::uninit(),
-                    )
-                        .assume_init()
-                }
-            );
+    // Greatest node whose key lies below the upper `bound`.
+    fn upper_bound(&self, bound: Bound<&K>) -> Link<K, V> where K: Ord {
+        let mut candidate = None;
+        let mut current = self.root;
+        while let Some(node) = current {
+            let key = unsafe { &(*node.as_ptr()).key };
+            let inside = match bound {
+                Bound::Included(bound) => key <= bound,
+                Bound::Excluded(bound) => key < bound,
+                Bound::Unbounded => true,
+            };
+            if inside {
+                candidate = Some(node);
+                current = right(node);
+            } else {
+                current = left(node);
+            }
         }
-        None => unreachable!()
-    };
-}
+        candidate
+    }
 
-fn resolve_status<K, V>(
-    link: &Option<Rc<UnsafeCell<Node<K, V>>>>,
-    key: &K,
-    found: RemovingStatus,
-    searching: RemovingStatus,
-) -> Option<RemovingStatus>
-    where K: Eq
-{
-    link.as_ref()
-        .map(|rc| if get_ref(rc).key.eq(&key) { found } else { searching })
-}
+    // Unlinks a node with at most one child and frees it.
+    unsafe fn unlink(&mut self, node: NonNull<Node<K, V>>) -> (K, V) {
+        let child = (*node.as_ptr()).left.or((*node.as_ptr()).right);
+        self.replace_child(node, child);
+        self.decrement_size();
+        let node = Box::from_raw(node.as_ptr());
+        (node.key, node.value)
+    }
+
+    // Puts `child` in place of `node` under the parent of `node`.
+    unsafe fn replace_child(&mut self, node: NonNull<Node<K, V>>, child: Link<K, V>) {
+        let parent = (*node.as_ptr()).parent;
+        match parent {
+            Some(parent) => {
+                let parent = &mut *parent.as_ptr();
+                if parent.left == Some(node) {
+                    parent.left = child;
+                } else {
+                    parent.right = child;
+                }
+            }
+            None => self.root = child,
+        }
+        if let Some(child) = child {
+            (*child.as_ptr()).parent = parent;
+        }
+    }
 
-impl<K, V> BinarySearchTree<K, V> {
     fn increment_size(&mut self) {
         self.size += 1;
     }
@@ -170,20 +253,113 @@ impl<K, V> BinarySearchTree<K, V> {
     }
 }
 
-impl<K, V> ReadonlyMap<K, V> for BinarySearchTree<K, V> where K: Ord, V: Debug {
-    fn get(&self, key: &K) -> Option<&V> {
-        let mut curr = &self.root;
-        while let Some(node_rc) = curr {
-            let node = get_ref(node_rc);
-
-            match node.key.cmp(key) {
-                Ordering::Equal => return Some(unsafe { &*node.value.as_ptr() }),
-                Ordering::Less => curr = &node.right,
-                Ordering::Greater => curr = &node.left,
+// Helpers read the links through raw pointers without creating a reference to the whole
+// node, so they stay valid while `IterMut` has handed out references to values.
+fn left<K, V>(node: NonNull<Node<K, V>>) -> Link<K, V> {
+    unsafe { (*node.as_ptr()).left }
+}
+
+fn right<K, V>(node: NonNull<Node<K, V>>) -> Link<K, V> {
+    unsafe { (*node.as_ptr()).right }
+}
+
+fn parent<K, V>(node: NonNull<Node<K, V>>) -> Link<K, V> {
+    unsafe { (*node.as_ptr()).parent }
+}
+
+fn leftmost<K, V>(mut node: NonNull<Node<K, V>>) -> NonNull<Node<K, V>> {
+    while let Some(left) = left(node) {
+        node = left;
+    }
+    node
+}
+
+fn rightmost<K, V>(mut node: NonNull<Node<K, V>>) -> NonNull<Node<K, V>> {
+    while let Some(right) = right(node) {
+        node = right;
+    }
+    node
+}
+
+fn successor<K, V>(node: NonNull<Node<K, V>>) -> Link<K, V> {
+    if let Some(right) = right(node) {
+        return Some(leftmost(right));
+    }
+
+    let mut child = node;
+    let mut current = parent(node);
+    while let Some(node) = current {
+        if left(node) == Some(child) {
+            break;
+        }
+        child = node;
+        current = parent(node);
+    }
+    current
+}
+
+fn predecessor<K, V>(node: NonNull<Node<K, V>>) -> Link<K, V> {
+    if let Some(left) = left(node) {
+        return Some(rightmost(left));
+    }
+
+    let mut child = node;
+    let mut current = parent(node);
+    while let Some(node) = current {
+        if right(node) == Some(child) {
+            break;
+        }
+        child = node;
+        current = parent(node);
+    }
+    current
+}
+
+// Next node in pre-order that is at most `limit` levels deep, along with its depth.
+fn pre_order_step<K, V>(node: NonNull<Node<K, V>>, depth: usize, limit: usize) -> Option<(NonNull<Node<K, V>>, usize)> {
+    if depth < limit {
+        if let Some(child) = left(node).or_else(|| right(node)) {
+            return Some((child, depth + 1));
+        }
+    }
+
+    let mut child = node;
+    let mut depth = depth;
+    while let Some(node) = parent(child) {
+        depth -= 1;
+        if left(node) == Some(child) {
+            if let Some(right) = right(node) {
+                return Some((right, depth + 1));
             }
         }
+        child = node;
+    }
+    None
+}
 
-        None
+fn first_post_order<K, V>(mut node: NonNull<Node<K, V>>) -> NonNull<Node<K, V>> {
+    while let Some(child) = left(node).or_else(|| right(node)) {
+        node = child;
+    }
+    node
+}
+
+fn post_order_step<K, V>(node: NonNull<Node<K, V>>) -> Link<K, V> {
+    let parent = parent(node)?;
+    match right(parent) {
+        Some(right) if left(parent) == Some(node) => Some(first_post_order(right)),
+        _ => Some(parent),
+    }
+}
+
+fn entry<'a, K, V>(node: NonNull<Node<K, V>>) -> (&'a K, &'a V) {
+    let node = unsafe { &*node.as_ptr() };
+    (&node.key, &node.value)
+}
+
+impl<K, V> ReadonlyMap<K, V> for BinarySearchTree<K, V> where K: Ord, V: Debug {
+    fn get(&self, key: &K) -> Option<&V> {
+        self.find(key).map(|node| unsafe { &(*node.as_ptr()).value })
     }
 
     fn size(&self) -> usize {
@@ -193,125 +369,421 @@ impl<K, V> ReadonlyMap<K, V> for BinarySearchTree<K, V> where K: Ord, V: Debug {
 
 impl<K, V> Map<K, V> for BinarySearchTree<K, V> where K: Ord, V: Debug {
     fn get_mut(&mut self, key: &K) -> Option<&mut V> {
-        let mut curr = &mut self.root;
-        while let Some(node_rc) = curr {
-            let node = get_mut(node_rc);
-
-            match node.key.cmp(key) {
-                Ordering::Equal => return Some(unsafe { &mut *node.value.as_mut_ptr() }),
-                Ordering::Less => curr = &mut node.right,
-                Ordering::Greater => curr = &mut node.left,
-            }
-        }
-
-        None
+        self.find(key).map(|node| unsafe { &mut (*node.as_ptr()).value })
     }
 
     fn put(&mut self, key: K, value: V) {
-        let mut curr = &mut self.root;
-
-        loop {
-            match curr {
-                Some(node_rc) => {
-                    let weak = Rc::downgrade(node_rc);
-                    let node = get_mut(node_rc);
-
-                    match (node.key.cmp(&key), node.left.is_some(), node.right.is_some()) {
-                        (Ordering::Equal, _, _) => {
-                            *node.value.borrow_mut() = MaybeUninit::new(value);
-
-                            return;
-                        }
-                        (Ordering::Greater, true, _) => curr = &mut node.left,
-                        (Ordering::Greater, false, _) => {
-                            *&mut node.left = Node::new_link(key, value, Some(weak));
-                            self.increment_size();
-                            return;
-                        }
-                        (Ordering::Less, _, true) => curr = &mut node.right,
-                        (Ordering::Less, _, false) => {
-                            *&mut node.right = Node::new_link(key, value, Some(weak));
-                            self.increment_size();
-                            return;
-                        }
-                    }
-                }
-                None => {
-                    *curr = Some(Rc::new(UnsafeCell::new(Node::new(key, value, None))));
-                    self.increment_size();
+        let mut parent = None;
+        let mut current = self.root;
+        let mut goes_left = false;
+        while let Some(link) = current {
+            let node = unsafe { &mut *link.as_ptr() };
+            match key.cmp(&node.key) {
+                Ordering::Equal => {
+                    node.value = value;
                     return;
                 }
+                Ordering::Less => {
+                    goes_left = true;
+                    current = node.left;
+                }
+                Ordering::Greater => {
+                    goes_left = false;
+                    current = node.right;
+                }
             }
+            parent = Some(link);
         }
+
+        let link = Some(Node::new_link(key, value, parent));
+        match parent {
+            Some(parent) if goes_left => unsafe { (*parent.as_ptr()).left = link },
+            Some(parent) => unsafe { (*parent.as_ptr()).right = link },
+            None => self.root = link,
+        }
+        self.increment_size();
     }
 
+    // A node with two children trades its entry with the in-order successor,
+    // which has no left child and is unlinked instead.
     fn remove(&mut self, key: &K) -> Option<V> {
-        let mut status = resolve_status(&self.root, key, FoundRoot, Searching)?;
+        let mut node = self.find(key)?;
+
+        unsafe {
+            if let (Some(_), Some(right)) = ((*node.as_ptr()).left, (*node.as_ptr()).right) {
+                let successor = leftmost(right);
+                let (target, source) = (&mut *node.as_ptr(), &mut *successor.as_ptr());
+                mem::swap(&mut target.key, &mut source.key);
+                mem::swap(&mut target.value, &mut source.value);
+                node = successor;
+            }
+
+            Some(self.unlink(node).1)
+        }
+    }
+}
+
+impl<K, V> NavigableMap<K, V> for BinarySearchTree<K, V> where K: Ord, V: Debug {
+    type Range<'a> = Range<'a, K, V> where K: 'a, V: 'a;
+
+    fn floor(&self, key: &K) -> Option<(&K, &V)> {
+        self.upper_bound(Bound::Included(key)).map(entry)
+    }
+
+    fn ceiling(&self, key: &K) -> Option<(&K, &V)> {
+        self.lower_bound(Bound::Included(key)).map(entry)
+    }
+
+    fn lower(&self, key: &K) -> Option<(&K, &V)> {
+        self.upper_bound(Bound::Excluded(key)).map(entry)
+    }
+
+    fn higher(&self, key: &K) -> Option<(&K, &V)> {
+        self.lower_bound(Bound::Excluded(key)).map(entry)
+    }
+
+    fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.root.map(leftmost).map(entry)
+    }
+
+    fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.root.map(rightmost).map(entry)
+    }
+
+    fn pop_first(&mut self) -> Option<(K, V)> {
+        let node = self.root.map(leftmost)?;
+        unsafe { Some(self.unlink(node)) }
+    }
+
+    fn pop_last(&mut self) -> Option<(K, V)> {
+        let node = self.root.map(rightmost)?;
+        unsafe { Some(self.unlink(node)) }
+    }
+
+    fn range(&self, range: impl RangeBounds<K>) -> Self::Range<'_> {
+        let front = self.lower_bound(range.start_bound());
+        let back = self.upper_bound(range.end_bound());
+        match (front, back) {
+            (Some(front), Some(back)) if entry(front).0 <= entry(back).0 => Range {
+                front: Some(front),
+                back: Some(back),
+                marker: PhantomData,
+            },
+            _ => Range {
+                front: None,
+                back: None,
+                marker: PhantomData,
+            },
+        }
+    }
+}
+
+impl<K, V> Debug for BinarySearchTree<K, V> where K: Debug, V: Debug {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
 
-        if let FoundRoot = status {
-            let res = remove_node(&mut self.root, NodeKind::Root);
-            if res.is_some() { self.decrement_size(); }
-            return res;
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.size == 0 {
+            return None;
         }
 
-        let mut parent = &mut self.root;
+        let node = self.front?;
+        self.front = successor(node);
+        self.size -= 1;
+        Some(entry(node))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
+}
 
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.size == 0 {
+            return None;
+        }
+
+        let node = self.back?;
+        self.back = predecessor(node);
+        self.size -= 1;
+        Some(entry(node))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.size == 0 {
+            return None;
+        }
+
+        let node = self.front?;
+        self.front = successor(node);
+        self.size -= 1;
+        unsafe { Some((&(*node.as_ptr()).key, &mut (*node.as_ptr()).value)) }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for IterMut<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.size == 0 {
+            return None;
+        }
+
+        let node = self.back?;
+        self.back = predecessor(node);
+        self.size -= 1;
+        unsafe { Some((&(*node.as_ptr()).key, &mut (*node.as_ptr()).value)) }
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for IterMut<'a, K, V> {}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Keys<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(key, _)| key)
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Keys<'a, K, V> {}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Values<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, value)| value)
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Values<'a, K, V> {}
+
+// Unlinks the minimum or the maximum, neither has a child on the outer side.
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.tree.size == 0 {
+            return None;
+        }
+
+        let node = self.front?;
+        self.front = right(node).map(leftmost).or_else(|| parent(node));
+        unsafe { Some(self.tree.unlink(node)) }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.tree.size, Some(self.tree.size))
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.tree.size == 0 {
+            return None;
+        }
+
+        let node = self.back?;
+        self.back = left(node).map(rightmost).or_else(|| parent(node));
+        unsafe { Some(self.tree.unlink(node)) }
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.front?;
+        if self.back == Some(node) {
+            self.front = None;
+            self.back = None;
+        } else {
+            self.front = successor(node);
+        }
+        Some(entry(node))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Range<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let node = self.back?;
+        if self.front == Some(node) {
+            self.front = None;
+            self.back = None;
+        } else {
+            self.back = predecessor(node);
+        }
+        Some(entry(node))
+    }
+}
+
+impl<'a, K, V> Iterator for PreOrder<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (node, depth) = self.next?;
+        self.next = pre_order_step(node, depth, usize::MAX);
+        self.size -= 1;
+        Some(entry(node))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for PreOrder<'a, K, V> {}
+
+impl<'a, K, V> Iterator for PostOrder<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next?;
+        self.next = post_order_step(node);
+        self.size -= 1;
+        Some(entry(node))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for PostOrder<'a, K, V> {}
+
+impl<'a, K, V> Iterator for LevelOrder<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.size == 0 {
+            return None;
+        }
 
         loop {
-            match parent {
-                Some(rc) => {
-                    let parent_node = get_mut(rc);
-
-                    status = match key.cmp(&parent_node.key) {
-                        Ordering::Equal => unreachable!(),
-                        Ordering::Greater => resolve_status(&parent_node.right, key, FoundRight, SearchingRight),
-                        Ordering::Less => resolve_status(&parent_node.left, key, FoundLeft, SearchingLeft),
-                    }?;
-
-
-                    parent = match status {
-                        FoundRoot | Searching => unreachable!(),
-                        SearchingLeft => &mut parent_node.left,
-                        SearchingRight => &mut parent_node.right,
-
-                        FoundLeft => {
-                            let res = remove_node(&mut parent_node.left, NodeKind::Left);
-                            if res.is_some() { self.decrement_size(); }
-                            return res;
-                        }
-
-                        FoundRight => {
-                            let res = remove_node(&mut parent_node.right, NodeKind::Right);
-                            if res.is_some() { self.decrement_size(); }
-                            return res;
-                        }
+            let candidate = match self.current {
+                Some((node, depth)) => pre_order_step(node, depth, self.level),
+                None => self.root.map(|root| (root, 0)),
+            };
+
+            match candidate {
+                Some((node, depth)) => {
+                    self.current = candidate;
+                    if depth == self.level {
+                        self.size -= 1;
+                        return Some(entry(node));
                     }
                 }
-                None => unreachable!()
+                None => {
+                    self.current = None;
+                    self.level += 1;
+                }
             }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for LevelOrder<'a, K, V> {}
+
+impl<K, V> IntoIterator for BinarySearchTree<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            front: self.root.map(leftmost),
+            back: self.root.map(rightmost),
+            tree: self,
+        }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a BinarySearchTree<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
 }
 
-#[derive(Copy, Clone)]
-enum NodeKind {
-    Left,
-    Right,
-    Root,
+impl<'a, K, V> IntoIterator for &'a mut BinarySearchTree<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
 }
 
-#[derive(Copy, Clone)]
-enum RemovingStatus {
-    FoundRoot,
-    Searching,
-    FoundLeft,
-    FoundRight,
-    SearchingLeft,
-    SearchingRight,
+// Frees the leaves bottom up, climbing back through the parent links, so dropping a
+// degenerate tree needs neither recursion nor an explicit stack.
+impl<K, V> Drop for BinarySearchTree<K, V> {
+    fn drop(&mut self) {
+        let mut current = self.root.take();
+        while let Some(node) = current {
+            unsafe {
+                let node_ref = &mut *node.as_ptr();
+                if let Some(left) = node_ref.left.take() {
+                    current = Some(left);
+                } else if let Some(right) = node_ref.right.take() {
+                    current = Some(right);
+                } else {
+                    current = node_ref.parent;
+                    drop(Box::from_raw(node.as_ptr()));
+                }
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+    use std::rc::Rc;
+
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
     use super::*;
 
     fn add_number(tree: &mut BinarySearchTree<i32, String>, value: i32) {
@@ -390,10 +862,10 @@ mod tests {
         let mut expected = [17, 28, 29, 44, 54, 65, 76, 78, 80, 82, 88, 97].iter();
 
         assert_eq!(tree.remove(&32), Some("32".to_string()));
-        // tree.traverse(
-        //     &mut |key, _| assert_eq!(expected.next(), Some(key)),
-        //     TraverseDirection::LNR,
-        // );
+        for (key, _) in &tree {
+            assert_eq!(expected.next(), Some(key));
+        }
+        assert_eq!(None, expected.next());
     }
 
     #[test]
@@ -402,10 +874,10 @@ mod tests {
         let mut expected = [28, 29, 32, 44, 54, 65, 76, 78, 80, 82, 88, 97].iter();
 
         assert_eq!(tree.remove(&17), Some("17".to_string()));
-        // tree.traverse(
-        //     &mut |key, _| assert_eq!(expected.next(), Some(key)),
-        //     TraverseDirection::LNR,
-        // );
+        for (key, _) in &tree {
+            assert_eq!(expected.next(), Some(key));
+        }
+        assert_eq!(None, expected.next());
     }
 
 
@@ -415,10 +887,10 @@ mod tests {
         let mut expected = [17, 28, 29, 32, 44, 54, 76, 78, 80, 82, 88, 97].iter();
         assert_eq!(Some("65".to_string()), tree.remove(&65));
 
-        // tree.traverse(
-        //     &mut |key, _| assert_eq!(expected.next(), Some(key)),
-        //     TraverseDirection::LNR,
-        // );
+        for (key, _) in &tree {
+            assert_eq!(expected.next(), Some(key));
+        }
+        assert_eq!(None, expected.next());
     }
 
     #[test]
@@ -434,4 +906,197 @@ mod tests {
         tree.put(1, 4000);
         assert_eq!(Some(&4000), tree.get(&1));
     }
+
+    #[test]
+    fn iter() {
+        let mut tree = create_tree();
+        let keys = [17, 28, 29, 32, 44, 54, 65, 76, 78, 80, 82, 88, 97];
+        assert!(tree.keys().eq(keys.iter()));
+        assert!(tree.keys().rev().eq(keys.iter().rev()));
+        assert_eq!(13, tree.iter().len());
+
+        let mut iter = tree.iter();
+        assert_eq!(Some((&17, &"17".to_string())), iter.next());
+        assert_eq!(Some((&97, &"97".to_string())), iter.next_back());
+        assert_eq!(11, iter.len());
+        assert_eq!(11, iter.by_ref().count());
+        assert_eq!(None, iter.next_back());
+
+        for (key, value) in tree.iter_mut().rev() {
+            value.push_str(&key.to_string());
+        }
+        assert_eq!(Some(&"4444".to_string()), tree.get(&44));
+        assert!(tree.values().all(|value| value.len() == 4));
+    }
+
+    #[test]
+    fn into_iter() {
+        let keys = [17, 28, 29, 32, 44, 54, 65, 76, 78, 80, 82, 88, 97];
+        let mut iter = create_tree().into_iter();
+        assert_eq!(Some((17, "17".to_string())), iter.next());
+        assert_eq!(Some((97, "97".to_string())), iter.next_back());
+        assert_eq!(Some((28, "28".to_string())), iter.next());
+        assert_eq!(Some((88, "88".to_string())), iter.next_back());
+        assert!(iter.map(|(key, _)| key).eq(keys[2..11].iter().copied()));
+
+        let mut iter = create_tree().into_iter();
+        iter.next_back();
+        iter.next();
+        drop(iter);
+
+        let mut tree = create_tree();
+        tree.put(10, "10".to_string());
+        assert_eq!(keys.len() + 1, tree.into_iter().rev().count());
+    }
+
+    #[test]
+    fn traversals() {
+        let tree = create_tree();
+        let keys = |iter: &mut dyn Iterator<Item=(&i32, &String)>| iter.map(|(key, _)| *key).collect::<Vec<_>>();
+
+        assert_eq!(vec![44, 17, 32, 28, 29, 88, 65, 54, 82, 76, 80, 78, 97], keys(&mut tree.pre_order()));
+        assert_eq!(vec![29, 28, 32, 17, 54, 78, 80, 76, 82, 65, 97, 88, 44], keys(&mut tree.post_order()));
+        assert_eq!(vec![44, 17, 88, 32, 65, 97, 28, 54, 82, 29, 76, 80, 78], keys(&mut tree.level_order()));
+
+        let empty = BinarySearchTree::<i32, String>::new();
+        assert_eq!(None, empty.pre_order().next());
+        assert_eq!(None, empty.post_order().next());
+        assert_eq!(None, empty.level_order().next());
+        assert_eq!(None, empty.iter().next_back());
+    }
+
+    #[test]
+    fn navigable() {
+        let mut tree = create_tree();
+        let key = |entry: Option<(&i32, &String)>| entry.map(|(key, _)| *key);
+
+        assert_eq!(Some(54), key(tree.floor(&54)));
+        assert_eq!(Some(32), key(tree.floor(&40)));
+        assert_eq!(None, key(tree.floor(&10)));
+        assert_eq!(Some(54), key(tree.ceiling(&54)));
+        assert_eq!(Some(65), key(tree.ceiling(&55)));
+        assert_eq!(None, key(tree.ceiling(&98)));
+        assert_eq!(Some(44), key(tree.lower(&54)));
+        assert_eq!(Some(97), key(tree.lower(&100)));
+        assert_eq!(Some(65), key(tree.higher(&54)));
+        assert_eq!(None, key(tree.higher(&97)));
+        assert_eq!(Some(17), key(tree.first_key_value()));
+        assert_eq!(Some(97), key(tree.last_key_value()));
+
+        assert_eq!(Some((17, "17".to_string())), tree.pop_first());
+        assert_eq!(Some((97, "97".to_string())), tree.pop_last());
+        assert_eq!(Some((28, "28".to_string())), tree.pop_first());
+        assert_eq!(10, tree.size());
+        assert_eq!(Some(29), key(tree.first_key_value()));
+
+        let mut empty = BinarySearchTree::<i32, String>::new();
+        assert_eq!(None, empty.pop_first());
+        assert_eq!(None, empty.pop_last());
+        assert_eq!(None, empty.floor(&1));
+    }
+
+    #[test]
+    fn range() {
+        let tree = create_tree();
+        let keys = |range: Range<'_, i32, String>| range.map(|(key, _)| *key).collect::<Vec<_>>();
+
+        assert_eq!(vec![54, 65, 76, 78], keys(tree.range(50..80)));
+        assert_eq!(vec![54, 65, 76, 78, 80], keys(tree.range(54..=80)));
+        assert_eq!(vec![17, 28], keys(tree.range(..29)));
+        assert_eq!(vec![88, 97], keys(tree.range(83..)));
+        assert_eq!(13, tree.range(..).count());
+        assert_eq!(vec![78, 76, 65, 54], tree.range(50..80).rev().map(|(key, _)| *key).collect::<Vec<_>>());
+        assert!(keys(tree.range(55..60)).is_empty());
+        assert!(keys(tree.range(100..)).is_empty());
+        assert!(keys(tree.range((Bound::Excluded(54), Bound::Excluded(65)))).is_empty());
+
+        let mut range = tree.range(30..70);
+        assert_eq!(Some(32), range.next().map(|(key, _)| *key));
+        assert_eq!(Some(65), range.next_back().map(|(key, _)| *key));
+        assert_eq!(Some(44), range.next().map(|(key, _)| *key));
+        assert_eq!(Some(54), range.next_back().map(|(key, _)| *key));
+        assert_eq!(None, range.next());
+        assert_eq!(None, range.next_back());
+    }
+
+    #[test]
+    fn navigable_against_btree_map() {
+        let mut rng = StdRng::seed_from_u64(43);
+        let mut tree = BinarySearchTree::new();
+        let mut expected = BTreeMap::new();
+
+        for _ in 0..3000 {
+            let key = rng.gen_range(0..300);
+            match rng.gen_range(0..10) {
+                0 => assert_eq!(expected.pop_first(), tree.pop_first()),
+                1 => assert_eq!(expected.pop_last(), tree.pop_last()),
+                2..=4 => assert_eq!(expected.remove(&key), tree.remove(&key)),
+                _ => {
+                    tree.put(key, key);
+                    expected.insert(key, key);
+                }
+            }
+
+            assert_eq!(expected.range(..=key).next_back(), tree.floor(&key));
+            assert_eq!(expected.range(key..).next(), tree.ceiling(&key));
+            assert_eq!(expected.range(..key).next_back(), tree.lower(&key));
+            assert_eq!(expected.range(key + 1..).next(), tree.higher(&key));
+            assert_eq!(expected.first_key_value(), tree.first_key_value());
+            assert_eq!(expected.last_key_value(), tree.last_key_value());
+
+            let end = key + rng.gen_range(0..50);
+            assert!(expected.range(key..end).eq(tree.range(key..end)));
+            assert!(expected.range(key..=end).rev().eq(tree.range(key..=end).rev()));
+        }
+    }
+
+    #[test]
+    fn debug() {
+        let mut tree = BinarySearchTree::new();
+        tree.put(2, "two");
+        tree.put(1, "one");
+        tree.put(3, "three");
+        assert_eq!(r#"{1: "one", 2: "two", 3: "three"}"#, format!("{:?}", tree));
+    }
+
+    #[test]
+    fn drops_values() {
+        let value = Rc::new(());
+        let mut tree = BinarySearchTree::new();
+        for key in [44, 17, 88, 32, 65, 97, 28, 54].iter() {
+            tree.put(*key, Rc::clone(&value));
+        }
+        tree.put(44, Rc::clone(&value));
+        assert_eq!(9, Rc::strong_count(&value));
+
+        drop(tree.remove(&44));
+        drop(tree.remove(&28));
+        assert_eq!(7, Rc::strong_count(&value));
+
+        drop(tree);
+        assert_eq!(1, Rc::strong_count(&value));
+    }
+
+    #[test]
+    fn drops_keys() {
+        let key = Rc::new(());
+        let mut tree = BinarySearchTree::new();
+        for i in 0..10 {
+            tree.put((i, Rc::clone(&key)), i);
+        }
+        assert_eq!(Some(5), tree.remove(&(5, Rc::clone(&key))));
+        assert_eq!(10, Rc::strong_count(&key));
+        drop(tree);
+        assert_eq!(1, Rc::strong_count(&key));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn drop_degenerate() {
+        let mut tree = BinarySearchTree::new();
+        for i in 0..10_000 {
+            tree.put(i, i);
+        }
+        assert_eq!(10_000, tree.size());
+    }
 }
@@ -0,0 +1,397 @@
+use std::fmt::{self, Debug, Formatter};
+use std::iter::FromIterator;
+use std::mem;
+use std::ops::Range;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::structures::types::*;
+
+type Link<T> = Option<Box<Node<T>>>;
+
+struct Node<T> {
+    value: T,
+    priority: u64,
+    size: usize,
+    // The children of every node in this subtree still have to be swapped.
+    reversed: bool,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+// Treap keyed by position: the key of a node is the size of everything before it, so it is
+// never stored and splitting by index keeps the order of the sequence. Reversal is a lazy
+// flag pushed down to the children whenever a node is restructured.
+pub struct ImplicitTreap<T> {
+    root: Link<T>,
+    rng: StdRng,
+}
+
+pub struct Iter<'a, T> {
+    stack: Vec<(&'a Node<T>, bool)>,
+    size: usize,
+}
+
+impl<T> Node<T> {
+    fn update(&mut self) {
+        self.size = 1 + size(&self.left) + size(&self.right);
+    }
+
+    fn push_down(&mut self) {
+        if self.reversed {
+            mem::swap(&mut self.left, &mut self.right);
+            for child in self.left.iter_mut().chain(self.right.iter_mut()) {
+                child.reversed = !child.reversed;
+            }
+            self.reversed = false;
+        }
+    }
+
+    // Children in sequence order, given whether an odd number of reversals is pending
+    // above and at this node.
+    fn children(&self, flipped: bool) -> (&Link<T>, &Link<T>) {
+        if flipped {
+            (&self.right, &self.left)
+        } else {
+            (&self.left, &self.right)
+        }
+    }
+}
+
+fn size<T>(link: &Link<T>) -> usize {
+    link.as_ref().map_or(0, |node| node.size)
+}
+
+// Splits into the first `index` values and the rest.
+fn split_at<T>(link: Link<T>, index: usize) -> (Link<T>, Link<T>) {
+    match link {
+        None => (None, None),
+        Some(mut node) => {
+            node.push_down();
+            let left_size = size(&node.left);
+            if index <= left_size {
+                let (left, right) = split_at(node.left.take(), index);
+                node.left = right;
+                node.update();
+                (left, Some(node))
+            } else {
+                let (left, right) = split_at(node.right.take(), index - left_size - 1);
+                node.right = left;
+                node.update();
+                (Some(node), right)
+            }
+        }
+    }
+}
+
+fn merge<T>(left: Link<T>, right: Link<T>) -> Link<T> {
+    match (left, right) {
+        (None, right) => right,
+        (left, None) => left,
+        (Some(mut left), Some(mut right)) => {
+            if left.priority > right.priority {
+                left.push_down();
+                left.right = merge(left.right.take(), Some(right));
+                left.update();
+                Some(left)
+            } else {
+                right.push_down();
+                right.left = merge(Some(left), right.left.take());
+                right.update();
+                Some(right)
+            }
+        }
+    }
+}
+
+impl<T> ImplicitTreap<T> {
+    pub fn new() -> Self {
+        ImplicitTreap::with_seed(StdRng::from_entropy().gen())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        ImplicitTreap {
+            root: None,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn push(&mut self, value: T) {
+        let node = self.new_link(value);
+        self.root = merge(self.root.take(), node);
+    }
+
+    // Reverses the values in `range` in O(log n) expected time.
+    pub fn reverse(&mut self, range: Range<usize>) -> Result<(), SeqError> {
+        let size = self.size();
+        if range.end > size {
+            return Err(SeqError::OutOfBound { index: range.end, size });
+        }
+        if range.start >= range.end {
+            return Ok(());
+        }
+
+        let (rest, right) = split_at(self.root.take(), range.end);
+        let (left, mut middle) = split_at(rest, range.start);
+        if let Some(middle) = middle.as_mut() {
+            middle.reversed = !middle.reversed;
+        }
+        self.root = merge(merge(left, middle), right);
+        Ok(())
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        let mut iter = Iter {
+            stack: Vec::new(),
+            size: self.size(),
+        };
+        iter.push_left(&self.root, false);
+        iter
+    }
+
+    fn new_link(&mut self, value: T) -> Link<T> {
+        Some(Box::new(Node {
+            value,
+            priority: self.rng.gen(),
+            size: 1,
+            reversed: false,
+            left: None,
+            right: None,
+        }))
+    }
+}
+
+impl<T> ReadonlySeq<T> for ImplicitTreap<T> {
+    fn get(&self, mut index: usize) -> Option<&T> {
+        let mut current = &self.root;
+        let mut flipped = false;
+        while let Some(node) = current {
+            flipped ^= node.reversed;
+            let (left, right) = node.children(flipped);
+            let left_size = size(left);
+            if index < left_size {
+                current = left;
+            } else if index == left_size {
+                return Some(&node.value);
+            } else {
+                index -= left_size + 1;
+                current = right;
+            }
+        }
+        None
+    }
+
+    fn index_of(&self, value: &T) -> Option<usize> where T: Eq {
+        self.iter().position(|current| current.eq(value))
+    }
+
+    fn size(&self) -> usize {
+        size(&self.root)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+}
+
+impl<T> Seq<T> for ImplicitTreap<T> {
+    fn insert(&mut self, index: usize, value: T) -> Result<(), SeqError> {
+        let size = self.size();
+        if index > size {
+            return Err(SeqError::OutOfBound { index, size });
+        }
+
+        let node = self.new_link(value);
+        let (left, right) = split_at(self.root.take(), index);
+        self.root = merge(merge(left, node), right);
+        Ok(())
+    }
+
+    fn remove_at(&mut self, index: usize) -> Option<T> {
+        if index >= self.size() {
+            return None;
+        }
+
+        let (left, rest) = split_at(self.root.take(), index);
+        let (middle, right) = split_at(rest, 1);
+        self.root = merge(left, right);
+        middle.map(|node| node.value)
+    }
+
+    fn get_mut(&mut self, mut index: usize) -> Option<&mut T> {
+        let mut current = &mut self.root;
+        while let Some(node) = current {
+            node.push_down();
+            let left_size = size(&node.left);
+            if index < left_size {
+                current = &mut node.left;
+            } else if index == left_size {
+                return Some(&mut node.value);
+            } else {
+                index -= left_size + 1;
+                current = &mut node.right;
+            }
+        }
+        None
+    }
+}
+
+impl<T> Debug for ImplicitTreap<T> where T: Debug {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Drop for ImplicitTreap<T> {
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<Node<T>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+impl<T> Extend<T> for ImplicitTreap<T> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for ImplicitTreap<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut treap = ImplicitTreap::new();
+        treap.extend(iter);
+        treap
+    }
+}
+
+impl<'a, T> Iter<'a, T> {
+    fn push_left(&mut self, mut link: &'a Link<T>, mut flipped: bool) {
+        while let Some(node) = link {
+            flipped ^= node.reversed;
+            self.stack.push((node, flipped));
+            link = node.children(flipped).0;
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (node, flipped) = self.stack.pop()?;
+        self.push_left(node.children(flipped).1, flipped);
+        self.size -= 1;
+        Some(&node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T> IntoIterator for &'a ImplicitTreap<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(treap: &ImplicitTreap<i32>) -> Vec<i32> {
+        treap.iter().copied().collect()
+    }
+
+    #[test]
+    fn create_new() {
+        let mut treap = ImplicitTreap::<i32>::new();
+        assert!(treap.is_empty());
+        assert_eq!(None, treap.get(0));
+        assert_eq!(None, treap.remove_at(0));
+        assert_eq!(Ok(()), treap.reverse(0..0));
+    }
+
+    #[test]
+    fn seq() {
+        let mut treap: ImplicitTreap<i32> = (0..5).collect();
+        treap.insert(5, 5).unwrap();
+        treap.insert(0, -1).unwrap();
+        treap.insert(3, 10).unwrap();
+        assert_eq!(Err(SeqError::OutOfBound { index: 9, size: 8 }), treap.insert(9, 0));
+        assert_eq!(vec![-1, 0, 1, 10, 2, 3, 4, 5], values(&treap));
+        assert_eq!(Some(3), treap.index_of(&10));
+        assert_eq!(Some(&10), treap.get(3));
+
+        *treap.get_mut(0).unwrap() = 20;
+        assert_eq!(Some(10), treap.remove_at(3));
+        assert_eq!(None, treap.remove_at(7));
+        assert_eq!(Err(SeqError::OutOfBound { index: 7, size: 7 }), treap.try_remove_at(7));
+        assert_eq!(vec![20, 0, 1, 2, 3, 4, 5], values(&treap));
+        assert_eq!("[20, 0, 1, 2, 3, 4, 5]", format!("{:?}", treap));
+    }
+
+    #[test]
+    fn reverse() {
+        let mut treap: ImplicitTreap<i32> = (0..10).collect();
+        treap.reverse(2..7).unwrap();
+        assert_eq!(vec![0, 1, 6, 5, 4, 3, 2, 7, 8, 9], values(&treap));
+        assert_eq!(Some(&5), treap.get(3));
+
+        treap.reverse(0..10).unwrap();
+        assert_eq!(vec![9, 8, 7, 2, 3, 4, 5, 6, 1, 0], values(&treap));
+        treap.reverse(3..8).unwrap();
+        assert_eq!(vec![9, 8, 7, 6, 5, 4, 3, 2, 1, 0], values(&treap));
+        assert_eq!(Some(0), treap.remove_at(9));
+        assert_eq!(Err(SeqError::OutOfBound { index: 10, size: 9 }), treap.reverse(5..10));
+    }
+
+    #[test]
+    fn against_vec() {
+        let mut rng = StdRng::seed_from_u64(46);
+        let mut treap = ImplicitTreap::with_seed(46);
+        let mut expected = Vec::new();
+
+        for value in 0..5000 {
+            let size = expected.len();
+            match rng.gen_range(0..10) {
+                0..=4 => {
+                    let index = rng.gen_range(0..=size);
+                    treap.insert(index, value).unwrap();
+                    expected.insert(index, value);
+                }
+                5..=6 if size > 0 => {
+                    let index = rng.gen_range(0..size);
+                    assert_eq!(Some(expected.remove(index)), treap.remove_at(index));
+                }
+                7..=8 => {
+                    let start = rng.gen_range(0..=size);
+                    let end = rng.gen_range(start..=size);
+                    treap.reverse(start..end).unwrap();
+                    expected[start..end].reverse();
+                }
+                _ if size > 0 => {
+                    let index = rng.gen_range(0..size);
+                    assert_eq!(expected.get(index), treap.get(index));
+                    *treap.get_mut(index).unwrap() += 1;
+                    expected[index] += 1;
+                }
+                _ => {}
+            }
+            assert_eq!(expected.len(), treap.size());
+        }
+
+        assert!(treap.iter().eq(expected.iter()));
+        assert!((0..expected.len()).all(|index| treap.get(index) == expected.get(index)));
+    }
+}
@@ -0,0 +1,724 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Formatter};
+use std::mem;
+use std::ops::{Bound, RangeBounds};
+use std::ptr;
+
+use crate::structures::types::{Map, NavigableMap, ReadonlyMap};
+
+type Link<K, V> = Option<Box<Node<K, V>>>;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Color {
+    Red,
+    Black,
+}
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    color: Color,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+// Sedgewick's left-leaning red-black tree, a 2-3 tree where the middle key of a 3-node
+// hangs off a red left link. Keeping red links on the left halves the number of cases,
+// at the price of more rotations than the classic tree: the invariant is restored on the
+// way back up every insertion and removal path.
+pub struct LeftLeaningRedBlackTree<K, V> {
+    root: Link<K, V>,
+    size: usize,
+    rotations: usize,
+}
+
+pub struct Iter<'a, K, V> {
+    range: Range<'a, K, V>,
+    size: usize,
+}
+
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+pub struct Values<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+// Nodes are not linked to their parents, so each end keeps the path it still has to visit,
+// with its next entry on top. Both ends stay inside the range, the iterator is exhausted
+// once they meet.
+pub struct Range<'a, K, V> {
+    front: Vec<&'a Node<K, V>>,
+    back: Vec<&'a Node<K, V>>,
+}
+
+impl Color {
+    fn flip(self) -> Color {
+        match self {
+            Color::Red => Color::Black,
+            Color::Black => Color::Red,
+        }
+    }
+}
+
+fn is_red<K, V>(link: &Link<K, V>) -> bool {
+    link.as_ref().is_some_and(|node| node.color == Color::Red)
+}
+
+fn rotate_left<K, V>(mut node: Box<Node<K, V>>, rotations: &mut usize) -> Box<Node<K, V>> {
+    let mut right = node.right.take().unwrap();
+    node.right = right.left.take();
+    right.color = node.color;
+    node.color = Color::Red;
+    right.left = Some(node);
+    *rotations += 1;
+    right
+}
+
+fn rotate_right<K, V>(mut node: Box<Node<K, V>>, rotations: &mut usize) -> Box<Node<K, V>> {
+    let mut left = node.left.take().unwrap();
+    node.left = left.right.take();
+    left.color = node.color;
+    node.color = Color::Red;
+    left.right = Some(node);
+    *rotations += 1;
+    left
+}
+
+// Splits a temporary 4-node, or merges the node with its children when going down.
+fn flip_colors<K, V>(node: &mut Node<K, V>) {
+    node.color = node.color.flip();
+    for child in node.left.iter_mut().chain(node.right.iter_mut()) {
+        child.color = child.color.flip();
+    }
+}
+
+fn fix_up<K, V>(mut node: Box<Node<K, V>>, rotations: &mut usize) -> Box<Node<K, V>> {
+    if is_red(&node.right) && !is_red(&node.left) {
+        node = rotate_left(node, rotations);
+    }
+    if is_red(&node.left) && is_red(&node.left.as_ref().unwrap().left) {
+        node = rotate_right(node, rotations);
+    }
+    if is_red(&node.left) && is_red(&node.right) {
+        flip_colors(&mut node);
+    }
+    node
+}
+
+// Makes the left child or one of its children red before descending to the left.
+fn move_red_left<K, V>(mut node: Box<Node<K, V>>, rotations: &mut usize) -> Box<Node<K, V>> {
+    flip_colors(&mut node);
+    if is_red(&node.right.as_ref().unwrap().left) {
+        node.right = node.right.take().map(|right| rotate_right(right, rotations));
+        node = rotate_left(node, rotations);
+        flip_colors(&mut node);
+    }
+    node
+}
+
+// Makes the right child or one of its children red before descending to the right.
+fn move_red_right<K, V>(mut node: Box<Node<K, V>>, rotations: &mut usize) -> Box<Node<K, V>> {
+    flip_colors(&mut node);
+    if is_red(&node.left.as_ref().unwrap().left) {
+        node = rotate_right(node, rotations);
+        flip_colors(&mut node);
+    }
+    node
+}
+
+fn insert<K, V>(link: Link<K, V>, key: K, value: V, rotations: &mut usize) -> (Box<Node<K, V>>, Option<V>) where K: Ord {
+    let mut node = match link {
+        Some(node) => node,
+        None => return (Box::new(Node { key, value, color: Color::Red, left: None, right: None }), None),
+    };
+
+    let replaced = match key.cmp(&node.key) {
+        Ordering::Equal => Some(mem::replace(&mut node.value, value)),
+        Ordering::Less => {
+            let (left, replaced) = insert(node.left.take(), key, value, rotations);
+            node.left = Some(left);
+            replaced
+        }
+        Ordering::Greater => {
+            let (right, replaced) = insert(node.right.take(), key, value, rotations);
+            node.right = Some(right);
+            replaced
+        }
+    };
+    (fix_up(node, rotations), replaced)
+}
+
+fn entry<K, V>(node: &Node<K, V>) -> (&K, &V) {
+    (&node.key, &node.value)
+}
+
+fn above<K>(bound: Bound<&K>, key: &K) -> bool where K: Ord {
+    match bound {
+        Bound::Included(bound) => key >= bound,
+        Bound::Excluded(bound) => key > bound,
+        Bound::Unbounded => true,
+    }
+}
+
+fn below<K>(bound: Bound<&K>, key: &K) -> bool where K: Ord {
+    match bound {
+        Bound::Included(bound) => key <= bound,
+        Bound::Excluded(bound) => key < bound,
+        Bound::Unbounded => true,
+    }
+}
+
+fn remove_min<K, V>(mut node: Box<Node<K, V>>, rotations: &mut usize) -> (Link<K, V>, Box<Node<K, V>>) {
+    if node.left.is_none() {
+        return (None, node);
+    }
+    if !is_red(&node.left) && !is_red(&node.left.as_ref().unwrap().left) {
+        node = move_red_left(node, rotations);
+    }
+    let (left, min) = remove_min(node.left.take().unwrap(), rotations);
+    node.left = left;
+    (Some(fix_up(node, rotations)), min)
+}
+
+fn remove_max<K, V>(mut node: Box<Node<K, V>>, rotations: &mut usize) -> (Link<K, V>, Box<Node<K, V>>) {
+    if is_red(&node.left) {
+        node = rotate_right(node, rotations);
+    }
+    if node.right.is_none() {
+        return (None, node);
+    }
+    if !is_red(&node.right) && !is_red(&node.right.as_ref().unwrap().left) {
+        node = move_red_right(node, rotations);
+    }
+    let (right, max) = remove_max(node.right.take().unwrap(), rotations);
+    node.right = right;
+    (Some(fix_up(node, rotations)), max)
+}
+
+// The key is known to be present, every step keeps the current node or its left child red
+// so the removed node is never a 2-node.
+fn remove<K, V>(mut node: Box<Node<K, V>>, key: &K, rotations: &mut usize) -> (Link<K, V>, V) where K: Ord {
+    let removed;
+    if *key < node.key {
+        if !is_red(&node.left) && !is_red(&node.left.as_ref().unwrap().left) {
+            node = move_red_left(node, rotations);
+        }
+        let (left, value) = remove(node.left.take().unwrap(), key, rotations);
+        node.left = left;
+        removed = value;
+    } else {
+        if is_red(&node.left) {
+            node = rotate_right(node, rotations);
+        }
+        if *key == node.key && node.right.is_none() {
+            return (None, node.value);
+        }
+        if !is_red(&node.right) && !is_red(&node.right.as_ref().unwrap().left) {
+            node = move_red_right(node, rotations);
+        }
+        if *key == node.key {
+            let (right, mut min) = remove_min(node.right.take().unwrap(), rotations);
+            mem::swap(&mut node.key, &mut min.key);
+            node.right = right;
+            removed = mem::replace(&mut node.value, min.value);
+        } else {
+            let (right, value) = remove(node.right.take().unwrap(), key, rotations);
+            node.right = right;
+            removed = value;
+        }
+    }
+    (Some(fix_up(node, rotations)), removed)
+}
+
+// Returns the black height of a valid subtree whose keys lie strictly between `lower`
+// and `upper`, and counts its nodes.
+fn check<'a, K, V>(link: &'a Link<K, V>, lower: Option<&'a K>, upper: Option<&'a K>, size: &mut usize) -> Option<usize> where K: Ord {
+    let node = match link {
+        Some(node) => node,
+        None => return Some(1),
+    };
+
+    if lower.is_some_and(|lower| node.key <= *lower)
+        || upper.is_some_and(|upper| node.key >= *upper)
+        || is_red(&node.right)
+        || (is_red(link) && is_red(&node.left)) {
+        return None;
+    }
+
+    *size += 1;
+    let left_height = check(&node.left, lower, Some(&node.key), size)?;
+    let right_height = check(&node.right, Some(&node.key), upper, size)?;
+    if left_height != right_height {
+        return None;
+    }
+    Some(left_height + if is_red(link) { 0 } else { 1 })
+}
+
+impl<K, V> LeftLeaningRedBlackTree<K, V> {
+    pub fn new() -> Self {
+        LeftLeaningRedBlackTree {
+            root: None,
+            size: 0,
+            rotations: 0,
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let mut range = Range {
+            front: Vec::new(),
+            back: Vec::new(),
+        };
+        range.push_left(&self.root);
+        range.push_right(&self.root);
+        Iter { range, size: self.size }
+    }
+
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+
+    // Number of rotations performed since the tree was created.
+    pub fn rotations(&self) -> usize {
+        self.rotations
+    }
+
+    // Checks the search order, that the root is black, that red links lean left and never
+    // follow each other, that every path has the same number of black links and the size.
+    pub fn is_valid(&self) -> bool where K: Ord {
+        let mut size = 0;
+        !is_red(&self.root)
+            && check(&self.root, None, None, &mut size).is_some()
+            && size == self.size
+    }
+
+    // Path to the least node whose key lies above the lower `bound`, the node is on top.
+    fn lower_bound(&self, bound: Bound<&K>) -> Vec<&Node<K, V>> where K: Ord {
+        let mut path = Vec::new();
+        let mut current = &self.root;
+        while let Some(node) = current {
+            if above(bound, &node.key) {
+                path.push(&**node);
+                current = &node.left;
+            } else {
+                current = &node.right;
+            }
+        }
+        path
+    }
+
+    // Path to the greatest node whose key lies below the upper `bound`, the node is on top.
+    fn upper_bound(&self, bound: Bound<&K>) -> Vec<&Node<K, V>> where K: Ord {
+        let mut path = Vec::new();
+        let mut current = &self.root;
+        while let Some(node) = current {
+            if below(bound, &node.key) {
+                path.push(&**node);
+                current = &node.right;
+            } else {
+                current = &node.left;
+            }
+        }
+        path
+    }
+
+    // The root is made red when both its children are black so the removal can borrow from
+    // it, and turns black again afterwards.
+    fn remove_root<R>(&mut self, remove: impl FnOnce(Box<Node<K, V>>, &mut usize) -> (Link<K, V>, R)) -> Option<R> {
+        let mut root = self.root.take()?;
+        if !is_red(&root.left) && !is_red(&root.right) {
+            root.color = Color::Red;
+        }
+        let (root, removed) = remove(root, &mut self.rotations);
+        self.root = root.map(|mut root| {
+            root.color = Color::Black;
+            root
+        });
+        self.size -= 1;
+        Some(removed)
+    }
+}
+
+impl<K, V> ReadonlyMap<K, V> for LeftLeaningRedBlackTree<K, V> where K: Ord {
+    fn get(&self, key: &K) -> Option<&V> {
+        let mut current = &self.root;
+        while let Some(node) = current {
+            current = match key.cmp(&node.key) {
+                Ordering::Equal => return Some(&node.value),
+                Ordering::Less => &node.left,
+                Ordering::Greater => &node.right,
+            };
+        }
+        None
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl<K, V> Map<K, V> for LeftLeaningRedBlackTree<K, V> where K: Ord {
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let mut current = &mut self.root;
+        while let Some(node) = current {
+            current = match key.cmp(&node.key) {
+                Ordering::Equal => return Some(&mut node.value),
+                Ordering::Less => &mut node.left,
+                Ordering::Greater => &mut node.right,
+            };
+        }
+        None
+    }
+
+    fn put(&mut self, key: K, value: V) {
+        let (mut root, replaced) = insert(self.root.take(), key, value, &mut self.rotations);
+        root.color = Color::Black;
+        self.root = Some(root);
+        if replaced.is_none() {
+            self.size += 1;
+        }
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        self.get(key)?;
+        self.remove_root(|root, rotations| remove(root, key, rotations))
+    }
+}
+
+impl<K, V> NavigableMap<K, V> for LeftLeaningRedBlackTree<K, V> where K: Ord {
+    type Range<'a> = Range<'a, K, V> where K: 'a, V: 'a;
+
+    fn floor(&self, key: &K) -> Option<(&K, &V)> {
+        self.upper_bound(Bound::Included(key)).pop().map(entry)
+    }
+
+    fn ceiling(&self, key: &K) -> Option<(&K, &V)> {
+        self.lower_bound(Bound::Included(key)).pop().map(entry)
+    }
+
+    fn lower(&self, key: &K) -> Option<(&K, &V)> {
+        self.upper_bound(Bound::Excluded(key)).pop().map(entry)
+    }
+
+    fn higher(&self, key: &K) -> Option<(&K, &V)> {
+        self.lower_bound(Bound::Excluded(key)).pop().map(entry)
+    }
+
+    fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.iter().next()
+    }
+
+    fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.iter().next_back()
+    }
+
+    fn pop_first(&mut self) -> Option<(K, V)> {
+        let min = self.remove_root(remove_min)?;
+        Some((min.key, min.value))
+    }
+
+    fn pop_last(&mut self) -> Option<(K, V)> {
+        let max = self.remove_root(remove_max)?;
+        Some((max.key, max.value))
+    }
+
+    fn range(&self, range: impl RangeBounds<K>) -> Self::Range<'_> {
+        let front = self.lower_bound(range.start_bound());
+        let back = self.upper_bound(range.end_bound());
+        match (front.last(), back.last()) {
+            (Some(first), Some(last)) if first.key <= last.key => Range { front, back },
+            _ => Range {
+                front: Vec::new(),
+                back: Vec::new(),
+            },
+        }
+    }
+}
+
+impl<K, V> Debug for LeftLeaningRedBlackTree<K, V> where K: Debug, V: Debug {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.range.next()?;
+        self.size -= 1;
+        Some(entry)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let entry = self.range.next_back()?;
+        self.size -= 1;
+        Some(entry)
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Keys<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(key, _)| key)
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Keys<'a, K, V> {}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Values<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, value)| value)
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Values<'a, K, V> {}
+
+impl<'a, K, V> Range<'a, K, V> {
+    fn push_left(&mut self, mut link: &'a Link<K, V>) {
+        while let Some(node) = link {
+            self.front.push(node);
+            link = &node.left;
+        }
+    }
+
+    fn push_right(&mut self, mut link: &'a Link<K, V>) {
+        while let Some(node) = link {
+            self.back.push(node);
+            link = &node.right;
+        }
+    }
+
+    // Yields the last entry once both ends point at the same node.
+    fn meet(&mut self) -> Option<(&'a K, &'a V)> {
+        match (self.front.last(), self.back.last()) {
+            (Some(front), Some(back)) if ptr::eq(*front, *back) => {
+                let node = *front;
+                self.front.clear();
+                self.back.clear();
+                Some(entry(node))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(last) = self.meet() {
+            return Some(last);
+        }
+        let node = self.front.pop()?;
+        self.push_left(&node.right);
+        Some(entry(node))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Range<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if let Some(last) = self.meet() {
+            return Some(last);
+        }
+        let node = self.back.pop()?;
+        self.push_right(&node.left);
+        Some(entry(node))
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a LeftLeaningRedBlackTree<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::helpers::compare_with_btree_map;
+
+    use super::*;
+
+    fn keys(tree: &LeftLeaningRedBlackTree<i32, i32>) -> Vec<i32> {
+        tree.iter().map(|(key, _)| *key).collect()
+    }
+
+    #[test]
+    fn create() {
+        let mut tree = LeftLeaningRedBlackTree::<i32, i32>::new();
+        assert_eq!(0, tree.size());
+        assert_eq!(None, tree.get(&1));
+        assert_eq!(None, tree.remove(&1));
+        assert!(tree.is_valid());
+    }
+
+    #[test]
+    fn leans_left() {
+        let mut tree = LeftLeaningRedBlackTree::new();
+        tree.put(1, 1);
+        tree.put(2, 2);
+        assert!(tree.is_valid());
+        assert_eq!(2, tree.root.as_ref().unwrap().key);
+        assert!(is_red(&tree.root.as_ref().unwrap().left));
+        assert_eq!(1, tree.rotations());
+
+        tree.put(3, 3);
+        assert!(tree.is_valid());
+        assert_eq!(vec![1, 2, 3], keys(&tree));
+        assert!(!is_red(&tree.root.as_ref().unwrap().left));
+    }
+
+    #[test]
+    fn put_get_remove() {
+        let mut tree = LeftLeaningRedBlackTree::new();
+        for key in 0..64 {
+            tree.put(key, key);
+            assert!(tree.is_valid());
+        }
+        tree.put(10, 100);
+        *tree.get_mut(&11).unwrap() = 110;
+        assert_eq!(Some(&100), tree.get(&10));
+        assert_eq!(64, tree.size());
+
+        for key in (0..64).step_by(3) {
+            assert_eq!(Some(key), tree.remove(&key));
+            assert!(tree.is_valid());
+        }
+        assert_eq!(None, tree.remove(&0));
+        assert_eq!(42, tree.size());
+        assert_eq!(format!("{:?}", tree.iter().collect::<BTreeMap<_, _>>()), format!("{:?}", tree));
+
+        while let Some(key) = tree.iter().next().map(|(key, _)| *key) {
+            tree.remove(&key);
+            assert!(tree.is_valid());
+        }
+        assert!(tree.root.is_none());
+    }
+
+    #[test]
+    fn double_ended() {
+        let mut tree = LeftLeaningRedBlackTree::new();
+        for key in 0..20 {
+            tree.put(key, key * 2);
+        }
+        assert!(tree.iter().rev().map(|(key, value)| (*key, *value)).eq((0..20).rev().map(|key| (key, key * 2))));
+        assert!(tree.keys().copied().eq(0..20));
+        assert!(tree.values().rev().copied().eq((0..20).rev().map(|key| key * 2)));
+
+        let mut iter = tree.iter();
+        assert_eq!(Some((&0, &0)), iter.next());
+        assert_eq!(Some((&19, &38)), iter.next_back());
+        assert_eq!(18, iter.len());
+        assert_eq!(18, iter.by_ref().rev().count());
+        assert_eq!(None, iter.next());
+    }
+
+    #[test]
+    fn range() {
+        let mut tree = LeftLeaningRedBlackTree::new();
+        for key in (0..10).map(|key| key * 10) {
+            tree.put(key, key);
+        }
+        let keys = |range: Range<'_, i32, i32>| range.map(|(key, _)| *key).collect::<Vec<_>>();
+
+        assert_eq!(vec![30, 40, 50], keys(tree.range(25..60)));
+        assert_eq!(vec![30, 40, 50, 60], keys(tree.range(30..=60)));
+        assert_eq!(vec![0, 10], keys(tree.range(..20)));
+        assert_eq!(vec![90], keys(tree.range(85..)));
+        assert_eq!(10, tree.range(..).count());
+        assert_eq!(vec![50, 40, 30], tree.range(25..60).rev().map(|(key, _)| *key).collect::<Vec<_>>());
+        assert!(keys(tree.range(41..49)).is_empty());
+        assert!(keys(tree.range((Bound::Excluded(40), Bound::Excluded(50)))).is_empty());
+
+        let mut range = tree.range(10..=40);
+        assert_eq!(Some((&10, &10)), range.next());
+        assert_eq!(Some((&40, &40)), range.next_back());
+        assert_eq!(Some((&20, &20)), range.next());
+        assert_eq!(Some((&30, &30)), range.next_back());
+        assert_eq!(None, range.next());
+        assert_eq!(None, range.next_back());
+    }
+
+    #[test]
+    fn navigable_against_btree_map() {
+        let mut rng = StdRng::seed_from_u64(45);
+        let mut tree = LeftLeaningRedBlackTree::new();
+        let mut expected = BTreeMap::new();
+
+        for _ in 0..3000 {
+            let key = rng.gen_range(0..200);
+            match rng.gen_range(0..10) {
+                0 => assert_eq!(expected.pop_first(), tree.pop_first()),
+                1 => assert_eq!(expected.pop_last(), tree.pop_last()),
+                2 | 3 => assert_eq!(expected.remove(&key), tree.remove(&key)),
+                _ => {
+                    tree.put(key, key);
+                    expected.insert(key, key);
+                }
+            }
+            assert!(tree.is_valid());
+
+            assert_eq!(expected.range(..=key).next_back(), tree.floor(&key));
+            assert_eq!(expected.range(key..).next(), tree.ceiling(&key));
+            assert_eq!(expected.range(..key).next_back(), tree.lower(&key));
+            assert_eq!(expected.range((Bound::Excluded(key), Bound::Unbounded)).next(), tree.higher(&key));
+            assert_eq!(expected.first_key_value(), tree.first_key_value());
+            assert_eq!(expected.last_key_value(), tree.last_key_value());
+
+            let (low, high) = (key.min(key / 2 + 50), key.max(key / 2 + 50));
+            assert!(tree.range(low..high).eq(expected.range(low..high)));
+            assert!(tree.range(low..=high).rev().eq(expected.range(low..=high).rev()));
+        }
+    }
+
+    #[test]
+    fn against_btree_map() {
+        let mut tree = LeftLeaningRedBlackTree::new();
+        let expected = compare_with_btree_map(&mut tree, 45, |tree, _, _, _| assert!(tree.is_valid()));
+        assert!(tree.iter().eq(expected.iter()));
+    }
+}
@@ -1,2 +1,12 @@
-mod simple_binary_tree;
+#[cfg(test)]
+mod tests;
+
+pub mod simple_binary_tree;
 mod binary_search_tree;
+mod avl_tree;
+mod red_black_tree;
+mod left_leaning_red_black_tree;
+mod treap;
+mod implicit_treap;
+mod splay_tree;
+mod b_tree;
@@ -0,0 +1,816 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::{Bound, RangeBounds};
+use std::ptr::NonNull;
+
+use crate::structures::types::{Map, NavigableMap, ReadonlyMap};
+
+type Link<K, V> = Option<NonNull<Node<K, V>>>;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Color {
+    Red,
+    Black,
+}
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    color: Color,
+    parent: Link<K, V>,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+// Classic red-black tree with parent links, missing children count as black leaves.
+// Insertion needs at most two rotations and removal at most three, the rest of the
+// rebalancing is recolouring.
+pub struct RedBlackTree<K, V> {
+    root: Link<K, V>,
+    size: usize,
+    rotations: usize,
+    marker: PhantomData<Box<Node<K, V>>>,
+}
+
+pub struct Iter<'a, K, V> {
+    front: Link<K, V>,
+    back: Link<K, V>,
+    size: usize,
+    marker: PhantomData<&'a Node<K, V>>,
+}
+
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+pub struct Values<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+// Both ends stay inside the range, the iterator is exhausted once they meet.
+pub struct Range<'a, K, V> {
+    front: Link<K, V>,
+    back: Link<K, V>,
+    marker: PhantomData<&'a Node<K, V>>,
+}
+
+unsafe impl<K: Send, V: Send> Send for RedBlackTree<K, V> {}
+
+unsafe impl<K: Sync, V: Sync> Sync for RedBlackTree<K, V> {}
+
+fn left<K, V>(node: NonNull<Node<K, V>>) -> Link<K, V> {
+    unsafe { (*node.as_ptr()).left }
+}
+
+fn right<K, V>(node: NonNull<Node<K, V>>) -> Link<K, V> {
+    unsafe { (*node.as_ptr()).right }
+}
+
+fn parent<K, V>(node: NonNull<Node<K, V>>) -> Link<K, V> {
+    unsafe { (*node.as_ptr()).parent }
+}
+
+fn is_red<K, V>(link: Link<K, V>) -> bool {
+    link.is_some_and(|node| unsafe { (*node.as_ptr()).color == Color::Red })
+}
+
+fn set_color<K, V>(node: NonNull<Node<K, V>>, color: Color) {
+    unsafe { (*node.as_ptr()).color = color }
+}
+
+fn color<K, V>(node: NonNull<Node<K, V>>) -> Color {
+    unsafe { (*node.as_ptr()).color }
+}
+
+fn leftmost<K, V>(mut node: NonNull<Node<K, V>>) -> NonNull<Node<K, V>> {
+    while let Some(left) = left(node) {
+        node = left;
+    }
+    node
+}
+
+fn rightmost<K, V>(mut node: NonNull<Node<K, V>>) -> NonNull<Node<K, V>> {
+    while let Some(right) = right(node) {
+        node = right;
+    }
+    node
+}
+
+fn successor<K, V>(node: NonNull<Node<K, V>>) -> Link<K, V> {
+    if let Some(right) = right(node) {
+        return Some(leftmost(right));
+    }
+
+    let mut child = node;
+    let mut current = parent(node);
+    while let Some(node) = current {
+        if left(node) == Some(child) {
+            break;
+        }
+        child = node;
+        current = parent(node);
+    }
+    current
+}
+
+fn predecessor<K, V>(node: NonNull<Node<K, V>>) -> Link<K, V> {
+    if let Some(left) = left(node) {
+        return Some(rightmost(left));
+    }
+
+    let mut child = node;
+    let mut current = parent(node);
+    while let Some(node) = current {
+        if right(node) == Some(child) {
+            break;
+        }
+        child = node;
+        current = parent(node);
+    }
+    current
+}
+
+fn entry<'a, K, V>(node: NonNull<Node<K, V>>) -> (&'a K, &'a V) {
+    let node = unsafe { &*node.as_ptr() };
+    (&node.key, &node.value)
+}
+
+// Returns the black height of a valid subtree whose keys lie strictly between `lower`
+// and `upper`, and counts its nodes.
+fn check<K, V>(link: Link<K, V>, parent: Link<K, V>, lower: Option<&K>, upper: Option<&K>, size: &mut usize) -> Option<usize> where K: Ord {
+    let node = match link {
+        Some(node) => node,
+        None => return Some(1),
+    };
+
+    let (key, node_parent) = unsafe { (&(*node.as_ptr()).key, (*node.as_ptr()).parent) };
+    if node_parent != parent
+        || lower.is_some_and(|lower| key <= lower)
+        || upper.is_some_and(|upper| key >= upper)
+        || (is_red(link) && (is_red(left(node)) || is_red(right(node)))) {
+        return None;
+    }
+
+    *size += 1;
+    let left_height = check(left(node), link, lower, Some(key), size)?;
+    let right_height = check(right(node), link, Some(key), upper, size)?;
+    if left_height != right_height {
+        return None;
+    }
+    Some(left_height + if is_red(link) { 0 } else { 1 })
+}
+
+impl<K, V> RedBlackTree<K, V> {
+    pub fn new() -> Self {
+        RedBlackTree {
+            root: None,
+            size: 0,
+            rotations: 0,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            front: self.root.map(leftmost),
+            back: self.root.map(rightmost),
+            size: self.size,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+
+    // Number of rotations performed since the tree was created.
+    pub fn rotations(&self) -> usize {
+        self.rotations
+    }
+
+    // Checks the search order, the parent links, that the root is black, that no red node
+    // has a red child, that every path has the same number of black nodes and the size.
+    pub fn is_valid(&self) -> bool where K: Ord {
+        let mut size = 0;
+        !is_red(self.root)
+            && check(self.root, None, None, None, &mut size).is_some()
+            && size == self.size
+    }
+
+    fn find(&self, key: &K) -> Link<K, V> where K: Ord {
+        let mut current = self.root;
+        while let Some(node) = current {
+            current = match key.cmp(unsafe { &(*node.as_ptr()).key }) {
+                Ordering::Equal => return Some(node),
+                Ordering::Less => left(node),
+                Ordering::Greater => right(node),
+            };
+        }
+        None
+    }
+
+    // Least node whose key lies above the lower `bound`.
+    fn lower_bound(&self, bound: Bound<&K>) -> Link<K, V> where K: Ord {
+        let mut candidate = None;
+        let mut current = self.root;
+        while let Some(node) = current {
+            let key = unsafe { &(*node.as_ptr()).key };
+            let inside = match bound {
+                Bound::Included(bound) => key >= bound,
+                Bound::Excluded(bound) => key > bound,
+                Bound::Unbounded => true,
+            };
+            if inside {
+                candidate = Some(node);
+                current = left(node);
+            } else {
+                current = right(node);
+            }
+        }
+        candidate
+    }
+
+    // Greatest node whose key lies below the upper `bound`.
+    fn upper_bound(&self, bound: Bound<&K>) -> Link<K, V> where K: Ord {
+        let mut candidate = None;
+        let mut current = self.root;
+        while let Some(node) = current {
+            let key = unsafe { &(*node.as_ptr()).key };
+            let inside = match bound {
+                Bound::Included(bound) => key <= bound,
+                Bound::Excluded(bound) => key < bound,
+                Bound::Unbounded => true,
+            };
+            if inside {
+                candidate = Some(node);
+                current = right(node);
+            } else {
+                current = left(node);
+            }
+        }
+        candidate
+    }
+
+    // A node with two children trades its entry with the in-order successor, so the
+    // unlinked node always has at most one child.
+    unsafe fn unlink(&mut self, mut node: NonNull<Node<K, V>>) -> (K, V) {
+        if let (Some(_), Some(right)) = (left(node), right(node)) {
+            let successor = leftmost(right);
+            let (target, source) = (&mut *node.as_ptr(), &mut *successor.as_ptr());
+            mem::swap(&mut target.key, &mut source.key);
+            mem::swap(&mut target.value, &mut source.value);
+            node = successor;
+        }
+
+        let child = left(node).or_else(|| right(node));
+        let parent = parent(node);
+        self.replace_child(node, child);
+        self.size -= 1;
+        if color(node) == Color::Black {
+            self.remove_fixup(child, parent);
+        }
+        let node = Box::from_raw(node.as_ptr());
+        (node.key, node.value)
+    }
+
+    // Puts `child` in place of `node` under the parent of `node`.
+    unsafe fn replace_child(&mut self, node: NonNull<Node<K, V>>, child: Link<K, V>) {
+        let parent = (*node.as_ptr()).parent;
+        match parent {
+            Some(parent) => {
+                let parent = &mut *parent.as_ptr();
+                if parent.left == Some(node) {
+                    parent.left = child;
+                } else {
+                    parent.right = child;
+                }
+            }
+            None => self.root = child,
+        }
+        if let Some(child) = child {
+            (*child.as_ptr()).parent = parent;
+        }
+    }
+
+    unsafe fn rotate_left(&mut self, node: NonNull<Node<K, V>>) {
+        let pivot = right(node).unwrap();
+        (*node.as_ptr()).right = left(pivot);
+        if let Some(child) = left(pivot) {
+            (*child.as_ptr()).parent = Some(node);
+        }
+        self.replace_child(node, Some(pivot));
+        (*pivot.as_ptr()).left = Some(node);
+        (*node.as_ptr()).parent = Some(pivot);
+        self.rotations += 1;
+    }
+
+    unsafe fn rotate_right(&mut self, node: NonNull<Node<K, V>>) {
+        let pivot = left(node).unwrap();
+        (*node.as_ptr()).left = right(pivot);
+        if let Some(child) = right(pivot) {
+            (*child.as_ptr()).parent = Some(node);
+        }
+        self.replace_child(node, Some(pivot));
+        (*pivot.as_ptr()).right = Some(node);
+        (*node.as_ptr()).parent = Some(pivot);
+        self.rotations += 1;
+    }
+
+    // Pushes a red-red violation up the tree by recolouring while the uncle is red and
+    // resolves it with one or two rotations otherwise.
+    unsafe fn insert_fixup(&mut self, mut node: NonNull<Node<K, V>>) {
+        while let Some(parent_node) = parent(node).filter(|parent| color(*parent) == Color::Red) {
+            let grandparent = parent(parent_node).unwrap();
+            if left(grandparent) == Some(parent_node) {
+                let uncle = right(grandparent);
+                if let Some(uncle) = uncle.filter(|uncle| color(*uncle) == Color::Red) {
+                    set_color(parent_node, Color::Black);
+                    set_color(uncle, Color::Black);
+                    set_color(grandparent, Color::Red);
+                    node = grandparent;
+                    continue;
+                }
+                if right(parent_node) == Some(node) {
+                    node = parent_node;
+                    self.rotate_left(node);
+                }
+                set_color(parent(node).unwrap(), Color::Black);
+                set_color(grandparent, Color::Red);
+                self.rotate_right(grandparent);
+            } else {
+                let uncle = left(grandparent);
+                if let Some(uncle) = uncle.filter(|uncle| color(*uncle) == Color::Red) {
+                    set_color(parent_node, Color::Black);
+                    set_color(uncle, Color::Black);
+                    set_color(grandparent, Color::Red);
+                    node = grandparent;
+                    continue;
+                }
+                if left(parent_node) == Some(node) {
+                    node = parent_node;
+                    self.rotate_right(node);
+                }
+                set_color(parent(node).unwrap(), Color::Black);
+                set_color(grandparent, Color::Red);
+                self.rotate_left(grandparent);
+            }
+        }
+        if let Some(root) = self.root {
+            set_color(root, Color::Black);
+        }
+    }
+
+    // `node` carries an extra black and may be a missing leaf, so its parent is passed
+    // along. The sibling always exists because its side has a black height of at least one.
+    unsafe fn remove_fixup(&mut self, mut node: Link<K, V>, mut parent_node: Link<K, V>) {
+        while node != self.root && !is_red(node) {
+            let parent_ref = parent_node.unwrap();
+            if left(parent_ref) == node {
+                let mut sibling = right(parent_ref).unwrap();
+                if color(sibling) == Color::Red {
+                    set_color(sibling, Color::Black);
+                    set_color(parent_ref, Color::Red);
+                    self.rotate_left(parent_ref);
+                    sibling = right(parent_ref).unwrap();
+                }
+                if !is_red(left(sibling)) && !is_red(right(sibling)) {
+                    set_color(sibling, Color::Red);
+                    node = Some(parent_ref);
+                    parent_node = parent(parent_ref);
+                    continue;
+                }
+                if !is_red(right(sibling)) {
+                    set_color(left(sibling).unwrap(), Color::Black);
+                    set_color(sibling, Color::Red);
+                    self.rotate_right(sibling);
+                    sibling = right(parent_ref).unwrap();
+                }
+                set_color(sibling, color(parent_ref));
+                set_color(parent_ref, Color::Black);
+                set_color(right(sibling).unwrap(), Color::Black);
+                self.rotate_left(parent_ref);
+            } else {
+                let mut sibling = left(parent_ref).unwrap();
+                if color(sibling) == Color::Red {
+                    set_color(sibling, Color::Black);
+                    set_color(parent_ref, Color::Red);
+                    self.rotate_right(parent_ref);
+                    sibling = left(parent_ref).unwrap();
+                }
+                if !is_red(left(sibling)) && !is_red(right(sibling)) {
+                    set_color(sibling, Color::Red);
+                    node = Some(parent_ref);
+                    parent_node = parent(parent_ref);
+                    continue;
+                }
+                if !is_red(left(sibling)) {
+                    set_color(right(sibling).unwrap(), Color::Black);
+                    set_color(sibling, Color::Red);
+                    self.rotate_left(sibling);
+                    sibling = left(parent_ref).unwrap();
+                }
+                set_color(sibling, color(parent_ref));
+                set_color(parent_ref, Color::Black);
+                set_color(left(sibling).unwrap(), Color::Black);
+                self.rotate_right(parent_ref);
+            }
+            node = self.root;
+        }
+        if let Some(node) = node {
+            set_color(node, Color::Black);
+        }
+    }
+}
+
+impl<K, V> ReadonlyMap<K, V> for RedBlackTree<K, V> where K: Ord {
+    fn get(&self, key: &K) -> Option<&V> {
+        self.find(key).map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl<K, V> Map<K, V> for RedBlackTree<K, V> where K: Ord {
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.find(key).map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    fn put(&mut self, key: K, value: V) {
+        let mut parent = None;
+        let mut current = self.root;
+        let mut goes_left = false;
+        while let Some(node) = current {
+            let node_ref = unsafe { &mut *node.as_ptr() };
+            match key.cmp(&node_ref.key) {
+                Ordering::Equal => {
+                    node_ref.value = value;
+                    return;
+                }
+                Ordering::Less => {
+                    goes_left = true;
+                    current = node_ref.left;
+                }
+                Ordering::Greater => {
+                    goes_left = false;
+                    current = node_ref.right;
+                }
+            }
+            parent = Some(node);
+        }
+
+        let node = NonNull::from(Box::leak(Box::new(Node {
+            key,
+            value,
+            color: Color::Red,
+            parent,
+            left: None,
+            right: None,
+        })));
+        match parent {
+            Some(parent) if goes_left => unsafe { (*parent.as_ptr()).left = Some(node) },
+            Some(parent) => unsafe { (*parent.as_ptr()).right = Some(node) },
+            None => self.root = Some(node),
+        }
+        self.size += 1;
+        unsafe { self.insert_fixup(node) }
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        let node = self.find(key)?;
+        unsafe { Some(self.unlink(node).1) }
+    }
+}
+
+impl<K, V> NavigableMap<K, V> for RedBlackTree<K, V> where K: Ord {
+    type Range<'a> = Range<'a, K, V> where K: 'a, V: 'a;
+
+    fn floor(&self, key: &K) -> Option<(&K, &V)> {
+        self.upper_bound(Bound::Included(key)).map(entry)
+    }
+
+    fn ceiling(&self, key: &K) -> Option<(&K, &V)> {
+        self.lower_bound(Bound::Included(key)).map(entry)
+    }
+
+    fn lower(&self, key: &K) -> Option<(&K, &V)> {
+        self.upper_bound(Bound::Excluded(key)).map(entry)
+    }
+
+    fn higher(&self, key: &K) -> Option<(&K, &V)> {
+        self.lower_bound(Bound::Excluded(key)).map(entry)
+    }
+
+    fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.root.map(leftmost).map(entry)
+    }
+
+    fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.root.map(rightmost).map(entry)
+    }
+
+    fn pop_first(&mut self) -> Option<(K, V)> {
+        let node = self.root.map(leftmost)?;
+        unsafe { Some(self.unlink(node)) }
+    }
+
+    fn pop_last(&mut self) -> Option<(K, V)> {
+        let node = self.root.map(rightmost)?;
+        unsafe { Some(self.unlink(node)) }
+    }
+
+    fn range(&self, range: impl RangeBounds<K>) -> Self::Range<'_> {
+        let front = self.lower_bound(range.start_bound());
+        let back = self.upper_bound(range.end_bound());
+        match (front, back) {
+            (Some(front), Some(back)) if entry(front).0 <= entry(back).0 => Range {
+                front: Some(front),
+                back: Some(back),
+                marker: PhantomData,
+            },
+            _ => Range {
+                front: None,
+                back: None,
+                marker: PhantomData,
+            },
+        }
+    }
+}
+
+impl<K, V> Debug for RedBlackTree<K, V> where K: Debug, V: Debug {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+// Frees the leaves bottom up, climbing back through the parent links.
+impl<K, V> Drop for RedBlackTree<K, V> {
+    fn drop(&mut self) {
+        let mut current = self.root.take();
+        while let Some(node) = current {
+            unsafe {
+                let node_ref = &mut *node.as_ptr();
+                if let Some(left) = node_ref.left.take() {
+                    current = Some(left);
+                } else if let Some(right) = node_ref.right.take() {
+                    current = Some(right);
+                } else {
+                    current = node_ref.parent;
+                    drop(Box::from_raw(node.as_ptr()));
+                }
+            }
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.size == 0 {
+            return None;
+        }
+
+        let node = self.front?;
+        self.front = successor(node);
+        self.size -= 1;
+        Some(entry(node))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.size == 0 {
+            return None;
+        }
+
+        let node = self.back?;
+        self.back = predecessor(node);
+        self.size -= 1;
+        Some(entry(node))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Keys<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(key, _)| key)
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Keys<'a, K, V> {}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Values<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, value)| value)
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Values<'a, K, V> {}
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.front?;
+        if self.back == Some(node) {
+            self.front = None;
+            self.back = None;
+        } else {
+            self.front = successor(node);
+        }
+        Some(entry(node))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Range<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let node = self.back?;
+        if self.front == Some(node) {
+            self.front = None;
+            self.back = None;
+        } else {
+            self.back = predecessor(node);
+        }
+        Some(entry(node))
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a RedBlackTree<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::helpers::compare_with_btree_map;
+
+    use super::*;
+
+    fn keys(tree: &RedBlackTree<i32, i32>) -> Vec<i32> {
+        tree.iter().map(|(key, _)| *key).collect()
+    }
+
+    #[test]
+    fn create() {
+        let tree = RedBlackTree::<i32, i32>::new();
+        assert_eq!(0, tree.size());
+        assert_eq!(None, tree.get(&1));
+        assert!(tree.is_valid());
+    }
+
+    #[test]
+    fn insert_cases() {
+        let mut tree = RedBlackTree::new();
+        // Straight line, zig-zag and a red uncle.
+        for key in [10, 20, 30, 15, 12, 5, 1].iter() {
+            tree.put(*key, *key);
+            assert!(tree.is_valid());
+        }
+        assert_eq!(vec![1, 5, 10, 12, 15, 20, 30], keys(&tree));
+        assert_eq!(4, tree.rotations());
+
+        tree.put(12, 120);
+        assert_eq!(Some(&120), tree.get(&12));
+        assert_eq!(7, tree.size());
+    }
+
+    #[test]
+    fn remove_cases() {
+        let mut tree = RedBlackTree::new();
+        for key in 0..32 {
+            tree.put(key, key);
+        }
+        for key in [0, 31, 16, 8, 24, 4, 12, 20, 28, 15].iter() {
+            assert_eq!(Some(*key), tree.remove(key));
+            assert!(tree.is_valid());
+        }
+        assert_eq!(None, tree.remove(&16));
+        assert_eq!(22, tree.size());
+
+        while let Some(key) = tree.iter().next().map(|(key, _)| *key) {
+            tree.remove(&key);
+            assert!(tree.is_valid());
+        }
+        assert_eq!(None, tree.root);
+    }
+
+    #[test]
+    fn iter() {
+        let mut tree = RedBlackTree::new();
+        for key in (0..100).rev() {
+            tree.put(key, key * 2);
+        }
+        assert!(tree.iter().map(|(key, _)| *key).eq(0..100));
+        assert!(tree.iter().rev().map(|(key, _)| *key).eq((0..100).rev()));
+        assert_eq!(100, tree.iter().len());
+        assert_eq!(format!("{:?}", tree.iter().collect::<BTreeMap<_, _>>()), format!("{:?}", tree));
+    }
+
+    #[test]
+    fn keys_and_values() {
+        let tree = (0..10).fold(RedBlackTree::new(), |mut tree, key| {
+            tree.put(key, key * 2);
+            tree
+        });
+        assert!(tree.keys().copied().eq(0..10));
+        assert!(tree.values().rev().copied().eq((0..10).rev().map(|key| key * 2)));
+        assert_eq!(10, tree.keys().len());
+    }
+
+    #[test]
+    fn range() {
+        let mut tree = RedBlackTree::new();
+        for key in (0..10).map(|key| key * 10) {
+            tree.put(key, key);
+        }
+        let keys = |range: Range<'_, i32, i32>| range.map(|(key, _)| *key).collect::<Vec<_>>();
+
+        assert_eq!(vec![30, 40, 50], keys(tree.range(25..60)));
+        assert_eq!(vec![30, 40, 50, 60], keys(tree.range(30..=60)));
+        assert_eq!(vec![0, 10], keys(tree.range(..20)));
+        assert_eq!(vec![90], keys(tree.range(85..)));
+        assert_eq!(vec![50, 40, 30], tree.range(25..60).rev().map(|(key, _)| *key).collect::<Vec<_>>());
+        assert!(keys(tree.range(41..49)).is_empty());
+        assert!(keys(tree.range((Bound::Excluded(40), Bound::Excluded(50)))).is_empty());
+
+        let mut range = tree.range(10..=40);
+        assert_eq!(Some((&10, &10)), range.next());
+        assert_eq!(Some((&40, &40)), range.next_back());
+        assert_eq!(Some((&20, &20)), range.next());
+        assert_eq!(Some((&30, &30)), range.next_back());
+        assert_eq!(None, range.next());
+        assert_eq!(None, range.next_back());
+    }
+
+    #[test]
+    fn navigable() {
+        let mut tree = RedBlackTree::new();
+        for key in (0..10).map(|key| key * 10) {
+            tree.put(key, key);
+        }
+        assert_eq!(Some((&30, &30)), tree.floor(&35));
+        assert_eq!(Some((&40, &40)), tree.ceiling(&35));
+        assert_eq!(Some((&20, &20)), tree.lower(&30));
+        assert_eq!(Some((&40, &40)), tree.higher(&30));
+        assert_eq!(None, tree.lower(&0));
+        assert_eq!(Some((&90, &90)), tree.last_key_value());
+
+        assert_eq!(Some((0, 0)), tree.pop_first());
+        assert_eq!(Some((90, 90)), tree.pop_last());
+        assert!(tree.is_valid());
+        while tree.pop_last().is_some() {
+            assert!(tree.is_valid());
+        }
+        assert_eq!(None, tree.pop_first());
+    }
+
+    #[test]
+    fn against_btree_map() {
+        let mut tree = RedBlackTree::new();
+        let expected = compare_with_btree_map(&mut tree, 45, |tree, _, _, _| assert!(tree.is_valid()));
+        assert!(tree.iter().eq(expected.iter()));
+    }
+}
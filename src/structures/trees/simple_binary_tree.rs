@@ -1,5 +1,8 @@
 use std::cmp::Ordering;
 use std::fmt::Debug;
+use std::ops::{Bound, RangeBounds};
+
+use crate::structures::types::{Map, NavigableMap, ReadonlyMap};
 
 enum Either<L, R> {
     Left(L),
@@ -24,6 +27,17 @@ pub struct BinaryTree<K, V>
         V: Debug,
 {
     root: Option<Box<Node<K, V>>>,
+    size: usize,
+}
+
+// Without parent links both ends keep the path of nodes still to be visited.
+pub struct Range<'a, K, V>
+    where
+        K: Ord + Debug,
+        V: Debug,
+{
+    front: Vec<&'a Node<K, V>>,
+    back: Vec<&'a Node<K, V>>,
 }
 
 pub enum TraverseDirection {
@@ -83,7 +97,7 @@ impl<K, V> BinaryTree<K, V>
         V: Debug,
 {
     pub fn new() -> BinaryTree<K, V> {
-        return BinaryTree { root: None };
+        return BinaryTree { root: None, size: 0 };
     }
 
     pub fn add(&mut self, key: K, value: V) {
@@ -98,9 +112,14 @@ impl<K, V> BinaryTree<K, V>
         }
 
         *node_link = Node::new_link(key, value);
+        self.size += 1;
     }
 
     pub fn get(&self, key: K) -> Option<&V> {
+        self.find(&key)
+    }
+
+    fn find(&self, key: &K) -> Option<&V> {
         let mut node_link = &self.root;
 
         while let Some(node_ptr) = node_link {
@@ -116,14 +135,35 @@ impl<K, V> BinaryTree<K, V>
         None
     }
 
+    fn find_mut(&mut self, key: &K) -> Option<&mut V> {
+        let mut node_link = &mut self.root;
+
+        while let Some(node_ptr) = node_link {
+            let node = &mut **node_ptr;
+
+            node_link = match key.cmp(&node.key) {
+                Ordering::Equal => return Some(&mut node.value),
+                Ordering::Less => &mut node.left,
+                Ordering::Greater => &mut node.right,
+            }
+        }
+
+        None
+    }
+
     pub fn remove(&mut self, key: K) -> Option<V> {
+        self.remove_key(&key)
+    }
+
+    fn remove_key(&mut self, key: &K) -> Option<V> {
         let mut node_link = &mut self.root;
 
 
         while node_link.is_some() {
             let node_box = node_link.take()?;
 
-            if (*node_box).key == key {
+            if (*node_box).key == *key {
+                self.size -= 1;
                 return BinaryTree::remove_node(node_link, node_box);
             }
 
@@ -185,6 +225,65 @@ impl<K, V> BinaryTree<K, V>
         return Some(node_box.value);
     }
 
+    // Least node whose key lies above the lower `bound`, with the path of greater nodes
+    // kept on the stack.
+    fn lower_path(&self, bound: Bound<&K>) -> Vec<&Node<K, V>> {
+        let mut path = Vec::new();
+        let mut node_link = &self.root;
+        while let Some(node) = node_link {
+            let inside = match bound {
+                Bound::Included(bound) => node.key >= *bound,
+                Bound::Excluded(bound) => node.key > *bound,
+                Bound::Unbounded => true,
+            };
+            if inside {
+                path.push(&**node);
+                node_link = &node.left;
+            } else {
+                node_link = &node.right;
+            }
+        }
+        path
+    }
+
+    // Greatest node whose key lies below the upper `bound`, with the path of lesser nodes
+    // kept on the stack.
+    fn upper_path(&self, bound: Bound<&K>) -> Vec<&Node<K, V>> {
+        let mut path = Vec::new();
+        let mut node_link = &self.root;
+        while let Some(node) = node_link {
+            let inside = match bound {
+                Bound::Included(bound) => node.key <= *bound,
+                Bound::Excluded(bound) => node.key < *bound,
+                Bound::Unbounded => true,
+            };
+            if inside {
+                path.push(&**node);
+                node_link = &node.right;
+            } else {
+                node_link = &node.left;
+            }
+        }
+        path
+    }
+
+    fn pop_edge(&mut self, left: bool) -> Option<(K, V)> {
+        let mut node_link = &mut self.root;
+        loop {
+            let next = if left { &node_link.as_ref()?.left } else { &node_link.as_ref()?.right };
+            if next.is_none() {
+                break;
+            }
+            let node = node_link.as_mut()?;
+            node_link = if left { &mut node.left } else { &mut node.right };
+        }
+
+        let mut node = node_link.take()?;
+        *node_link = if left { node.right.take() } else { node.left.take() };
+        self.size -= 1;
+        Some((node.key, node.value))
+    }
+
     pub fn traverse<F>(&self, func: &mut F, direction: TraverseDirection) where F: FnMut(&K, &V) {
         match self.root.as_ref() {
             Some(node_ref) => {
@@ -200,6 +299,134 @@ impl<K, V> BinaryTree<K, V>
     }
 }
 
+impl<K, V> ReadonlyMap<K, V> for BinaryTree<K, V>
+    where
+        K: Ord + Debug,
+        V: Debug,
+{
+    fn get(&self, key: &K) -> Option<&V> {
+        self.find(key)
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl<K, V> Map<K, V> for BinaryTree<K, V>
+    where
+        K: Ord + Debug,
+        V: Debug,
+{
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.find_mut(key)
+    }
+
+    fn put(&mut self, key: K, value: V) {
+        match self.find_mut(&key) {
+            Some(current) => *current = value,
+            None => self.add(key, value),
+        }
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        self.remove_key(key)
+    }
+}
+
+impl<K, V> NavigableMap<K, V> for BinaryTree<K, V>
+    where
+        K: Ord + Debug,
+        V: Debug,
+{
+    type Range<'a> = Range<'a, K, V> where K: 'a, V: 'a;
+
+    fn floor(&self, key: &K) -> Option<(&K, &V)> {
+        self.upper_path(Bound::Included(key)).pop().map(|node| (&node.key, &node.value))
+    }
+
+    fn ceiling(&self, key: &K) -> Option<(&K, &V)> {
+        self.lower_path(Bound::Included(key)).pop().map(|node| (&node.key, &node.value))
+    }
+
+    fn lower(&self, key: &K) -> Option<(&K, &V)> {
+        self.upper_path(Bound::Excluded(key)).pop().map(|node| (&node.key, &node.value))
+    }
+
+    fn higher(&self, key: &K) -> Option<(&K, &V)> {
+        self.lower_path(Bound::Excluded(key)).pop().map(|node| (&node.key, &node.value))
+    }
+
+    fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.lower_path(Bound::Unbounded).pop().map(|node| (&node.key, &node.value))
+    }
+
+    fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.upper_path(Bound::Unbounded).pop().map(|node| (&node.key, &node.value))
+    }
+
+    fn pop_first(&mut self) -> Option<(K, V)> {
+        self.pop_edge(true)
+    }
+
+    fn pop_last(&mut self) -> Option<(K, V)> {
+        self.pop_edge(false)
+    }
+
+    fn range(&self, range: impl RangeBounds<K>) -> Self::Range<'_> {
+        let front = self.lower_path(range.start_bound());
+        let back = self.upper_path(range.end_bound());
+        match (front.last(), back.last()) {
+            (Some(first), Some(last)) if first.key <= last.key => Range { front, back },
+            _ => Range { front: Vec::new(), back: Vec::new() },
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V>
+    where
+        K: Ord + Debug,
+        V: Debug,
+{
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.front.pop()?;
+        if self.back.last().is_some_and(|last| std::ptr::eq(*last, node)) {
+            self.front.clear();
+            self.back.clear();
+        } else {
+            let mut node_link = &node.right;
+            while let Some(next) = node_link {
+                self.front.push(next);
+                node_link = &next.left;
+            }
+        }
+        Some((&node.key, &node.value))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Range<'a, K, V>
+    where
+        K: Ord + Debug,
+        V: Debug,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let node = self.back.pop()?;
+        if self.front.last().is_some_and(|last| std::ptr::eq(*last, node)) {
+            self.front.clear();
+            self.back.clear();
+        } else {
+            let mut node_link = &node.left;
+            while let Some(next) = node_link {
+                self.back.push(next);
+                node_link = &next.right;
+            }
+        }
+        Some((&node.key, &node.value))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -278,6 +505,58 @@ mod tests {
         assert_eq!(Some(&4000), tree.get(1));
     }
 
+    #[test]
+    fn map() {
+        let mut tree = create_tree();
+        assert_eq!(13, tree.size());
+        tree.put(54, "fifty four".to_string());
+        *tree.get_mut(&17).unwrap() = "seventeen".to_string();
+        assert_eq!(13, tree.size());
+        assert_eq!(Some(&"fifty four".to_string()), ReadonlyMap::get(&tree, &54));
+        assert_eq!(Some("seventeen".to_string()), Map::remove(&mut tree, &17));
+        assert_eq!(None, Map::remove(&mut tree, &17));
+        assert_eq!(12, tree.size());
+    }
+
+    #[test]
+    fn navigable() {
+        let mut tree = create_tree();
+        let key = |entry: Option<(&i32, &String)>| entry.map(|(key, _)| *key);
+
+        assert_eq!(Some(32), key(tree.floor(&40)));
+        assert_eq!(Some(54), key(tree.ceiling(&54)));
+        assert_eq!(Some(44), key(tree.lower(&54)));
+        assert_eq!(Some(65), key(tree.higher(&54)));
+        assert_eq!(None, key(tree.higher(&97)));
+        assert_eq!(Some(17), key(tree.first_key_value()));
+        assert_eq!(Some(97), key(tree.last_key_value()));
+
+        assert_eq!(Some((17, "17".to_string())), tree.pop_first());
+        assert_eq!(Some((97, "97".to_string())), tree.pop_last());
+        assert_eq!(Some((88, "88".to_string())), tree.pop_last());
+        assert_eq!(10, tree.size());
+        assert_eq!(Some(82), key(tree.last_key_value()));
+    }
+
+    #[test]
+    fn range() {
+        let tree = create_tree();
+        let keys = |range: Range<'_, i32, String>| range.map(|(key, _)| *key).collect::<Vec<_>>();
+
+        assert_eq!(vec![54, 65, 76, 78], keys(tree.range(50..80)));
+        assert_eq!(vec![17, 28], keys(tree.range(..29)));
+        assert_eq!(13, tree.range(..).count());
+        assert!(keys(tree.range(55..60)).is_empty());
+        assert_eq!(vec![80, 78, 76, 65], tree.range(60..=80).rev().map(|(key, _)| *key).collect::<Vec<_>>());
+
+        let mut range = tree.range(30..70);
+        assert_eq!(Some(32), range.next().map(|(key, _)| *key));
+        assert_eq!(Some(65), range.next_back().map(|(key, _)| *key));
+        assert_eq!(Some(44), range.next().map(|(key, _)| *key));
+        assert_eq!(Some(54), range.next_back().map(|(key, _)| *key));
+        assert_eq!(None, range.next());
+    }
+
     #[test]
     fn traverse_inorder() {
         let tree = create_tree();
@@ -0,0 +1,498 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Formatter};
+
+use crate::helpers::{comparisons, reset_comparisons, Counted};
+use crate::structures::trees::binary_search_tree::BinarySearchTree;
+use crate::structures::types::{Map, ReadonlyMap};
+
+type Link<K, V> = Option<Box<Node<K, V>>>;
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    size: usize,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+// Every mutable access moves the accessed key to the root, so recently used keys stay
+// near the top and a sequence of m operations costs O((m + n) log n) in total even though
+// a single one may walk a path of length n. `ReadonlyMap::get` cannot restructure the tree
+// and is a plain search, `get_mut` is the splaying lookup.
+pub struct SplayTree<K, V> {
+    root: Link<K, V>,
+}
+
+pub struct Iter<'a, K, V> {
+    stack: Vec<&'a Node<K, V>>,
+    size: usize,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Costs {
+    pub binary_search_tree: usize,
+    pub splay_tree: usize,
+}
+
+impl<K, V> Node<K, V> {
+    fn new_link(key: K, value: V) -> Box<Node<K, V>> {
+        Box::new(Node {
+            key,
+            value,
+            size: 1,
+            left: None,
+            right: None,
+        })
+    }
+
+    fn update(&mut self) {
+        self.size = 1 + size(&self.left) + size(&self.right);
+    }
+}
+
+fn size<K, V>(link: &Link<K, V>) -> usize {
+    link.as_ref().map_or(0, |node| node.size)
+}
+
+// Top-down splay: walking down, the nodes passed on the way are hung off a left tree of
+// smaller keys and a right tree of greater keys, rotating first on zig-zig steps. The two
+// trees are collected in the order their nodes were linked and assembled at the end.
+// `direction` tells on which side of a key the target lies, the node that ends up at the
+// root is the target or the last node on the search path.
+fn splay<K, V, F>(mut node: Box<Node<K, V>>, direction: F) -> Box<Node<K, V>> where F: Fn(&K) -> Ordering {
+    let mut smaller: Vec<Box<Node<K, V>>> = Vec::new();
+    let mut greater: Vec<Box<Node<K, V>>> = Vec::new();
+
+    loop {
+        match direction(&node.key) {
+            Ordering::Equal => break,
+            Ordering::Less => {
+                let mut left = match node.left.take() {
+                    Some(left) => left,
+                    None => break,
+                };
+                if direction(&left.key) == Ordering::Less {
+                    node.left = left.right.take();
+                    node.update();
+                    left.right = Some(node);
+                    node = left;
+                    left = match node.left.take() {
+                        Some(left) => left,
+                        None => break,
+                    };
+                }
+                greater.push(node);
+                node = left;
+            }
+            Ordering::Greater => {
+                let mut right = match node.right.take() {
+                    Some(right) => right,
+                    None => break,
+                };
+                if direction(&right.key) == Ordering::Greater {
+                    node.right = right.left.take();
+                    node.update();
+                    right.left = Some(node);
+                    node = right;
+                    right = match node.right.take() {
+                        Some(right) => right,
+                        None => break,
+                    };
+                }
+                smaller.push(node);
+                node = right;
+            }
+        }
+    }
+
+    let mut left = node.left.take();
+    for mut parent in smaller.into_iter().rev() {
+        parent.right = left;
+        parent.update();
+        left = Some(parent);
+    }
+    let mut right = node.right.take();
+    for mut parent in greater.into_iter().rev() {
+        parent.left = right;
+        parent.update();
+        right = Some(parent);
+    }
+    node.left = left;
+    node.right = right;
+    node.update();
+    node
+}
+
+// The maximum of `left` is splayed to its root, where it has no right child to receive
+// `right`.
+fn join<K, V>(left: Link<K, V>, right: Link<K, V>) -> Link<K, V> where K: Ord {
+    let (mut left, right) = match (left, right) {
+        (Some(left), Some(right)) => (splay(left, |_| Ordering::Greater), splay(right, |_| Ordering::Less)),
+        (left, right) => return left.or(right),
+    };
+
+    assert!(left.key < right.key, "left keys must be less than right keys");
+    left.right = Some(right);
+    left.update();
+    Some(left)
+}
+
+impl<K, V> SplayTree<K, V> {
+    pub fn new() -> Self {
+        SplayTree { root: None }
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let mut iter = Iter {
+            stack: Vec::new(),
+            size: size(&self.root),
+        };
+        iter.push_left(&self.root);
+        iter
+    }
+
+    // Checks the search order and the subtree sizes without recursion, the tree may be
+    // a single path.
+    pub fn is_valid(&self) -> bool where K: Ord {
+        let mut stack: Vec<&Node<K, V>> = self.root.iter().map(|node| &**node).collect();
+        while let Some(node) = stack.pop() {
+            if node.size != 1 + size(&node.left) + size(&node.right) {
+                return false;
+            }
+            stack.extend(node.left.as_deref());
+            stack.extend(node.right.as_deref());
+        }
+
+        let mut keys = self.iter().map(|(key, _)| key);
+        let mut previous = keys.next();
+        for key in keys {
+            if previous.is_some_and(|previous| previous >= key) {
+                return false;
+            }
+            previous = Some(key);
+        }
+        true
+    }
+
+    // Moves the keys greater than or equal to `key` into the second tree.
+    pub fn split(mut self, key: &K) -> (SplayTree<K, V>, SplayTree<K, V>) where K: Ord {
+        let mut root = match self.root.take() {
+            Some(root) => splay(root, |current| key.cmp(current)),
+            None => return (SplayTree::new(), SplayTree::new()),
+        };
+
+        if root.key < *key {
+            let right = root.right.take();
+            root.update();
+            (SplayTree { root: Some(root) }, SplayTree { root: right })
+        } else {
+            let left = root.left.take();
+            root.update();
+            (SplayTree { root: left }, SplayTree { root: Some(root) })
+        }
+    }
+
+    // Every key of `left` has to be less than every key of `right`.
+    pub fn join(mut left: SplayTree<K, V>, mut right: SplayTree<K, V>) -> SplayTree<K, V> where K: Ord {
+        SplayTree { root: join(left.root.take(), right.root.take()) }
+    }
+
+    fn splay(&mut self, key: &K) -> Option<&mut Node<K, V>> where K: Ord {
+        let root = splay(self.root.take()?, |current| key.cmp(current));
+        let root = self.root.insert(root);
+        if root.key == *key {
+            Some(root)
+        } else {
+            None
+        }
+    }
+}
+
+impl<K, V> ReadonlyMap<K, V> for SplayTree<K, V> where K: Ord {
+    fn get(&self, key: &K) -> Option<&V> {
+        let mut current = &self.root;
+        while let Some(node) = current {
+            current = match key.cmp(&node.key) {
+                Ordering::Equal => return Some(&node.value),
+                Ordering::Less => &node.left,
+                Ordering::Greater => &node.right,
+            };
+        }
+        None
+    }
+
+    fn size(&self) -> usize {
+        size(&self.root)
+    }
+}
+
+impl<K, V> Map<K, V> for SplayTree<K, V> where K: Ord {
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.splay(key).map(|node| &mut node.value)
+    }
+
+    fn put(&mut self, key: K, value: V) {
+        if let Some(node) = self.splay(&key) {
+            node.value = value;
+            return;
+        }
+
+        let mut node = Node::new_link(key, value);
+        if let Some(mut root) = self.root.take() {
+            if node.key < root.key {
+                node.left = root.left.take();
+                root.update();
+                node.right = Some(root);
+            } else {
+                node.right = root.right.take();
+                root.update();
+                node.left = Some(root);
+            }
+            node.update();
+        }
+        self.root = Some(node);
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        self.splay(key)?;
+
+        let root = *self.root.take().unwrap();
+        self.root = join(root.left, root.right);
+        Some(root.value)
+    }
+}
+
+impl<K, V> Debug for SplayTree<K, V> where K: Debug, V: Debug {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+// Nodes are dropped one at a time, the tree may be a single path.
+impl<K, V> Drop for SplayTree<K, V> {
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<Node<K, V>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+    fn push_left(&mut self, mut link: &'a Link<K, V>) {
+        while let Some(node) = link {
+            self.stack.push(node);
+            link = &node.left;
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_left(&node.right);
+        self.size -= 1;
+        Some((&node.key, &node.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+impl<'a, K, V> IntoIterator for &'a SplayTree<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// Builds a `BinarySearchTree` and a `SplayTree` by inserting `initial` in the same order,
+// then looks up every key of the trace and counts the key comparisons of the lookups.
+pub fn simulate<K>(initial: &[K], trace: &[K]) -> Costs where K: Ord + Clone {
+    let mut tree = BinarySearchTree::new();
+    let mut splay_tree = SplayTree::new();
+    for key in initial {
+        tree.put(Counted(key.clone()), ());
+        splay_tree.put(Counted(key.clone()), ());
+    }
+
+    reset_comparisons();
+    for key in trace {
+        tree.get(&Counted(key.clone()));
+    }
+    let binary_search_tree = comparisons();
+
+    reset_comparisons();
+    for key in trace {
+        splay_tree.get_mut(&Counted(key.clone()));
+    }
+
+    Costs {
+        binary_search_tree,
+        splay_tree: comparisons(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+
+    use crate::helpers::compare_with_btree_map;
+
+    use super::*;
+
+    fn create_tree(keys: &[i32]) -> SplayTree<i32, String> {
+        let mut tree = SplayTree::new();
+        for key in keys {
+            tree.put(*key, key.to_string());
+            assert!(tree.is_valid());
+        }
+        tree
+    }
+
+    fn keys(tree: &SplayTree<i32, String>) -> Vec<i32> {
+        tree.iter().map(|(key, _)| *key).collect()
+    }
+
+    fn root(tree: &SplayTree<i32, String>) -> i32 {
+        tree.root.as_ref().unwrap().key
+    }
+
+    #[test]
+    fn create() {
+        let mut tree = SplayTree::<i32, String>::new();
+        assert_eq!(0, tree.size());
+        assert_eq!(None, tree.get(&1));
+        assert_eq!(None, tree.get_mut(&1));
+        assert_eq!(None, tree.remove(&1));
+        assert!(tree.is_valid());
+    }
+
+    #[test]
+    fn splays_accessed_key() {
+        let mut tree = create_tree(&[44, 17, 88, 32, 65, 97, 28, 54, 82, 29, 76, 80, 78]);
+        assert_eq!(78, root(&tree));
+
+        assert_eq!(Some(&"29".to_string()), tree.get(&29));
+        assert_eq!(78, root(&tree));
+        tree.get_mut(&29).unwrap().push('!');
+        assert_eq!(29, root(&tree));
+        assert!(tree.is_valid());
+
+        // A miss splays the last node on the search path.
+        assert_eq!(None, tree.get_mut(&55));
+        assert!(root(&tree) == 54 || root(&tree) == 65);
+        assert_eq!(13, tree.size());
+        assert_eq!(Some(&"29!".to_string()), tree.get(&29));
+    }
+
+    #[test]
+    fn put_remove() {
+        let mut tree = create_tree(&[44, 17, 88, 32, 65, 97, 28, 54, 82, 29, 76, 80, 78]);
+        tree.put(54, "fifty four".to_string());
+        assert_eq!(54, root(&tree));
+        assert_eq!(13, tree.size());
+
+        assert_eq!(Some("44".to_string()), tree.remove(&44));
+        assert_eq!(Some("17".to_string()), tree.remove(&17));
+        assert_eq!(None, tree.remove(&17));
+        assert!(tree.is_valid());
+        assert_eq!(vec![28, 29, 32, 54, 65, 76, 78, 80, 82, 88, 97], keys(&tree));
+        assert_eq!(format!("{:?}", tree.iter().collect::<BTreeMap<_, _>>()), format!("{:?}", tree));
+    }
+
+    #[test]
+    fn split_join() {
+        let tree = create_tree(&(0..100).collect::<Vec<_>>());
+        let (left, right) = tree.split(&40);
+        assert!(left.is_valid() && right.is_valid());
+        assert_eq!(40, left.size());
+        assert_eq!(60, right.size());
+        assert!(left.iter().map(|(key, _)| *key).eq(0..40));
+
+        let (middle, right) = right.split(&200);
+        assert_eq!(60, middle.size());
+        assert_eq!(0, right.size());
+
+        let mut tree = SplayTree::join(left, middle);
+        assert!(tree.is_valid());
+        assert_eq!(100, tree.size());
+        assert_eq!(Some(&mut "70".to_string()), tree.get_mut(&70));
+
+        let tree = SplayTree::join(tree, right);
+        assert_eq!(100, tree.size());
+    }
+
+    #[test]
+    #[should_panic(expected = "left keys must be less than right keys")]
+    fn join_overlapping() {
+        SplayTree::join(create_tree(&[1, 5]), create_tree(&[3, 7]));
+    }
+
+    #[test]
+    fn deep_tree() {
+        let mut tree = SplayTree::new();
+        for key in 0..100_000 {
+            tree.put(key, ());
+        }
+        assert!(tree.is_valid());
+        assert_eq!(Some(&mut ()), tree.get_mut(&0));
+        assert_eq!(100_000, tree.size());
+    }
+
+    #[test]
+    fn against_btree_map() {
+        let mut tree = SplayTree::new();
+        let expected = compare_with_btree_map(&mut tree, 47, |_, _, _, _| {});
+        assert!(tree.is_valid());
+        assert!(tree.iter().eq(expected.iter()));
+    }
+
+    #[test]
+    fn simulator() {
+        const SIZE: u32 = 1000;
+        let mut rng = StdRng::seed_from_u64(47);
+        let mut initial = (0..SIZE).collect::<Vec<_>>();
+        initial.shuffle(&mut rng);
+
+        // Roughly Zipf distributed ranks mapped to keys spread over the whole tree.
+        let mut ranks = initial.clone();
+        ranks.shuffle(&mut rng);
+        let skewed = (0..50_000)
+            .map(|_| ranks[(f64::from(SIZE) * rng.gen::<f64>().powi(6)) as usize])
+            .collect::<Vec<_>>();
+        let sequential = (0..50).flat_map(|_| 0..SIZE).collect::<Vec<_>>();
+        // A small working set that moves every thousand accesses.
+        let mut locality = Vec::new();
+        for _ in 0..50 {
+            let working_set = initial.choose_multiple(&mut rng, 10).copied().collect::<Vec<_>>();
+            locality.extend((0..1000).map(|_| working_set[rng.gen_range(0..10)]));
+        }
+        let uniform = (0..50_000).map(|_| rng.gen_range(0..SIZE)).collect::<Vec<_>>();
+
+        let skewed = simulate(&initial, &skewed);
+        let sequential = simulate(&initial, &sequential);
+        let locality = simulate(&initial, &locality);
+        let uniform = simulate(&initial, &uniform);
+        println!("\nSkewed     : {:?}", skewed);
+        println!("Sequential : {:?}", sequential);
+        println!("Locality   : {:?}", locality);
+        println!("Uniform    : {:?}", uniform);
+
+        assert!(skewed.splay_tree < skewed.binary_search_tree);
+        assert!(sequential.splay_tree < sequential.binary_search_tree);
+        assert!(locality.splay_tree < locality.binary_search_tree / 2);
+    }
+}
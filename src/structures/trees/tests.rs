@@ -0,0 +1,49 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::structures::types::Map;
+
+use super::avl_tree::AvlTree;
+use super::left_leaning_red_black_tree::LeftLeaningRedBlackTree;
+use super::red_black_tree::RedBlackTree;
+
+const SIZE: usize = 20_000;
+
+// Returns the rotations spent by the insertions and by the removals.
+fn count_rotations<M>(mut map: M, rotations: fn(&M) -> usize, inserts: &[usize], removes: &[usize]) -> (usize, usize) where M: Map<usize, usize> {
+    for key in inserts {
+        map.put(*key, *key);
+    }
+    let inserted = rotations(&map);
+
+    for key in removes {
+        assert_eq!(Some(*key), map.remove(key));
+    }
+    (inserted, rotations(&map) - inserted)
+}
+
+#[test]
+fn rotations_against_avl() {
+    let sorted = (0..SIZE).collect::<Vec<_>>();
+    let mut rng = StdRng::seed_from_u64(SIZE as u64);
+    let mut inserts = sorted.clone();
+    inserts.shuffle(&mut rng);
+    let mut removes = sorted.clone();
+    removes.shuffle(&mut rng);
+
+    for (name, inserts, removes) in [("Sorted", &sorted, &sorted), ("Shuffled", &inserts, &removes)].iter() {
+        let avl = count_rotations(AvlTree::new(), AvlTree::rotations, inserts, removes);
+        let red_black = count_rotations(RedBlackTree::new(), RedBlackTree::rotations, inserts, removes);
+        let left_leaning = count_rotations(LeftLeaningRedBlackTree::new(), LeftLeaningRedBlackTree::rotations, inserts, removes);
+
+        println!("\n{} insert/remove rotations: {}", name, SIZE);
+        println!("AVL          : {:?}", avl);
+        println!("Red-black    : {:?}", red_black);
+        println!("Left-leaning : {:?}", left_leaning);
+
+        assert!(red_black.0 <= 2 * SIZE && red_black.1 <= 3 * SIZE);
+        assert!(avl.0 <= 2 * SIZE);
+        assert!(left_leaning.0 + left_leaning.1 >= red_black.0 + red_black.1);
+    }
+}
@@ -0,0 +1,388 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Formatter};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::structures::types::{Map, ReadonlyMap};
+
+type Link<K, V> = Option<Box<Node<K, V>>>;
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    priority: u64,
+    size: usize,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+// Binary search tree on the keys and max-heap on random priorities, so its shape is the
+// one of a BST built by inserting the keys in a random order and the expected depth is
+// O(log n) whatever the real insertion order is. Every update is a split and a merge.
+pub struct Treap<K, V> {
+    root: Link<K, V>,
+    rng: StdRng,
+}
+
+pub struct Iter<'a, K, V> {
+    stack: Vec<&'a Node<K, V>>,
+    size: usize,
+}
+
+impl<K, V> Node<K, V> {
+    fn update(&mut self) {
+        self.size = 1 + size(&self.left) + size(&self.right);
+    }
+}
+
+fn size<K, V>(link: &Link<K, V>) -> usize {
+    link.as_ref().map_or(0, |node| node.size)
+}
+
+// Splits into the keys less than `key` and the rest.
+fn split<K, V>(link: Link<K, V>, key: &K) -> (Link<K, V>, Link<K, V>) where K: Ord {
+    match link {
+        None => (None, None),
+        Some(mut node) => {
+            if node.key < *key {
+                let (left, right) = split(node.right.take(), key);
+                node.right = left;
+                node.update();
+                (Some(node), right)
+            } else {
+                let (left, right) = split(node.left.take(), key);
+                node.left = right;
+                node.update();
+                (left, Some(node))
+            }
+        }
+    }
+}
+
+// Every key of `left` has to be less than every key of `right`.
+fn merge<K, V>(left: Link<K, V>, right: Link<K, V>) -> Link<K, V> {
+    match (left, right) {
+        (None, right) => right,
+        (left, None) => left,
+        (Some(mut left), Some(mut right)) => {
+            if left.priority > right.priority {
+                left.right = merge(left.right.take(), Some(right));
+                left.update();
+                Some(left)
+            } else {
+                right.left = merge(Some(left), right.left.take());
+                right.update();
+                Some(right)
+            }
+        }
+    }
+}
+
+fn remove<K, V>(link: &mut Link<K, V>, key: &K) -> Option<V> where K: Ord {
+    let node = link.as_mut()?;
+    let removed = match key.cmp(&node.key) {
+        Ordering::Less => remove(&mut node.left, key),
+        Ordering::Greater => remove(&mut node.right, key),
+        Ordering::Equal => {
+            let node = *link.take().unwrap();
+            *link = merge(node.left, node.right);
+            return Some(node.value);
+        }
+    };
+    if removed.is_some() {
+        node.size -= 1;
+    }
+    removed
+}
+
+// Checks the search order, the heap order of the priorities and the subtree sizes.
+fn check<K, V>(link: &Link<K, V>, lower: Option<&K>, upper: Option<&K>, priority: u64) -> bool where K: Ord {
+    match link {
+        None => true,
+        Some(node) => {
+            node.priority <= priority
+                && lower.is_none_or(|lower| node.key > *lower)
+                && upper.is_none_or(|upper| node.key < *upper)
+                && node.size == 1 + size(&node.left) + size(&node.right)
+                && check(&node.left, lower, Some(&node.key), node.priority)
+                && check(&node.right, Some(&node.key), upper, node.priority)
+        }
+    }
+}
+
+impl<K, V> Treap<K, V> {
+    pub fn new() -> Self {
+        Treap::with_rng(StdRng::from_entropy())
+    }
+
+    // The same seed and the same operations always build the same tree.
+    pub fn with_seed(seed: u64) -> Self {
+        Treap::with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(rng: StdRng) -> Self {
+        Treap { root: None, rng }
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let mut iter = Iter {
+            stack: Vec::new(),
+            size: size(&self.root),
+        };
+        iter.push_left(&self.root);
+        iter
+    }
+
+    pub fn height(&self) -> usize {
+        fn height<K, V>(link: &Link<K, V>) -> usize {
+            link.as_ref().map_or(0, |node| 1 + height(&node.left).max(height(&node.right)))
+        }
+        height(&self.root)
+    }
+
+    pub fn is_valid(&self) -> bool where K: Ord {
+        check(&self.root, None, None, u64::MAX)
+    }
+
+    // Moves the keys greater than or equal to `key` into the second treap in O(log n)
+    // expected time.
+    pub fn split(mut self, key: &K) -> (Treap<K, V>, Treap<K, V>) where K: Ord {
+        let (left, right) = split(self.root.take(), key);
+        let mut other = Treap::with_rng(StdRng::seed_from_u64(self.rng.gen()));
+        self.root = left;
+        other.root = right;
+        (self, other)
+    }
+
+    // Joins two treaps in O(log n) expected time, every key of `left` has to be less than
+    // every key of `right`.
+    pub fn merge(mut left: Treap<K, V>, mut right: Treap<K, V>) -> Treap<K, V> where K: Ord {
+        if let (Some((last, _)), Some((first, _))) = (left.last(), right.first()) {
+            assert!(last < first, "left keys must be less than right keys");
+        }
+        left.root = merge(left.root.take(), right.root.take());
+        left
+    }
+
+    fn first(&self) -> Option<(&K, &V)> {
+        let mut node = self.root.as_ref()?;
+        while let Some(left) = node.left.as_ref() {
+            node = left;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    fn last(&self) -> Option<(&K, &V)> {
+        let mut node = self.root.as_ref()?;
+        while let Some(right) = node.right.as_ref() {
+            node = right;
+        }
+        Some((&node.key, &node.value))
+    }
+}
+
+impl<K, V> ReadonlyMap<K, V> for Treap<K, V> where K: Ord {
+    fn get(&self, key: &K) -> Option<&V> {
+        let mut current = &self.root;
+        while let Some(node) = current {
+            current = match key.cmp(&node.key) {
+                Ordering::Equal => return Some(&node.value),
+                Ordering::Less => &node.left,
+                Ordering::Greater => &node.right,
+            };
+        }
+        None
+    }
+
+    fn size(&self) -> usize {
+        size(&self.root)
+    }
+}
+
+impl<K, V> Map<K, V> for Treap<K, V> where K: Ord {
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let mut current = &mut self.root;
+        while let Some(node) = current {
+            current = match key.cmp(&node.key) {
+                Ordering::Equal => return Some(&mut node.value),
+                Ordering::Less => &mut node.left,
+                Ordering::Greater => &mut node.right,
+            };
+        }
+        None
+    }
+
+    fn put(&mut self, key: K, value: V) {
+        if let Some(current) = self.get_mut(&key) {
+            *current = value;
+            return;
+        }
+
+        let (left, right) = split(self.root.take(), &key);
+        let node = Box::new(Node {
+            key,
+            value,
+            priority: self.rng.gen(),
+            size: 1,
+            left: None,
+            right: None,
+        });
+        self.root = merge(merge(left, Some(node)), right);
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        remove(&mut self.root, key)
+    }
+}
+
+impl<K, V> Debug for Treap<K, V> where K: Debug, V: Debug {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+// Nodes are dropped one at a time, so a pathological shape cannot overflow the stack.
+impl<K, V> Drop for Treap<K, V> {
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<Node<K, V>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+    fn push_left(&mut self, mut link: &'a Link<K, V>) {
+        while let Some(node) = link {
+            self.stack.push(node);
+            link = &node.left;
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_left(&node.right);
+        self.size -= 1;
+        Some((&node.key, &node.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+impl<'a, K, V> IntoIterator for &'a Treap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::compare_with_btree_map;
+
+    use super::*;
+
+    fn create_treap(keys: impl Iterator<Item=i32>) -> Treap<i32, i32> {
+        let mut treap = Treap::with_seed(46);
+        for key in keys {
+            treap.put(key, key * 10);
+        }
+        treap
+    }
+
+    fn keys(treap: &Treap<i32, i32>) -> Vec<i32> {
+        treap.iter().map(|(key, _)| *key).collect()
+    }
+
+    #[test]
+    fn create() {
+        let treap = Treap::<i32, i32>::new();
+        assert_eq!(0, treap.size());
+        assert_eq!(None, treap.get(&1));
+        assert!(treap.is_valid());
+    }
+
+    #[test]
+    fn put_get_remove() {
+        let mut treap = create_treap([44, 17, 88, 32, 65, 97, 28, 54, 82, 29, 76, 80, 78].iter().copied());
+        assert!(treap.is_valid());
+        assert_eq!(13, treap.size());
+        assert_eq!(Some(&540), treap.get(&54));
+
+        treap.put(54, 1);
+        *treap.get_mut(&17).unwrap() = 2;
+        assert_eq!(13, treap.size());
+        assert_eq!(Some(&1), treap.get(&54));
+        assert_eq!(Some(&2), treap.get(&17));
+
+        assert_eq!(Some(440), treap.remove(&44));
+        assert_eq!(None, treap.remove(&44));
+        assert!(treap.is_valid());
+        assert_eq!(vec![17, 28, 29, 32, 54, 65, 76, 78, 80, 82, 88, 97], keys(&treap));
+    }
+
+    #[test]
+    fn seeded() {
+        let first = create_treap(0..100);
+        let second = create_treap(0..100);
+        assert_eq!(first.height(), second.height());
+        assert!(first.height() < 30);
+    }
+
+    #[test]
+    fn split_merge() {
+        let treap = create_treap(0..100);
+        let (left, right) = treap.split(&40);
+        assert!(left.is_valid() && right.is_valid());
+        assert_eq!(40, left.size());
+        assert_eq!(60, right.size());
+        assert!(left.iter().map(|(key, _)| *key).eq(0..40));
+        assert!(right.iter().map(|(key, _)| *key).eq(40..100));
+
+        let (middle, right) = right.split(&70);
+        let mut treap = Treap::merge(left, right);
+        assert!(treap.is_valid());
+        assert_eq!(70, treap.size());
+        assert_eq!(None, treap.get(&50));
+        treap.put(50, 0);
+        assert_eq!(Some(&0), treap.get(&50));
+        assert_eq!(30, middle.size());
+
+        let (empty, all) = treap.split(&-1);
+        assert_eq!(0, empty.size());
+        assert_eq!(71, Treap::merge(empty, all).size());
+    }
+
+    #[test]
+    #[should_panic(expected = "left keys must be less than right keys")]
+    fn merge_overlapping() {
+        Treap::merge(create_treap(0..10), create_treap(5..15));
+    }
+
+    #[test]
+    fn sorted_insertion_stays_balanced() {
+        let treap = create_treap(0..100_000);
+        assert!(treap.is_valid());
+        assert!(treap.height() < 60);
+    }
+
+    #[test]
+    fn against_btree_map() {
+        let mut treap = Treap::with_seed(46);
+        let expected = compare_with_btree_map(&mut treap, 46, |_, _, _, _| {});
+        assert!(treap.is_valid());
+        assert!(treap.iter().eq(expected.iter()));
+    }
+}
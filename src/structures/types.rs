@@ -1,20 +1,86 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::ops::RangeBounds;
+
 pub trait Queue<T> {
     fn peek(&self) -> Option<&T>;
     fn enqueue(&mut self, value: T);
     fn dequeue(&mut self) -> Option<T>;
+
+    fn try_dequeue(&mut self) -> Result<T, SeqError> {
+        self.dequeue().ok_or(SeqError::Empty)
+    }
 }
 
-pub trait Stack<T> {
+pub trait ReadonlyStack<T> {
     fn peek(&self) -> Option<&T>;
+}
+
+pub trait Stack<T>: ReadonlyStack<T> {
     fn push(&mut self, value: T);
     fn pop(&mut self) -> Option<T>;
+
+    fn try_pop(&mut self) -> Result<T, SeqError> {
+        self.pop().ok_or(SeqError::Empty)
+    }
+}
+
+pub trait Deque<T> {
+    fn peek_front(&self) -> Option<&T>;
+    fn peek_back(&self) -> Option<&T>;
+    fn push_front(&mut self, value: T);
+    fn push_back(&mut self, value: T);
+    fn pop_front(&mut self) -> Option<T>;
+    fn pop_back(&mut self) -> Option<T>;
+
+    fn try_pop_front(&mut self) -> Result<T, SeqError> {
+        self.pop_front().ok_or(SeqError::Empty)
+    }
+
+    fn try_pop_back(&mut self) -> Result<T, SeqError> {
+        self.pop_back().ok_or(SeqError::Empty)
+    }
+}
+
+pub trait ConcurrentQueue<T>: Sync {
+    fn enqueue(&self, value: T);
+    fn dequeue(&self) -> Option<T>;
+    fn is_empty(&self) -> bool;
+}
+
+pub trait ConcurrentStack<T>: Sync {
+    fn push(&self, value: T);
+    fn pop(&self) -> Option<T>;
+    fn is_empty(&self) -> bool;
 }
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum SeqError {
-    OutOfBound
+    OutOfBound { index: usize, size: usize },
+    Empty,
+    CapacityOverflow,
+    AllocError { bytes: usize },
 }
 
+impl Display for SeqError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SeqError::OutOfBound { index, size } =>
+                write!(f, "index {} is out of bounds for a sequence of size {}", index, size),
+            SeqError::Empty =>
+                write!(f, "sequence is empty"),
+            SeqError::CapacityOverflow =>
+                write!(f, "requested capacity exceeds the maximum size of a sequence"),
+            SeqError::AllocError { bytes } =>
+                write!(f, "memory allocation of {} bytes failed", bytes),
+        }
+    }
+}
+
+impl Error for SeqError {}
+
 pub trait ReadonlySeq<T> {
     fn get(&self, index: usize) -> Option<&T>;
 
@@ -30,8 +96,213 @@ pub trait Seq<T>: ReadonlySeq<T> {
     fn remove_at(&mut self, index: usize) -> Option<T>;
 
     fn get_mut(&mut self, index: usize) -> Option<&mut T>;
+
+    fn try_remove_at(&mut self, index: usize) -> Result<T, SeqError> {
+        let size = self.size();
+        self.remove_at(index).ok_or(SeqError::OutOfBound { index, size })
+    }
+
+    fn try_get_mut(&mut self, index: usize) -> Result<&mut T, SeqError> {
+        let size = self.size();
+        self.get_mut(index).ok_or(SeqError::OutOfBound { index, size })
+    }
+}
+
+pub trait Compare<T> {
+    fn compare(&self, left: &T, right: &T) -> Ordering;
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct MaxOrder;
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct MinOrder;
+
+impl<T> Compare<T> for MaxOrder where T: Ord {
+    fn compare(&self, left: &T, right: &T) -> Ordering {
+        left.cmp(right)
+    }
+}
+
+impl<T> Compare<T> for MinOrder where T: Ord {
+    fn compare(&self, left: &T, right: &T) -> Ordering {
+        right.cmp(left)
+    }
+}
+
+impl<T, F> Compare<T> for F where F: Fn(&T, &T) -> Ordering {
+    fn compare(&self, left: &T, right: &T) -> Ordering {
+        self(left, right)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum HeapError {
+    InvalidHandle,
+    InvalidKey,
+}
+
+impl Display for HeapError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            HeapError::InvalidHandle =>
+                write!(f, "handle does not refer to an element of this heap"),
+            HeapError::InvalidKey =>
+                write!(f, "new key moves the element in the opposite direction of the requested change"),
+        }
+    }
+}
+
+impl Error for HeapError {}
+
+pub trait AddressableHeap<T>: Queue<T> {
+    type Handle;
+
+    fn insert(&mut self, value: T) -> Self::Handle;
+    fn get(&self, handle: &Self::Handle) -> Option<&T>;
+    fn decrease_key(&mut self, handle: &Self::Handle, value: T) -> Result<(), HeapError>;
+    fn increase_key(&mut self, handle: &Self::Handle, value: T) -> Result<(), HeapError>;
+    fn remove(&mut self, handle: &Self::Handle) -> Result<T, HeapError>;
+    fn meld(&mut self, other: Self) where Self: Sized;
+
+    fn size(&self) -> usize;
+    fn is_empty(&self) -> bool;
 }
 
+#[derive(Debug, Eq, PartialEq)]
+pub struct SendError<T>(pub T);
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum TrySendError<T> {
+    Full(T),
+    Closed(T),
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum TryRecvError {
+    Empty,
+    Closed,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum RecvTimeoutError {
+    Timeout,
+    Closed,
+}
+
+impl<T> Display for SendError<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "sending on a closed channel")
+    }
+}
+
+impl<T> Display for TrySendError<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TrySendError::Full(_) =>
+                write!(f, "sending on a full channel"),
+            TrySendError::Closed(_) =>
+                write!(f, "sending on a closed channel"),
+        }
+    }
+}
+
+impl Display for TryRecvError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TryRecvError::Empty =>
+                write!(f, "receiving on an empty channel"),
+            TryRecvError::Closed =>
+                write!(f, "receiving on a closed and empty channel"),
+        }
+    }
+}
+
+impl Display for RecvTimeoutError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RecvTimeoutError::Timeout =>
+                write!(f, "timed out waiting on an empty channel"),
+            RecvTimeoutError::Closed =>
+                write!(f, "receiving on a closed and empty channel"),
+        }
+    }
+}
+
+impl<T> Error for SendError<T> where T: fmt::Debug {}
+
+impl<T> Error for TrySendError<T> where T: fmt::Debug {}
+
+impl Error for TryRecvError {}
+
+impl Error for RecvTimeoutError {}
+
+#[derive(Debug)]
+pub enum StorageError {
+    Io(io::Error),
+    Corrupted { page: u64 },
+    InvalidRecord { offset: u64 },
+    EntryTooLarge { size: usize, limit: usize },
+}
+
+impl Display for StorageError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Io(error) =>
+                write!(f, "storage i/o failed: {}", error),
+            StorageError::Corrupted { page } =>
+                write!(f, "page {} is corrupted", page),
+            StorageError::InvalidRecord { offset } =>
+                write!(f, "record at offset {} cannot be decoded", offset),
+            StorageError::EntryTooLarge { size, limit } =>
+                write!(f, "entry of {} bytes exceeds the limit of {} bytes", size, limit),
+        }
+    }
+}
+
+impl Error for StorageError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            StorageError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for StorageError {
+    fn from(error: io::Error) -> Self {
+        StorageError::Io(error)
+    }
+}
+
+// Turns values into bytes and back, `decode` returns `None` for bytes it did not produce.
+pub trait Codec<T> {
+    fn encode(&self, value: &T, bytes: &mut Vec<u8>);
+    fn decode(&self, bytes: &[u8]) -> Option<T>;
+
+    fn encoded_size(&self, value: &T) -> usize {
+        let mut bytes = Vec::new();
+        self.encode(value, &mut bytes);
+        bytes.len()
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum MapError {
+    KeyNotFound,
+}
+
+impl Display for MapError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            MapError::KeyNotFound =>
+                write!(f, "key is not in the map"),
+        }
+    }
+}
+
+impl Error for MapError {}
+
 pub trait ReadonlyMap<K, V> {
     fn get(&self, key: &K) -> Option<&V>;
     fn size(&self) -> usize;
@@ -41,4 +312,54 @@ pub trait Map<K, V>: ReadonlyMap<K, V> {
     fn get_mut(&mut self, key: &K) -> Option<&mut V>;
     fn put(&mut self, key: K, value: V);
     fn remove(&mut self, key: &K) -> Option<V>;
+
+    fn try_get_mut(&mut self, key: &K) -> Result<&mut V, MapError> {
+        self.get_mut(key).ok_or(MapError::KeyNotFound)
+    }
+
+    fn try_remove(&mut self, key: &K) -> Result<V, MapError> {
+        self.remove(key).ok_or(MapError::KeyNotFound)
+    }
+}
+
+// Ordered map queries. `floor` and `ceiling` include an equal key, `lower` and `higher`
+// are strict.
+pub trait NavigableMap<K, V>: Map<K, V> {
+    type Range<'a>: DoubleEndedIterator<Item=(&'a K, &'a V)> where Self: 'a, K: 'a, V: 'a;
+
+    fn floor(&self, key: &K) -> Option<(&K, &V)>;
+    fn ceiling(&self, key: &K) -> Option<(&K, &V)>;
+    fn lower(&self, key: &K) -> Option<(&K, &V)>;
+    fn higher(&self, key: &K) -> Option<(&K, &V)>;
+    fn first_key_value(&self) -> Option<(&K, &V)>;
+    fn last_key_value(&self) -> Option<(&K, &V)>;
+    fn pop_first(&mut self) -> Option<(K, V)>;
+    fn pop_last(&mut self) -> Option<(K, V)>;
+    fn range(&self, range: impl RangeBounds<K>) -> Self::Range<'_>;
+}
+
+pub trait ReadonlySet<K> {
+    fn contains(&self, key: &K) -> bool;
+    fn size(&self) -> usize;
+}
+
+pub trait Set<K>: ReadonlySet<K> {
+    fn insert(&mut self, key: K) -> bool;
+    fn remove(&mut self, key: &K) -> bool;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seq_error_display() {
+        assert_eq!("index 3 is out of bounds for a sequence of size 2",
+                   SeqError::OutOfBound { index: 3, size: 2 }.to_string());
+        assert_eq!("sequence is empty", SeqError::Empty.to_string());
+        assert_eq!("requested capacity exceeds the maximum size of a sequence",
+                   SeqError::CapacityOverflow.to_string());
+        assert_eq!("memory allocation of 64 bytes failed", SeqError::AllocError { bytes: 64 }.to_string());
+        assert_eq!("key is not in the map", MapError::KeyNotFound.to_string());
+    }
 }